@@ -0,0 +1,119 @@
+use std::io::{ErrorKind, Write};
+use std::process::{Child, Command, Stdio};
+
+use anyhow::{anyhow, Result};
+
+// On Linux a selection is owned by the process that set it and disappears
+// when that process exits, which tabsel does right after confirming. Rather
+// than holding it in-process we hand the text to `wl-copy` (Wayland) or
+// `xclip` (X11), which fork and keep serving the selection after we're gone.
+
+/// Which system selection to write to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    /// The regular clipboard (Ctrl+V).
+    Clipboard,
+    /// The X11/Wayland primary selection (middle-click paste).
+    Primary,
+}
+
+/// Copy `text` to the given selection, with the tool for the running
+/// display server or, when that isn't installed, the other one.
+#[cfg(target_os = "linux")]
+pub fn copy(text: &str, selection: Selection) -> Result<()> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let (program, mut child) = match spawn(copy_command(selection, wayland))? {
+        Some(spawned) => spawned,
+        None => spawn(copy_command(selection, !wayland))?.ok_or_else(|| {
+            anyhow!("neither wl-copy (Wayland) nor xclip (X11) is installed; install one to copy")
+        })?,
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(anyhow!("{program} exited with {status}"));
+    }
+
+    Ok(())
+}
+
+/// Start `command` reading from a pipe, with the program's name. `None`
+/// when the program isn't on the `PATH`.
+#[cfg(target_os = "linux")]
+fn spawn(mut command: Command) -> Result<Option<(String, Child)>> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    match command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => Ok(Some((program, child))),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(anyhow!("failed to run {program}: {err}")),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn copy(_text: &str, selection: Selection) -> Result<()> {
+    match selection {
+        Selection::Clipboard => Err(anyhow!("clipboard output is only supported on Linux")),
+        Selection::Primary => Err(anyhow!("the primary selection only exists on Linux")),
+    }
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn copy_command(selection: Selection, wayland: bool) -> Command {
+    if wayland {
+        let mut command = Command::new("wl-copy");
+        if selection == Selection::Primary {
+            command.arg("--primary");
+        }
+        command
+    } else {
+        let mut command = Command::new("xclip");
+        command.arg("-selection").arg(match selection {
+            Selection::Clipboard => "clipboard",
+            Selection::Primary => "primary",
+        });
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn argv(command: &Command) -> Vec<String> {
+        std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn wayland_commands() {
+        assert_eq!(argv(&copy_command(Selection::Clipboard, true)), vec!["wl-copy"]);
+        assert_eq!(
+            argv(&copy_command(Selection::Primary, true)),
+            vec!["wl-copy", "--primary"]
+        );
+    }
+
+    #[test]
+    fn x11_commands() {
+        assert_eq!(
+            argv(&copy_command(Selection::Clipboard, false)),
+            vec!["xclip", "-selection", "clipboard"]
+        );
+        assert_eq!(
+            argv(&copy_command(Selection::Primary, false)),
+            vec!["xclip", "-selection", "primary"]
+        );
+    }
+}
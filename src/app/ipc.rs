@@ -0,0 +1,36 @@
+use anyhow::{anyhow, Result};
+
+/// Fail unless `fd` is an open file descriptor of this process.
+#[cfg(unix)]
+pub fn check_fd(fd: i32) -> Result<()> {
+    // SAFETY: F_GETFD only reads the descriptor flags.
+    if unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+        return Err(anyhow!("file descriptor {fd} is not open"));
+    }
+    Ok(())
+}
+
+/// Write `text` and a newline to the inherited file descriptor `fd`.
+#[cfg(unix)]
+pub fn write_fd(fd: i32, text: &str) -> Result<()> {
+    use std::fs::File;
+    use std::io::Write;
+    use std::mem::ManuallyDrop;
+    use std::os::unix::io::FromRawFd;
+
+    check_fd(fd)?;
+    // SAFETY: the descriptor is open and handed to tabsel by its caller for
+    // this purpose. It is left open so `--loop` can write to it again.
+    let mut file = ManuallyDrop::new(unsafe { File::from_raw_fd(fd) });
+    writeln!(file, "{text}").map_err(|err| anyhow!("failed to write to fd {fd}: {err}"))
+}
+
+#[cfg(not(unix))]
+pub fn check_fd(_fd: i32) -> Result<()> {
+    Err(anyhow!("--index-fd is only supported on Unix"))
+}
+
+#[cfg(not(unix))]
+pub fn write_fd(fd: i32, _text: &str) -> Result<()> {
+    check_fd(fd)
+}
@@ -0,0 +1,85 @@
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Result};
+
+/// Run the `--on-select` command for the confirmed `value`. Unless `detach`
+/// is set, wait for it and fail if it exits unsuccessfully.
+pub fn run(template: &str, value: &str, detach: bool) -> Result<()> {
+    let mut command = on_select_command(template, value)?;
+    let program = command.get_program().to_string_lossy().into_owned();
+
+    if detach {
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|err| anyhow!("failed to run {program}: {err}"))?;
+
+    if detach {
+        return Ok(());
+    }
+
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(anyhow!("{program} exited with {status}"));
+    }
+
+    Ok(())
+}
+
+/// Build the command from a template split on whitespace (no shell is
+/// involved). Every `{}` is replaced by `value`, which stays part of a single
+/// argument even if it contains spaces; without any `{}` the value is
+/// appended as the last argument.
+fn on_select_command(template: &str, value: &str) -> Result<Command> {
+    let mut words = template.split_whitespace();
+    let program = words
+        .next()
+        .ok_or_else(|| anyhow!("--on-select command is empty"))?;
+
+    let mut args: Vec<String> = words.map(|word| word.replace("{}", value)).collect();
+    if !template.contains("{}") {
+        args.push(value.to_string());
+    }
+
+    let mut command = Command::new(program.replace("{}", value));
+    command.args(args);
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn argv(command: &Command) -> Vec<String> {
+        std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn placeholder_is_replaced_within_one_argument() {
+        let command = on_select_command("xdg-open --file={} {}", "my notes.txt").unwrap();
+        assert_eq!(
+            argv(&command),
+            vec!["xdg-open", "--file=my notes.txt", "my notes.txt"]
+        );
+    }
+
+    #[test]
+    fn value_is_appended_without_placeholder() {
+        let command = on_select_command("notify-send  Picked", "Alice,30").unwrap();
+        assert_eq!(argv(&command), vec!["notify-send", "Picked", "Alice,30"]);
+    }
+
+    #[test]
+    fn empty_template_is_an_error() {
+        assert!(on_select_command("  ", "x").is_err());
+    }
+}
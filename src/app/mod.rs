@@ -1,25 +1,50 @@
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::process::exit;
+use std::time::{Duration, Instant};
 
+use iced::alignment::Horizontal;
 use iced::widget::{
-    column, container, horizontal_rule, scrollable, text, text_input, Button, Column, Container,
-    Row, TextInput,
+    column, container, horizontal_rule, mouse_area, scrollable, text, text_input, Button, Column,
+    Container, Row, TextInput,
+};
+use iced::widget::text::Shaping;
+use iced::{
+    event, window, Alignment, Application, Command, Element, Length, Padding, Settings,
+    Subscription,
 };
-use iced::{event, window, Alignment, Application, Command, Element, Length, Settings, Subscription};
 use iced_core::keyboard::key::Named;
 use iced_core::keyboard::{Key, Modifiers};
-use iced_core::widget::operation::scrollable::RelativeOffset;
+use iced_core::widget::operation::scrollable::{AbsoluteOffset, RelativeOffset};
 use iced_core::window::settings::PlatformSpecific;
-use iced_core::{Event, Font, Pixels, Size};
+use iced_core::font::Weight;
+use iced_core::{Event, Font, Pixels, Point, Size};
 use iced_style::Theme;
 use once_cell::sync::Lazy;
-use tracing::debug;
+use tracing::{debug, info, warn};
 
+use crate::app::state::ScrollViewport;
 use crate::app::style::rows::button::ButtonStyle;
+use crate::app::style::rows::cell_rule;
+use crate::app::style::rows::generic::GenericContainerStyle;
+use crate::app::style::rows::{HeaderRowStyle, RowStyles};
+use crate::app::style::{GridStyle, ScrollPolicy, TabBehavior};
+use crate::config::color::OnagreColor;
+use crate::config::padding::OnagrePadding;
+use crate::data::{
+    Aggregate, CaseFold, Decode, FilterMode, GroupAggregate, InputFormat, InputSource, ModeFormats,
+    MultiEnter, OutputFormat, OutputOptions, ParseOptions, RowSlice, SelectionMode, SortKey, Table,
+};
 use crate::data::output;
-use crate::data::{OutputFormat, SelectionMode, Table};
+use crate::data::template::Template;
 use crate::THEME;
 
+pub mod clipboard;
 pub mod entries;
+pub mod ipc;
+pub mod launch;
+pub mod mru;
 pub mod state;
 pub mod style;
 
@@ -38,35 +63,71 @@ fn add_word_break_hints(s: &str) -> String {
     out
 }
 
-pub fn run(
-    table: Table,
-    available_modes: Vec<SelectionMode>,
-    filter_enabled: bool,
-    output_format: OutputFormat,
-    hidden_columns: Vec<usize>,
-    window_size: (f32, f32),
-) -> iced::Result {
-    debug!("Starting Tabsel in debug mode");
+/// Right-to-left scripts need full text shaping; basic shaping is cheaper
+/// and enough otherwise.
+fn cell_shaping() -> Shaping {
+    if THEME.rtl {
+        Shaping::Advanced
+    } else {
+        Shaping::Basic
+    }
+}
 
-    let default_font = THEME
+/// The theme's font family, or iced's default.
+fn theme_font() -> Font {
+    THEME
         .font
         .as_ref()
         .map(|font| Font::with_name(font))
-        .unwrap_or_default();
+        .unwrap_or_default()
+}
+
+/// A cell's text as a row of runs with the fuzzy-matched characters at
+/// `positions` in bold.
+fn highlighted_text<'a>(value: &str, positions: &[usize], size: u16) -> Element<'a, Message> {
+    let bold = Font {
+        weight: Weight::Bold,
+        ..theme_font()
+    };
+    let runs = state::highlight_runs(value, positions)
+        .into_iter()
+        .map(|(run, matched)| {
+            let run = text(add_word_break_hints(&run))
+                .size(size)
+                .shaping(cell_shaping());
+            if matched { run.font(bold) } else { run }.into()
+        });
+    Row::with_children(runs).into()
+}
+
+/// Open the window at the minimum of `bounds`
+/// (`(min_width, max_width, min_height, max_height)`, in pixels), which also
+/// limit any later resize so taller content scrolls instead.
+pub fn run(flags: TabselFlags, bounds: (f32, f32, f32, f32)) -> iced::Result {
+    let (min_w, max_w, min_h, max_h) = bounds;
+    debug!("Starting Tabsel in debug mode");
+
+    let default_font = theme_font();
 
     Tabsel::run(Settings {
         id: Some("tabsel".to_string()),
         window: window::Settings {
             transparent: true,
             size: Size {
-                width: window_size.0,
-                height: window_size.1,
+                width: min_w,
+                height: min_h,
             },
             decorations: false,
             resizable: false,
             position: window::Position::Centered,
-            min_size: None,
-            max_size: None,
+            min_size: Some(Size {
+                width: min_w,
+                height: min_h,
+            }),
+            max_size: Some(Size {
+                width: max_w,
+                height: max_h,
+            }),
             icon: None,
             visible: true,
             platform_specific: PlatformSpecific {
@@ -78,13 +139,7 @@ pub fn run(
         default_text_size: Pixels::from(THEME.font_size),
         antialiasing: true,
         default_font,
-        flags: TabselFlags {
-            table,
-            available_modes,
-            filter_enabled,
-            output_format,
-            hidden_columns,
-        },
+        flags,
         fonts: vec![],
     })
 }
@@ -92,26 +147,484 @@ pub fn run(
 #[derive(Debug)]
 pub struct Tabsel {
     state: state::State,
+    /// `--hidden-column` specs, resolved against the table once it is loaded.
+    hidden_column_specs: Vec<String>,
+    /// `--filter-weights` pairs, resolved the same way.
+    filter_weight_specs: Vec<(String, f64)>,
+    /// `--sort` keys as `(column spec, descending)`, resolved the same way.
+    sort_specs: Vec<(String, bool)>,
+    /// `--group-by` spec and `--group-agg` pairs, resolved against the
+    /// parsed table before it is grouped.
+    group_by_spec: Option<String>,
+    group_agg_specs: Vec<(Aggregate, String)>,
+    /// `--id-column` spec, resolved the same way.
+    id_column_spec: Option<String>,
+    /// `--mru-key` spec, resolved the same way.
+    mru_key_spec: Option<String>,
+    /// Where the `--mru` list is kept; `None` without `--mru`.
+    mru_path: Option<PathBuf>,
+    /// `--select` row, applied after the initial `--query` filter.
+    initial_selection: usize,
+    /// Exit on any `Table::validate` warning (`--strict`).
+    strict: bool,
+    /// Leave `--template` placeholders naming no column empty instead of
+    /// exiting.
+    template_allow_missing: bool,
+    /// `--head`/`--tail`, applied to the parsed table before anything else.
+    row_slice: Option<RowSlice>,
+    /// Remove a layer of literal quotes from every cell (`--strip-quotes`).
+    strip_quotes: bool,
+    /// Where the selection's input position is written on confirm.
+    index_fd: Option<i32>,
+    /// Confirming with nothing to select succeeds when the input had no
+    /// rows (`--allow-empty`).
+    allow_empty: bool,
+    /// Where the output goes on confirm instead of stdout, unless `tee` also
+    /// prints it.
+    output_file: Option<PathBuf>,
+    tee: bool,
+    /// Command run with the output on confirm (`--on-select`).
+    on_select: Option<String>,
+    on_select_detach: bool,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Loading,
+    /// The parsed table, and the input text when it can be parsed again.
+    Loaded(Table, Option<String>),
     Click(usize),
+    /// A click on one cell, by filtered row and actual column, outside row
+    /// mode.
+    ClickCell(usize, usize),
+    /// A press on the drag handle, which starts moving the window.
+    Drag,
     InputChanged(String),
     KeyboardEvent(Key, Modifiers),
+    Scrolled(scrollable::Viewport),
+    ClearFlash,
     Unfocused,
+    /// Periodic check of the `--timeout` deadline.
+    Timeout(Instant),
 }
 
 static SCROLL_ID: Lazy<scrollable::Id> = Lazy::new(scrollable::Id::unique);
 static INPUT_ID: Lazy<text_input::Id> = Lazy::new(text_input::Id::unique);
 
+/// How long a status flash such as "Copied" stays visible.
+const FLASH_DURATION: Duration = Duration::from_millis(1500);
+
+/// How often the `--timeout` deadline is checked.
+const TIMEOUT_TICK: Duration = Duration::from_millis(250);
+
+/// Height of the `--draggable` handle strip.
+const DRAG_HANDLE_HEIGHT: f32 = 12.0;
+
+#[derive(Default)]
 pub struct TabselFlags {
-    pub table: Table,
+    pub input_format: InputFormat,
+    pub has_header: bool,
+    pub parse_options: ParseOptions,
+    pub source: InputSource,
     pub available_modes: Vec<SelectionMode>,
+    /// The mode to start in; one of `available_modes`.
+    pub initial_mode: SelectionMode,
     pub filter_enabled: bool,
-    pub output_format: OutputFormat,
-    pub hidden_columns: Vec<usize>,
+    pub focus_filter: bool,
+    pub multi_select: bool,
+    pub multi_enter: MultiEnter,
+    pub decode: Option<Decode>,
+    pub quick_keys: bool,
+    pub pins: Vec<String>,
+    pub pin_sticky: bool,
+    pub loop_mode: bool,
+    pub loop_remove: bool,
+    pub filter_mode: FilterMode,
+    pub case_fold: CaseFold,
+    pub output_formats: ModeFormats,
+    pub output_options: OutputOptions,
+    pub hidden_columns: Vec<String>,
+    pub column_limit: Option<usize>,
+    pub output_visible_only: bool,
+    pub id_column: Option<String>,
+    pub mru_path: Option<PathBuf>,
+    pub mru_key: Option<String>,
+    pub filter_weights: Vec<(String, f64)>,
+    pub sort: Vec<(String, bool)>,
+    pub group_by: Option<String>,
+    pub group_aggregates: Vec<(Aggregate, String)>,
+    pub initial_query: String,
+    pub scope: Option<String>,
+    pub initial_selection: usize,
+    pub copy_to: Vec<clipboard::Selection>,
+    pub on_select: Option<String>,
+    pub on_select_detach: bool,
+    pub show_header: bool,
+    pub footer_aggregate: Aggregate,
+    pub timeout: Option<Duration>,
+    pub timeout_reset_on_input: bool,
+    pub row_slice: Option<RowSlice>,
+    pub strip_quotes: bool,
+    pub strict: bool,
+    pub template: Option<Template>,
+    pub template_allow_missing: bool,
+    pub index_fd: Option<i32>,
+    pub output_file: Option<PathBuf>,
+    pub tee: bool,
+}
+
+/// A parsed table after the flags that reshape it, with the column specs
+/// resolved against it.
+struct PreparedTable {
+    table: Table,
+    hidden_columns: Vec<usize>,
+    column_weights: Vec<f64>,
+    sort_keys: Vec<SortKey>,
+    id_column: Option<usize>,
+    mru_key: usize,
+}
+
+impl Tabsel {
+    /// Set up the (still loading) state from the command line flags.
+    fn with_flags(flags: TabselFlags) -> Self {
+        let entry = (flags.source == InputSource::Null).then(|| state::DataEntry {
+            format: flags.input_format,
+            options: flags.parse_options.clone(),
+            header_pending: flags.has_header,
+            editing: None,
+        });
+        let mru = flags.mru_path.as_deref().map_or_else(Vec::new, |path| {
+            mru::load(path).unwrap_or_else(|err| {
+                warn!("Could not read the recently used list: {err}");
+                Vec::new()
+            })
+        });
+        let state = state::State {
+            active_mode: flags.initial_mode,
+            available_modes: flags.available_modes,
+            filter_enabled: flags.filter_enabled,
+            focus_filter: flags.focus_filter,
+            multi_select: flags.multi_select,
+            multi_enter: flags.multi_enter,
+            decode: flags.decode,
+            quick_keys: flags.quick_keys,
+            pins: flags.pins,
+            pin_sticky: flags.pin_sticky,
+            mru,
+            loop_mode: flags.loop_mode,
+            loop_remove: flags.loop_remove,
+            filter_mode: flags.filter_mode,
+            case_fold: flags.case_fold,
+            filter_text: flags.initial_query,
+            scope: flags.scope,
+            output_formats: flags.output_formats,
+            output_options: flags.output_options,
+            column_limit: flags.column_limit,
+            output_visible_only: flags.output_visible_only,
+            show_header: flags.show_header,
+            footer_aggregate: flags.footer_aggregate,
+            timeout: flags.timeout,
+            timeout_reset_on_input: flags.timeout_reset_on_input,
+            copy_to: flags.copy_to,
+            template: flags.template,
+            input_format: flags.input_format,
+            has_header: flags.has_header,
+            parse_options: flags.parse_options.clone(),
+            entry,
+            loading: true,
+            ..Default::default()
+        };
+
+        Tabsel {
+            state,
+            hidden_column_specs: flags.hidden_columns,
+            filter_weight_specs: flags.filter_weights,
+            sort_specs: flags.sort,
+            group_by_spec: flags.group_by,
+            group_agg_specs: flags.group_aggregates,
+            id_column_spec: flags.id_column,
+            mru_key_spec: flags.mru_key,
+            mru_path: flags.mru_path,
+            initial_selection: flags.initial_selection,
+            row_slice: flags.row_slice,
+            strip_quotes: flags.strip_quotes,
+            index_fd: flags.index_fd,
+            allow_empty: flags.parse_options.allow_empty,
+            output_file: flags.output_file,
+            tee: flags.tee,
+            strict: flags.strict,
+            template_allow_missing: flags.template_allow_missing,
+            on_select: flags.on_select,
+            on_select_detach: flags.on_select_detach,
+        }
+    }
+
+    /// Parse the input in the foreground and load it, for the paths that
+    /// never open a window.
+    fn headless(flags: TabselFlags) -> Self {
+        let table = crate::parse_input(
+            flags.input_format,
+            flags.has_header,
+            &flags.parse_options,
+            &flags.source,
+        );
+        let mut tabsel = Tabsel::with_flags(flags);
+        tabsel.check_table(&table);
+        tabsel.load_table(table);
+        tabsel
+    }
+
+    /// Report structural problems with the input, exiting under `--strict`.
+    fn check_table(&self, table: &Table) {
+        let warnings = table.validate();
+        for warning in &warnings {
+            if self.strict {
+                eprintln!("Invalid input: {warning}");
+            } else {
+                warn!("Input: {warning}");
+            }
+        }
+        if self.strict && !warnings.is_empty() {
+            exit(1);
+        }
+    }
+
+    /// Resolve the column specs against a parsed table, install it and apply
+    /// the initial selection, exiting if a spec names no column. Returns the
+    /// indices of columns not shown, whether hidden or cut off by the column
+    /// limit.
+    fn load_table(&mut self, table: Table) -> Vec<usize> {
+        let prepared = self.prepare_table(table).unwrap_or_else(|err| {
+            eprintln!("{err}");
+            exit(1);
+        });
+        self.install_table(prepared)
+    }
+
+    /// Apply `--head`/`--tail`, `--strip-quotes` and `--group-by` to a parsed
+    /// table and resolve the other column specs against the result, or say
+    /// which spec names no column.
+    fn prepare_table(&self, mut table: Table) -> Result<PreparedTable, String> {
+        if let Some(slice) = self.row_slice {
+            slice.apply(&mut table);
+        }
+        if self.strip_quotes {
+            table.strip_quotes();
+        }
+        // Every other column spec refers to the grouped table
+        if let Some(spec) = &self.group_by_spec {
+            let aggregates = self
+                .group_agg_specs
+                .iter()
+                .map(|(aggregate, spec)| {
+                    Ok(GroupAggregate {
+                        aggregate: *aggregate,
+                        col: crate::resolve_column(&table, spec)?,
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            table = table.group_by(crate::resolve_column(&table, spec)?, &aggregates);
+        }
+        let sort_keys = self
+            .sort_specs
+            .iter()
+            .map(|(spec, descending)| {
+                Ok(SortKey {
+                    col: crate::resolve_column(&table, spec)?,
+                    descending: *descending,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        let id_column = match &self.id_column_spec {
+            Some(spec) => Some(crate::resolve_column(&table, spec)?),
+            None => None,
+        };
+        let mru_key = match &self.mru_key_spec {
+            Some(spec) => crate::resolve_column(&table, spec)?,
+            None => 0,
+        };
+        if let Some(template) = &self.state.template {
+            for placeholder in template.missing(&table) {
+                if !self.template_allow_missing {
+                    return Err(format!("--template placeholder {placeholder} names no column"));
+                }
+                warn!("--template placeholder {placeholder} names no column; it will be empty");
+            }
+        }
+        Ok(PreparedTable {
+            hidden_columns: crate::resolve_hidden_columns(&table, &self.hidden_column_specs)?,
+            column_weights: crate::resolve_filter_weights(&table, &self.filter_weight_specs)?,
+            sort_keys,
+            id_column,
+            mru_key,
+            table,
+        })
+    }
+
+    /// Install a prepared table and apply the initial selection. Returns the
+    /// indices of columns not shown.
+    fn install_table(&mut self, prepared: PreparedTable) -> Vec<usize> {
+        self.state.column_weights = prepared.column_weights;
+        self.state.sort_keys = prepared.sort_keys;
+        self.state.id_column = prepared.id_column;
+        self.state.mru_key = prepared.mru_key;
+        self.state.load_table(prepared.table, &prepared.hidden_columns);
+        self.state.select_row(self.initial_selection);
+        (0..self.state.table.width())
+            .filter(|col| !self.state.visible_columns.contains(col))
+            .collect()
+    }
+
+    /// Read the input again as the next format in [`InputFormat::CYCLE`]
+    /// that parses it and that the column specs fit, going through the same
+    /// steps as the first load. Checks and a drilled-into cell are dropped
+    /// since they point at the old table. Nothing changes when no other
+    /// format fits.
+    fn reparse_next_format(&mut self) -> Result<InputFormat, String> {
+        let current = self.state.input_format;
+        let mut format = current;
+        let mut reason = "No other input format reads the input".to_string();
+        for _ in 0..InputFormat::CYCLE.len() {
+            format = format.next();
+            if format == current {
+                break;
+            }
+            let prepared = self.state.parse_as(format).and_then(|table| self.prepare_table(table));
+            match prepared {
+                Ok(prepared) => {
+                    self.state.input_format = format;
+                    self.state.drilled = None;
+                    self.state.selected_rows.clear();
+                    self.state.selected_cols.clear();
+                    self.state.selected_col = 0;
+                    self.install_table(prepared);
+                    return Ok(format);
+                }
+                Err(err) => reason = err,
+            }
+        }
+        Err(reason)
+    }
+}
+
+/// Parse stdin and resolve the selection the flags describe without opening
+/// a window: a description goes to stderr and the output to stdout. Exits 1
+/// if nothing would be selected, unless `--allow-empty` input had no rows.
+pub fn dry_run(flags: TabselFlags) -> ! {
+    let tabsel = Tabsel::headless(flags);
+
+    eprintln!("{}", tabsel.state.describe_selection());
+    match tabsel.state.selection_output() {
+        Some(result) => {
+            print_selection(&tabsel.state, &result);
+            exit(0);
+        }
+        None => exit(tabsel.nothing_selected_code()),
+    }
+}
+
+/// Parse the input and confirm the selection the flags describe without
+/// opening a window (`--no-window`), so no display is needed. The output is
+/// printed and handed to `--index-fd` and `--on-select` as on a confirm in
+/// the window. Exits 1 if nothing would be selected, unless `--allow-empty`
+/// input had no rows.
+pub fn no_window(flags: TabselFlags) -> ! {
+    let mut tabsel = Tabsel::headless(flags);
+    let Some(result) = tabsel.state.selection_output() else {
+        exit(tabsel.nothing_selected_code());
+    };
+    let mru_keys = tabsel.state.mru_keys();
+    tabsel.save_mru(&mru_keys);
+    tabsel.emit(&result);
+    exit(0);
+}
+
+/// A row's own padding, which a boxed grid moves into the row's cells so
+/// their lines meet.
+fn row_padding(padding: &OnagrePadding, boxed: bool) -> Padding {
+    if boxed {
+        Padding::ZERO
+    } else {
+        padding.to_iced_padding()
+    }
+}
+
+/// Outline a header or footer cell in a boxed grid.
+fn boxed_plain_cell<'a>(
+    cell: Container<'a, Message>,
+    row_style: &HeaderRowStyle,
+) -> Element<'a, Message> {
+    let base = GenericContainerStyle {
+        background: OnagreColor::TRANSPARENT,
+        color: row_style.color,
+        padding: OnagrePadding::ZERO,
+        ..Default::default()
+    };
+    let style = THEME.boxed_cell(&base, &row_style.padding, false);
+    cell.padding(style.padding.to_iced_padding())
+        .style(iced::theme::Container::Custom(Box::new(style)))
+        .into()
+}
+
+/// Draw the outer half of a boxed grid's line around `content`.
+fn grid_frame<'a>(content: impl Into<Element<'a, Message>>) -> Element<'a, Message> {
+    Container::new(content)
+        .style(iced::theme::Container::Custom(Box::new(THEME.grid_frame())))
+        .into()
+}
+
+/// Print a confirmed selection to stdout.
+fn print_selection(state: &state::State, result: &str) {
+    print_bytes(&selection_bytes(state, result));
+}
+
+/// What a confirmed selection writes: its text and a newline, after a byte
+/// order mark with `--output-bom`. With `--decode` a cell's decoded bytes
+/// are written as they are instead, with no trailing newline; a cell that
+/// doesn't decode is written as text, with a warning.
+fn selection_bytes(state: &state::State, result: &str) -> Vec<u8> {
+    match state.decoded_cell() {
+        Some(Ok(bytes)) => return bytes,
+        Some(Err(err)) => warn!("Could not decode the selected cell, printing it as text: {err}"),
+        None => {}
+    }
+    // A --loop session is one stream of output, marked once at its start
+    let text = if state.confirmed > 1 {
+        result.to_string()
+    } else {
+        output::with_bom(result.to_string(), &state.output_options)
+    };
+    format!("{text}\n").into_bytes()
+}
+
+fn print_bytes(bytes: &[u8]) {
+    let mut stdout = io::stdout().lock();
+    if let Err(err) = stdout.write_all(bytes).and_then(|()| stdout.flush()) {
+        warn!("Could not write the selection: {err}");
+    }
+}
+
+/// Write `bytes` to the `--output-file`, creating it, and either replacing
+/// what it held or adding to the end.
+fn write_output_file(path: &Path, bytes: &[u8], append: bool) -> io::Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)?;
+    file.write_all(bytes)
+}
+
+/// Parse stdin and print every row passing the `--query` filter as a single
+/// JSON array without opening a window (`--output json-rows`).
+pub fn export_json_rows(flags: TabselFlags) -> ! {
+    let tabsel = Tabsel::headless(flags);
+
+    let rows = tabsel.state.json_rows_output();
+    let text = output::with_bom(rows, &tabsel.state.output_options);
+    tabsel.write_output(format!("{text}\n").as_bytes());
+    exit(0);
 }
 
 impl Application for Tabsel {
@@ -121,31 +634,27 @@ impl Application for Tabsel {
     type Flags = TabselFlags;
 
     fn new(flags: TabselFlags) -> (Self, Command<Self::Message>) {
-        let active_mode = flags.available_modes[0];
-        let num_cols = flags
-            .table
-            .headers
-            .as_ref()
-            .map_or_else(|| flags.table.rows.first().map_or(0, |r| r.len()), |h| h.len());
-        let visible_columns: Vec<usize> = (0..num_cols)
-            .filter(|c| !flags.hidden_columns.contains(c))
-            .collect();
-        let mut state = state::State {
-            table: flags.table,
-            active_mode,
-            available_modes: flags.available_modes,
-            filter_enabled: flags.filter_enabled,
-            output_format: flags.output_format,
-            visible_columns,
-            ..Default::default()
-        };
-        state.init_filtered_indices();
-
-        let tabsel = Tabsel { state };
+        // Parse off the UI thread so the window can show a loading state
+        // while large inputs are read.
+        let input_format = flags.input_format;
+        let has_header = flags.has_header;
+        let parse_options = flags.parse_options.clone();
+        let source = flags.source.clone();
+        let mut tabsel = Tabsel::with_flags(flags);
+        tabsel.state.start_timeout(Instant::now());
+        let load = Command::perform(
+            async move {
+                crate::parse_input_keeping_raw(input_format, has_header, &parse_options, &source)
+            },
+            |(table, raw_input)| Message::Loaded(table, raw_input),
+        );
 
         (
             tabsel,
-            Command::perform(async {}, move |()| Message::Loading),
+            Command::batch([
+                Command::perform(async {}, move |()| Message::Loading),
+                load,
+            ]),
         )
     }
 
@@ -156,23 +665,84 @@ impl Application for Tabsel {
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
         match message {
             Message::Loading => {
-                if self.state.filter_enabled {
+                if self.state.filter_enabled && self.state.focus_filter {
                     text_input::focus(INPUT_ID.clone())
                 } else {
                     Command::none()
                 }
             }
+            Message::Loaded(table, raw_input) => {
+                // A header with no rows still has columns to pick, and
+                // --allow-empty opens on the empty-state message
+                let nothing = table.is_empty() && table.headers.is_none();
+                if nothing && self.state.entry.is_none() && !self.allow_empty {
+                    eprintln!("No data rows to display");
+                    exit(1);
+                }
+
+                info!(
+                    "Parsed table: {} rows, {} columns",
+                    table.rows.len(),
+                    table.width()
+                );
+                self.check_table(&table);
+
+                let hidden_columns = self.load_table(table);
+                self.state.raw_input = raw_input;
+
+                let (width, height) = crate::resolve_window_size(
+                    &self.state.table,
+                    self.state.filter_enabled,
+                    &hidden_columns,
+                );
+                self.state.page_rows = crate::rows_per_page(
+                    &self.state.table,
+                    self.state.filter_enabled,
+                    &hidden_columns,
+                    height,
+                );
+                let (screen_w, screen_h) = *crate::SCREEN_SIZE;
+                Command::batch([
+                    window::resize(window::Id::MAIN, Size { width, height }),
+                    window::move_to(
+                        window::Id::MAIN,
+                        Point::new((screen_w - width) / 2.0, (screen_h - height) / 2.0),
+                    ),
+                    self.snap(),
+                ])
+            }
             Message::InputChanged(value) => {
-                self.state.filter_text = value;
-                self.state.update_filtered_indices();
-                self.state.selected_row = 0;
+                self.state.note_input(Instant::now());
+                self.state.set_filter_text(value);
                 self.snap()
             }
-            Message::KeyboardEvent(key, modifiers) => self.handle_input(key, modifiers),
+            Message::KeyboardEvent(key, modifiers) => {
+                self.state.note_input(Instant::now());
+                self.handle_input(key, modifiers)
+            }
+            Message::Click(_) | Message::ClickCell(..) if self.state.loading => Command::none(),
             Message::Click(filtered_pos) => {
                 self.state.selected_row = filtered_pos;
-                self.on_confirm()
+                self.state.header_selected = false;
+                self.on_confirm(None)
             }
+            Message::ClickCell(filtered_pos, col) => {
+                self.state.select_cell(filtered_pos, col);
+                self.on_confirm(None)
+            }
+            Message::Scrolled(viewport) => {
+                self.state.viewport = Some(ScrollViewport {
+                    offset_y: viewport.absolute_offset().y,
+                    height: viewport.bounds().height,
+                    content_height: viewport.content_bounds().height,
+                });
+                Command::none()
+            }
+            Message::ClearFlash => {
+                self.state.flash = None;
+                Command::none()
+            }
+            Message::Drag => window::drag(window::Id::MAIN),
             Message::Unfocused => {
                 if THEME.exit_unfocused {
                     exit(0);
@@ -180,21 +750,52 @@ impl Application for Tabsel {
                     Command::none()
                 }
             }
+            Message::Timeout(now) => {
+                if self.state.timed_out(now) {
+                    info!("No selection before the timeout, cancelling");
+                    self.cancel();
+                }
+                Command::none()
+            }
         }
     }
 
     fn view(&self) -> Element<'_, Self::Message> {
         let visible_cols = &self.state.visible_columns;
+        let display_cols = self.state.display_columns(THEME.rtl);
 
         let mut app_column: Vec<Element<'_, Self::Message>> = Vec::new();
 
+        // A strip of its own to drag the window by, so row clicks still
+        // select and confirm
+        if THEME.draggable {
+            let grip = Container::new(horizontal_rule(2)).width(Length::Fixed(32.0));
+            let handle = Container::new(grip)
+                .width(Length::Fill)
+                .height(Length::Fixed(DRAG_HANDLE_HEIGHT))
+                .center_x()
+                .center_y();
+            app_column.push(mouse_area(handle).on_press(Message::Drag).into());
+        }
+
         // Filter bar (if enabled)
         if self.state.filter_enabled {
             let search_style = THEME.search();
             let input_style = THEME.search_input();
 
+            let placeholder = match self.state.filter_mode {
+                _ if self.state.is_editing() => "Edit cell...".to_string(),
+                _ if self.state.entry.is_some() => "Add a row...".to_string(),
+                _ if self.state.scope.is_some() => {
+                    let scope = self.state.scope.as_deref().unwrap_or_default();
+                    format!("Filter within \"{scope}\" (Ctrl+L to clear)...")
+                }
+                FilterMode::Substring => "Filter...".to_string(),
+                mode => format!("Filter ({})...", mode.name()),
+            };
+
             let input: TextInput<'_, Message> =
-                text_input("Filter...", &self.state.filter_text)
+                text_input(&placeholder, &self.state.filter_text)
                     .id(INPUT_ID.clone())
                     .on_input(Message::InputChanged)
                     .size(input_style.font_size)
@@ -210,99 +811,243 @@ impl Application for Tabsel {
         }
 
         // Build rows
-        let column_spacing = THEME.app_container.rows.column_spacing;
+        // A boxed grid draws its lines around cells that sit flush; with a
+        // fixed row height the cells fill it so the lines reach across
+        let boxed = THEME.grid_style == GridStyle::Boxed;
+        let column_spacing = if boxed { 0 } else { THEME.app_container.rows.column_spacing };
+        let cell_height = match THEME.row_height {
+            Some(_) if boxed => Length::Fill,
+            _ => Length::Shrink,
+        };
         let mut rows_column: Vec<Element<'_, Self::Message>> = Vec::new();
 
-        // Header row (if present)
-        if let Some(headers) = &self.state.table.headers {
+        // Where an opened nested cell came from; Escape goes back
+        if let Some(frame) = &self.state.drilled {
+            let header_style = &THEME.app_container.rows.header;
+            rows_column.push(
+                Container::new(
+                    text(format!("‹ {}", frame.breadcrumb)).size(header_style.font_size),
+                )
+                .padding(header_style.padding.to_iced_padding())
+                .into(),
+            );
+        }
+
+        let grid_start = rows_column.len();
+
+        // Header row (if present and not hidden with Ctrl+H)
+        let headers = self.state.table.headers.as_ref();
+        if let Some(headers) = headers.filter(|_| self.state.show_header) {
             let header_style = &THEME.app_container.rows.header;
-            let header_cells: Vec<Element<'_, Self::Message>> = visible_cols
+            let more_label = self.state.more_columns_label();
+            let mut header_cells: Vec<Element<'_, Self::Message>> = display_cols
                 .iter()
-                .map(|&col| {
-                    let h = &headers[col];
-                    Container::new(
-                        text(add_word_break_hints(h.as_str()))
-                            .size(header_style.font_size),
-                    )
-                    .width(Length::FillPortion(1))
-                    .clip(true)
-                    .into()
+                .map(|&(vis_col, col)| {
+                    let h = headers.get(col).map_or("", String::as_str);
+                    let h = match &more_label {
+                        Some(more) if vis_col + 1 == visible_cols.len() => format!("{h} {more}"),
+                        _ => h.to_string(),
+                    };
+                    let align = self.column_alignment(col);
+                    let label = text(add_word_break_hints(&h))
+                        .size(header_style.font_size)
+                        .shaping(cell_shaping())
+                        .horizontal_alignment(align);
+                    // The type badge is secondary text after the name
+                    let label: Element<'_, Self::Message> = if THEME.show_column_types {
+                        let badge = text(self.state.column_type_badge(col))
+                            .size(header_style.font_size as f32 * 0.75);
+                        Row::with_children([label.into(), badge.into()])
+                            .spacing(4)
+                            .align_items(Alignment::Center)
+                            .into()
+                    } else {
+                        label.into()
+                    };
+                    let cell = Container::new(label)
+                        .width(Length::FillPortion(1))
+                        .align_x(align)
+                        .clip(true);
+                    if boxed {
+                        boxed_plain_cell(cell, header_style)
+                    } else {
+                        cell.into()
+                    }
                 })
                 .collect();
+            if let Some(gutter) = self.pin_gutter(false, header_style.font_size) {
+                header_cells.insert(0, gutter);
+            }
+
+            // A selected header borrows the selected row style
+            let header_container_style: Box<dyn container::StyleSheet<Style = Theme>> =
+                if self.state.header_selected {
+                    Box::new(&THEME.app_container.rows.row_selected)
+                } else {
+                    Box::new(header_style)
+                };
 
             let header_row = Container::new(
                 Row::with_children(header_cells)
                     .width(Length::Fill)
                     .spacing(column_spacing),
             )
-            .style(iced::theme::Container::Custom(Box::new(header_style)))
-            .padding(header_style.padding.to_iced_padding())
+            .style(iced::theme::Container::Custom(header_container_style))
+            .padding(row_padding(&header_style.padding, boxed))
             .width(header_style.width);
 
             rows_column.push(header_row.into());
 
-            // Separator line between header and data
-            if header_style.separator_width > 0.0 {
+            // Separator line between header and data, which a grid draws
+            if header_style.separator_width > 0.0 && !boxed {
                 rows_column.push(horizontal_rule(header_style.separator_width as u16).into());
             }
         }
 
         // Data rows (filtered)
         for (filtered_pos, &actual_idx) in self.state.filtered_indices.iter().enumerate() {
-            let row_data = &self.state.table.rows[actual_idx];
-            let cells: Vec<Element<'_, Self::Message>> = visible_cols
+            let Some(row_data) = self.state.table.row(actual_idx) else {
+                continue;
+            };
+            let row_style = self.row_style(filtered_pos);
+            let checked = self.state.row_is_checked(filtered_pos);
+            let mut cells: Vec<Element<'_, Self::Message>> = display_cols
                 .iter()
-                .enumerate()
-                .map(|(vis_col, &actual_col)| {
+                .map(|&(vis_col, actual_col)| {
                     let selected = self.state.cell_is_selected(filtered_pos, vis_col);
-                    let cell_style = if selected {
-                        &THEME.app_container.rows.row_selected
+                    let cell_style = if checked || selected {
+                        &row_style.title
+                    } else {
+                        &THEME.app_container.rows.row.title
+                    };
+                    // In Cell mode the one selected cell is outlined inside
+                    // the selected row
+                    let outlined = selected && self.state.active_mode == SelectionMode::Cell;
+                    let cell_style = if outlined {
+                        &THEME.app_container.rows.cell_selected
                     } else {
-                        &THEME.app_container.rows.row
+                        cell_style
                     };
 
-                    let cell_text = row_data.get(actual_col).map(|s| s.as_str()).unwrap_or("");
-                    Container::new(
-                        text(add_word_break_hints(cell_text))
-                            .size(cell_style.title.font_size),
-                    )
-                    .style(iced::theme::Container::Custom(Box::new(&cell_style.title)))
-                    .padding(cell_style.title.padding.to_iced_padding())
-                    .width(Length::FillPortion(1))
-                    .clip(true)
-                    .into()
+                    let value = row_data.get(actual_col).map_or("", String::as_str);
+                    // The theme's cell rules recolor cells outside the selection
+                    let ruled = (!checked && !selected)
+                        .then(|| {
+                            let table = &self.state.table;
+                            cell_rule::style_cell(
+                                &THEME.cell_rules,
+                                cell_style,
+                                table,
+                                actual_col,
+                                value,
+                            )
+                        })
+                        .flatten();
+                    let ruled = if boxed {
+                        let base = ruled.as_ref().unwrap_or(cell_style);
+                        Some(THEME.boxed_cell(base, &row_style.padding, outlined))
+                    } else {
+                        ruled
+                    };
+                    let container_style: Box<dyn container::StyleSheet<Style = Theme>> =
+                        match &ruled {
+                            Some(style) => Box::new(style.clone()),
+                            None => Box::new(cell_style),
+                        };
+                    let cell_style = ruled.as_ref().unwrap_or(cell_style);
+
+                    let cell_text =
+                        state::cell_display_text(value, &THEME.empty_cell_placeholder);
+                    let align = self.column_alignment(actual_col);
+                    // Runs are laid out left to right, so RTL text isn't split
+                    let matched = self
+                        .state
+                        .fuzzy_match_positions(actual_idx, actual_col)
+                        .filter(|positions| !positions.is_empty() && !THEME.rtl);
+                    let content: Element<'_, Self::Message> = match matched {
+                        Some(positions) => {
+                            highlighted_text(cell_text, &positions, cell_style.font_size)
+                        }
+                        None => text(add_word_break_hints(cell_text))
+                            .size(cell_style.font_size)
+                            .shaping(cell_shaping())
+                            .horizontal_alignment(align)
+                            .into(),
+                    };
+                    let cell = Container::new(content)
+                        .style(iced::theme::Container::Custom(container_style))
+                        .padding(cell_style.padding.to_iced_padding())
+                        .width(Length::FillPortion(1))
+                        .height(cell_height)
+                        .align_x(align)
+                        .clip(true);
+                    // Outside row mode a click picks the cell under the
+                    // pointer before the row's own click handler sees it
+                    if self.state.active_mode == SelectionMode::Row {
+                        cell.into()
+                    } else {
+                        mouse_area(cell)
+                            .on_press(Message::ClickCell(filtered_pos, actual_col))
+                            .into()
+                    }
                 })
                 .collect();
-
-            // Row container uses selected style if any cell in the row is selected
-            let row_has_selection =
-                (0..visible_cols.len()).any(|c| self.state.cell_is_selected(filtered_pos, c));
-            let row_style = if row_has_selection {
-                &THEME.app_container.rows.row_selected
-            } else {
-                &THEME.app_container.rows.row
-            };
+            let pinned = self.state.is_pinned(actual_idx);
+            if let Some(gutter) = self.pin_gutter(pinned, row_style.title.font_size) {
+                cells.insert(0, gutter);
+            }
 
             let row_content = Row::with_children(cells)
                 .width(Length::Fill)
                 .spacing(column_spacing)
                 .align_items(Alignment::Start);
 
-            let button = Button::new(row_content)
-                .style(iced::theme::Button::Custom(Box::new(&ButtonStyle)))
-                .on_press(Message::Click(filtered_pos));
+            let clickable: Element<'_, Self::Message> = if THEME.row_as_button {
+                Button::new(row_content)
+                    .style(iced::theme::Button::Custom(Box::new(&ButtonStyle)))
+                    .on_press(Message::Click(filtered_pos))
+                    .into()
+            } else {
+                mouse_area(row_content)
+                    .on_press(Message::Click(filtered_pos))
+                    .into()
+            };
 
-            let row_container = Container::new(button)
+            let row_container = Container::new(clickable)
                 .style(iced::theme::Container::Custom(Box::new(row_style)))
-                .padding(row_style.padding.to_iced_padding())
-                .width(row_style.width);
+                .padding(row_padding(&row_style.padding, boxed))
+                .width(row_style.width)
+                .height(THEME.row_container_height());
 
             rows_column.push(row_container.into());
         }
 
+        if boxed && rows_column.len() > grid_start {
+            let grid = rows_column.split_off(grid_start);
+            rows_column.push(grid_frame(column(grid)));
+        }
+
+        // Until the background parse finishes there are no rows to show
+        if self.state.loading {
+            rows_column.push(
+                text("Loading…")
+                    .size(THEME.app_container.rows.row.title.font_size)
+                    .into(),
+            );
+        }
+
+        if let Some(message) = self.state.empty_message() {
+            rows_column.push(
+                text(message)
+                    .size(THEME.app_container.rows.row.title.font_size)
+                    .into(),
+            );
+        }
+
         // Scrollable containing all rows
         let scrollable = scrollable(column(rows_column))
             .id(SCROLL_ID.clone())
+            .on_scroll(Message::Scrolled)
             .style(iced::theme::Scrollable::Custom(Box::new(
                 THEME.scrollable(),
             )));
@@ -317,6 +1062,105 @@ impl Application for Tabsel {
 
         app_column.push(scrollable.into());
 
+        // Footer with an aggregate of each numeric column over the filtered rows
+        if THEME.show_footer && !self.state.loading {
+            let header_style = &THEME.app_container.rows.header;
+            let mut footer_cells: Vec<Element<'_, Self::Message>> = display_cols
+                .iter()
+                .map(|&(_, col)| {
+                    let align = self.column_alignment(col);
+                    let cell = Container::new(
+                        text(self.state.footer_label(col))
+                            .size(header_style.font_size)
+                            .shaping(cell_shaping())
+                            .horizontal_alignment(align),
+                    )
+                    .width(Length::FillPortion(1))
+                    .align_x(align)
+                    .clip(true);
+                    if boxed {
+                        boxed_plain_cell(cell, header_style)
+                    } else {
+                        cell.into()
+                    }
+                })
+                .collect();
+            if let Some(gutter) = self.pin_gutter(false, header_style.font_size) {
+                footer_cells.insert(0, gutter);
+            }
+
+            let footer_row = Container::new(
+                Row::with_children(footer_cells)
+                    .width(Length::Fill)
+                    .spacing(column_spacing),
+            )
+            .style(iced::theme::Container::Custom(Box::new(header_style)))
+            .padding(row_padding(&header_style.padding, boxed))
+            .width(header_style.width);
+
+            app_column.push(if boxed {
+                grid_frame(footer_row)
+            } else {
+                footer_row.into()
+            });
+        }
+
+        // The full, unclipped value of the selection, wrapped
+        if THEME.show_detail {
+            if let Some(value) = self.state.detail_text() {
+                let detail_style = &THEME.detail;
+                let detail = Container::new(
+                    text(add_word_break_hints(&value))
+                        .size(detail_style.font_size)
+                        .shaping(cell_shaping()),
+                )
+                .style(iced::theme::Container::Custom(Box::new(detail_style)))
+                .padding(detail_style.padding.to_iced_padding())
+                .width(detail_style.width)
+                .height(detail_style.height)
+                .align_x(detail_style.align_x)
+                .align_y(detail_style.align_y);
+                app_column.push(detail.into());
+            }
+        }
+
+        if let Some(flash) = &self.state.flash {
+            app_column.push(text(flash).size(THEME.font_size as f32 * 0.8).into());
+        }
+
+        // The status line: the mode badge, then the position on the right
+        let mut status: Vec<Element<'_, Self::Message>> = Vec::new();
+        if THEME.show_mode_badge {
+            let badge_style = &THEME.mode_badge;
+            let badge = Container::new(text(self.state.mode_badge()).size(badge_style.font_size))
+                .style(iced::theme::Container::Custom(Box::new(badge_style)))
+                .padding(badge_style.padding.to_iced_padding())
+                .width(badge_style.width)
+                .height(badge_style.height)
+                .align_x(badge_style.align_x)
+                .align_y(badge_style.align_y);
+            status.push(badge.into());
+        }
+
+        let scroll_style = THEME.scrollable();
+        if scroll_style.show_position {
+            if let Some(label) = self.state.position_label() {
+                let position = text(label)
+                    .size(scroll_style.position_font_size)
+                    .style(iced::theme::Text::Color(scroll_style.position_color.into()));
+                status.push(
+                    Container::new(position)
+                        .width(Length::Fill)
+                        .align_x(Horizontal::Right)
+                        .into(),
+                );
+            }
+        }
+
+        if !status.is_empty() {
+            app_column.push(Row::with_children(status).align_items(Alignment::Center).into());
+        }
+
         let app_container = Container::new(
             Column::with_children(app_column).align_items(Alignment::Start),
         )
@@ -337,18 +1181,235 @@ impl Application for Tabsel {
     }
 
     fn subscription(&self) -> Subscription<Self::Message> {
-        Tabsel::keyboard_event()
+        let mut subscriptions = vec![Tabsel::keyboard_event()];
+        if self.state.flash.is_some() {
+            subscriptions.push(iced::time::every(FLASH_DURATION).map(|_| Message::ClearFlash));
+        }
+        if self.state.deadline.is_some() {
+            subscriptions.push(iced::time::every(TIMEOUT_TICK).map(Message::Timeout));
+        }
+        Subscription::batch(subscriptions)
     }
 }
 
 impl Tabsel {
+    /// Horizontal alignment for an actual column index (theme override, or
+    /// numeric detection).
+    fn column_alignment(&self, col: usize) -> Horizontal {
+        let rows = &THEME.app_container.rows;
+        // Right-to-left themes right-align unless --column-align says otherwise
+        if THEME.rtl && rows.column_align.get(col).is_none() {
+            return Horizontal::Right;
+        }
+        rows.column_alignment(col, self.state.column_is_numeric(col))
+    }
+
+    /// The narrow leading column marking pinned rows with a star, kept
+    /// (blank) in the header and footer so the columns line up. `None`
+    /// without `--pin`.
+    fn pin_gutter(&self, pinned: bool, size: u16) -> Option<Element<'_, Message>> {
+        if self.state.pins.is_empty() {
+            return None;
+        }
+        let mark = if pinned { "★" } else { "" };
+        Some(
+            Container::new(text(mark).size(size))
+                .width(Length::Fixed(size as f32 * 1.5))
+                .into(),
+        )
+    }
+
+    /// Style for a data row: checked rows use the selected style, and the
+    /// cursor row does too unless multi-select needs it told apart.
+    fn row_style(&self, filtered_pos: usize) -> &'static RowStyles {
+        let rows = &THEME.app_container.rows;
+        let is_cursor = self.state.row_is_cursor(filtered_pos);
+
+        if self.state.row_is_checked(filtered_pos) {
+            &rows.row_selected
+        } else if is_cursor && self.state.multi_select {
+            &rows.row_cursor
+        } else if is_cursor {
+            &rows.row_selected
+        } else {
+            &rows.row
+        }
+    }
+
     fn handle_input(&mut self, key_code: Key, modifiers: Modifiers) -> Command<Message> {
+        // Only cancelling makes sense while the table is still loading
+        if self.state.loading {
+            if key_code == Key::Named(Named::Escape) {
+                exit(1);
+            }
+            return Command::none();
+        }
+
+        // In --null-input mode Enter adds the prompt line as a row (or stores
+        // the cell being edited), F2 edits the selected cell and Escape
+        // abandons the edit. Enter on an empty prompt confirms as usual.
+        if self.state.entry.is_some() {
+            match key_code {
+                Key::Named(Named::Enter)
+                    if !self.state.filter_text.is_empty() || self.state.is_editing() =>
+                {
+                    if let Err(err) = self.state.submit_entry() {
+                        self.state.flash = Some(err);
+                    }
+                    return self.snap();
+                }
+                Key::Named(Named::F2) if self.state.begin_edit() => {
+                    return text_input::move_cursor_to_end(INPUT_ID.clone());
+                }
+                Key::Named(Named::Escape) if self.state.cancel_edit() => return Command::none(),
+                _ => {}
+            }
+        }
+
         // Shift+Tab cycles selection mode
         if key_code == Key::Named(Named::Tab) && modifiers.shift() {
             self.state.cycle_mode();
             return Command::none();
         }
 
+        // With --multi-enter toggle-row Enter checks rows like Tab, and
+        // Ctrl+Enter confirms
+        if key_code == Key::Named(Named::Enter)
+            && self.state.enter_checks_row(modifiers.control())
+        {
+            return self.inc_selected_row();
+        }
+
+        // Tab checks the cursor row in multi-select mode, or the selected
+        // column in Column mode
+        if key_code == Key::Named(Named::Tab) && self.state.multi_select {
+            self.state.toggle_checked();
+            if self.state.active_mode == SelectionMode::Column {
+                return self.inc_selected_col();
+            }
+            return self.inc_selected_row();
+        }
+
+        // Plain Tab does what the theme's --tab-behavior asks for
+        if key_code == Key::Named(Named::Tab) {
+            match THEME.tab_behavior {
+                TabBehavior::CycleMode => self.state.cycle_mode(),
+                TabBehavior::FocusTable => return iced::widget::focus_next(),
+                TabBehavior::AcceptCompletion => {
+                    // Cell mode completes within the selected column
+                    let column = (self.state.active_mode == SelectionMode::Cell)
+                        .then(|| self.state.actual_col_index(self.state.selected_col));
+                    if let Some(completion) = self.state.filter_completion(column) {
+                        self.state.set_filter_text(completion);
+                        return Command::batch([
+                            text_input::move_cursor_to_end(INPUT_ID.clone()),
+                            self.snap(),
+                        ]);
+                    }
+                }
+            }
+            return Command::none();
+        }
+
+        // With --quick-keys, 1-9 pick and confirm a row; Ctrl is needed while
+        // digits would otherwise go into the filter
+        if self.state.quick_keys && (modifiers.control() || !self.state.filter_enabled) {
+            if let Key::Character(c) = &key_code {
+                if self.state.quick_select(c) {
+                    return self.on_confirm(None);
+                }
+            }
+        }
+
+        // The theme's --format-keys confirm with their output format
+        if modifiers.control() {
+            if let Key::Character(c) = &key_code {
+                if let Some(format) = THEME.format_for_key(c) {
+                    return self.on_confirm(Some(format));
+                }
+            }
+        }
+
+        // Ctrl+Y (or plain `y` when there is no filter bar to type into)
+        // yanks the selection to the clipboard without confirming
+        if key_code == Key::Character("y".into())
+            && (modifiers.control() || !self.state.filter_enabled)
+        {
+            self.yank();
+            return Command::none();
+        }
+
+        // Emacs-style line editing in the filter
+        if self.state.filter_enabled && modifiers.control() {
+            if let Key::Character(c) = &key_code {
+                match c.as_str() {
+                    "a" => return text_input::move_cursor_to_front(INPUT_ID.clone()),
+                    "e" => return text_input::move_cursor_to_end(INPUT_ID.clone()),
+                    "u" => {
+                        self.state.clear_filter();
+                        return self.snap();
+                    }
+                    "w" => {
+                        self.state.delete_filter_word();
+                        return Command::batch([
+                            text_input::move_cursor_to_end(INPUT_ID.clone()),
+                            self.snap(),
+                        ]);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // Ctrl+R inverts which of the filtered rows are checked
+        if modifiers.control() && key_code == Key::Character("r".into()) {
+            self.state.invert_checked();
+            return Command::none();
+        }
+
+        // Ctrl+D outputs how the two checked rows differ
+        if modifiers.control()
+            && key_code == Key::Character("d".into())
+            && self.state.multi_select
+        {
+            match self.emit_diff() {
+                Ok(()) => exit(0),
+                Err(reason) => self.state.flash = Some(reason),
+            }
+            return Command::none();
+        }
+
+        // Ctrl+L drops the --scope
+        if modifiers.control() && key_code == Key::Character("l".into()) {
+            self.state.clear_scope();
+            return self.snap();
+        }
+
+        // Ctrl+H shows or hides the header row
+        if modifiers.control() && key_code == Key::Character("h".into()) {
+            self.state.toggle_header();
+            return Command::none();
+        }
+
+        // Ctrl+Shift+F reads the input again in the next input format
+        if modifiers.control() && modifiers.shift() {
+            if let Key::Character(c) = &key_code {
+                if c.eq_ignore_ascii_case("f") {
+                    self.state.flash = Some(match self.reparse_next_format() {
+                        Ok(format) => format!("Input: {}", format.name()),
+                        Err(reason) => reason,
+                    });
+                    return self.snap();
+                }
+            }
+        }
+
+        // Ctrl+F cycles the filter mode
+        if modifiers.control() && key_code == Key::Character("f".into()) {
+            self.state.cycle_filter_mode();
+            return self.snap();
+        }
+
         match key_code {
             Key::Named(Named::ArrowUp) => {
                 match self.state.active_mode {
@@ -362,57 +1423,165 @@ impl Tabsel {
                     SelectionMode::Column => {}
                 }
             }
-            Key::Named(Named::ArrowLeft) => {
+            // Left and Right follow the layout, which right-to-left reverses
+            Key::Named(Named::ArrowLeft) | Key::Named(Named::ArrowRight) => {
+                let forward = (key_code == Key::Named(Named::ArrowRight)) != THEME.rtl;
                 match self.state.active_mode {
+                    SelectionMode::Column | SelectionMode::Cell if forward => {
+                        return self.inc_selected_col()
+                    }
                     SelectionMode::Column | SelectionMode::Cell => return self.dec_selected_col(),
                     SelectionMode::Row => {}
                 }
             }
-            Key::Named(Named::ArrowRight) => {
-                match self.state.active_mode {
-                    SelectionMode::Column | SelectionMode::Cell => return self.inc_selected_col(),
-                    SelectionMode::Row => {}
-                }
-            }
-            Key::Named(Named::Enter) => return self.on_confirm(),
-            Key::Named(Named::Escape) => {
-                exit(1);
+            // Enter on a nested JSON cell opens it, Shift+Enter outputs it
+            Key::Named(Named::Enter) if !modifiers.shift() && self.state.drill_down() => {
+                return self.snap();
             }
+            Key::Named(Named::Enter) => return self.on_confirm(None),
+            Key::Named(Named::Escape) if self.state.drill_up() => return self.snap(),
+            Key::Named(Named::Escape) => self.cancel(),
             _ => {}
         };
 
         Command::none()
     }
 
-    fn on_confirm(&self) -> Command<Message> {
-        let fmt = self.state.output_format;
-        let table = &self.state.table;
+    /// Confirm the selection, in `format` rather than the mode's own output
+    /// format when a `--format-keys` binding asks for one.
+    fn on_confirm(&mut self, format: Option<OutputFormat>) -> Command<Message> {
+        let Some(result) = self.confirm_output(format) else {
+            if self.state.confirm_empty(THEME.confirm_empty) {
+                exit(self.nothing_selected_code());
+            }
+            return self.snap();
+        };
+
+        self.emit(&result);
+
+        // In --loop mode the picker stays open for the next selection
+        if self.state.loop_mode {
+            return self.snap();
+        }
+        exit(0);
+    }
+
+    /// The output of a confirm, recording what it picks in the `--mru` list
+    /// when there is any.
+    fn confirm_output(&mut self, format: Option<OutputFormat>) -> Option<String> {
+        // A --loop confirm clears the checks, so the keys are taken first
+        let mru_keys = self.state.mru_keys();
 
-        if self.state.visible_rows() == 0 {
-            exit(1);
+        self.state.format_override = format;
+        let output = if self.state.entry.is_some() {
+            self.state.entry_output()
+        } else if self.state.loop_mode {
+            self.state.loop_confirm()
+        } else {
+            self.state.selection_output()
+        };
+        self.state.format_override = None;
+
+        if output.is_some() {
+            self.save_mru(&mru_keys);
+        }
+        output
+    }
+
+    /// The exit code when a confirm has nothing to output: 0 for input
+    /// without rows under `--allow-empty`, otherwise 1.
+    fn nothing_selected_code(&self) -> i32 {
+        if self.allow_empty && self.state.table.is_empty() {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Put the rows a confirm picks at the front of the `--mru` list, outside
+    /// data entry.
+    fn save_mru(&mut self, keys: &[String]) {
+        if let Some(path) = self.mru_path.as_deref().filter(|_| self.state.entry.is_none()) {
+            self.state.record_mru(keys);
+            if let Err(err) = mru::save(path, &self.state.mru) {
+                warn!("Could not save the recently used list: {err}");
+            }
+        }
+    }
+
+    /// Hand a confirmed selection's output to the clipboard, stdout,
+    /// `--index-fd` and `--on-select`.
+    fn emit(&self, result: &str) {
+        for &selection in &self.state.copy_to {
+            if let Err(err) = clipboard::copy(result, selection) {
+                warn!("Could not copy selection to {selection:?}: {err}");
+            }
         }
 
-        let result = match self.state.active_mode {
-            SelectionMode::Row => {
-                let actual_idx = self.state.actual_row_index(self.state.selected_row);
-                output::format_row(table, fmt, actual_idx)
+        self.write_output(&selection_bytes(&self.state, result));
+
+        if let (Some(fd), Some(payload)) = (self.index_fd, self.state.index_payload()) {
+            if let Err(err) = ipc::write_fd(fd, &payload) {
+                warn!("Could not write the selection index: {err}");
             }
-            SelectionMode::Column => {
-                let actual_col = self.state.actual_col_index(self.state.selected_col);
-                output::format_column(table, fmt, actual_col)
+        }
+
+        if let Some(template) = &self.on_select {
+            if let Err(err) = launch::run(template, result, self.on_select_detach) {
+                eprintln!("Error running --on-select command: {err}");
+                exit(1);
             }
-            SelectionMode::Cell => {
-                let actual_idx = self.state.actual_row_index(self.state.selected_row);
-                let actual_col = self.state.actual_col_index(self.state.selected_col);
-                output::format_cell(table, fmt, actual_idx, actual_col)
+        }
+    }
+
+    /// Write output to the `--output-file`, and to stdout without one or
+    /// with `--tee`.
+    fn write_output(&self, bytes: &[u8]) {
+        if let Some(path) = &self.output_file {
+            // A --loop session adds each selection to what it wrote before
+            let append = self.state.confirmed > 1;
+            if let Err(err) = write_output_file(path, bytes, append) {
+                eprintln!("Could not write --output-file {}: {err}", path.display());
+                exit(1);
             }
+        }
+        if self.output_file.is_none() || self.tee {
+            print_bytes(bytes);
+        }
+    }
+
+    /// Emit how the two checked rows differ, like a confirmed selection.
+    fn emit_diff(&self) -> Result<(), String> {
+        let diff = self.state.diff_output()?;
+        self.emit(&diff);
+        Ok(())
+    }
+
+    /// Exit without a selection: unsuccessfully, unless `--loop` has
+    /// already printed some.
+    fn cancel(&self) -> ! {
+        exit(if self.state.confirmed > 0 { 0 } else { 1 })
+    }
+
+    fn yank(&mut self) {
+        let Some(result) = self.state.yank_text() else {
+            return;
         };
 
-        println!("{result}");
-        exit(0);
+        self.state.flash = Some(match clipboard::copy(&result, clipboard::Selection::Clipboard) {
+            Ok(()) => "Copied".to_string(),
+            Err(err) => {
+                warn!("Could not copy selection to clipboard: {err}");
+                "Copy failed".to_string()
+            }
+        });
     }
 
     fn inc_selected_row(&mut self) -> Command<Message> {
+        if self.state.header_selected {
+            self.state.header_selected = false;
+            return self.snap();
+        }
         let total = self.state.visible_rows();
         if total > 0 && self.state.selected_row < total - 1 {
             self.state.selected_row += 1;
@@ -423,26 +1592,42 @@ impl Tabsel {
     fn dec_selected_row(&mut self) -> Command<Message> {
         if self.state.selected_row > 0 {
             self.state.selected_row -= 1;
+        } else if self.state.header_selectable() {
+            self.state.header_selected = true;
         }
         self.snap()
     }
 
     fn inc_selected_col(&mut self) -> Command<Message> {
-        let num_cols = self.state.num_columns();
-        if num_cols > 0 && self.state.selected_col < num_cols - 1 {
-            self.state.selected_col += 1;
+        if self.state.next_col(THEME.cell_flow_wrap) {
+            return self.snap();
         }
         Command::none()
     }
 
     fn dec_selected_col(&mut self) -> Command<Message> {
-        if self.state.selected_col > 0 {
-            self.state.selected_col -= 1;
+        if self.state.prev_col(THEME.cell_flow_wrap) {
+            return self.snap();
         }
         Command::none()
     }
 
     fn snap(&self) -> Command<Message> {
+        if THEME.scroll_policy == ScrollPolicy::OnEdge && self.state.viewport.is_some() {
+            return match self.state.edge_scroll_offset(THEME.row_height) {
+                Some(y) => scrollable::scroll_to(SCROLL_ID.clone(), AbsoluteOffset { x: 0.0, y }),
+                None => Command::none(),
+            };
+        }
+
+        // With a fixed row height the offset can be worked out exactly
+        if let Some(y) = THEME
+            .row_height
+            .and_then(|height| self.state.proportional_scroll_offset(height))
+        {
+            return scrollable::scroll_to(SCROLL_ID.clone(), AbsoluteOffset { x: 0.0, y });
+        }
+
         let total = self.state.visible_rows();
         if total <= 1 {
             return scrollable::snap_to(SCROLL_ID.clone(), RelativeOffset::START);
@@ -470,3 +1655,78 @@ impl Tabsel {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn diff_goes_to_the_output_file() {
+        let path = std::env::temp_dir().join(format!("tabsel-diff-{}.txt", std::process::id()));
+        let mut tabsel = Tabsel::with_flags(TabselFlags {
+            multi_select: true,
+            output_file: Some(path.clone()),
+            ..Default::default()
+        });
+        let rows = vec![
+            vec!["Alice".to_string(), "30".to_string()],
+            vec!["Bob".to_string(), "25".to_string()],
+        ];
+        tabsel.load_table(Table::new(Some(vec!["name".into(), "age".into()]), rows));
+        tabsel.state.selected_rows = std::collections::BTreeSet::from([0, 1]);
+
+        assert_eq!(tabsel.emit_diff(), Ok(()));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "name: Alice -> Bob\nage: 30 -> 25\n"
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn a_confirm_without_output_leaves_the_mru_list_alone() {
+        let path = std::env::temp_dir().join(format!("tabsel-mru-{}.json", std::process::id()));
+        let mut tabsel = Tabsel::with_flags(TabselFlags {
+            mru_path: Some(path.clone()),
+            ..Default::default()
+        });
+        tabsel.load_table(Table::new(None, vec![vec!["Alice".to_string()]]));
+        tabsel.state.set_filter_text("nobody".to_string());
+
+        assert_eq!(tabsel.confirm_output(None), None);
+        assert!(!path.exists());
+
+        tabsel.state.set_filter_text(String::new());
+        assert_eq!(tabsel.confirm_output(None), Some("Alice".to_string()));
+        assert_eq!(mru::load(&path).unwrap(), vec!["Alice"]);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn reparse_resolves_the_column_specs_again() {
+        let mut tabsel = Tabsel::with_flags(TabselFlags {
+            has_header: true,
+            hidden_columns: vec!["age".to_string()],
+            id_column: Some("name".to_string()),
+            mru_key: Some("name".to_string()),
+            ..Default::default()
+        });
+        tabsel.state.raw_input = Some("name age\tcity\nAlice 30\tParis\n".to_string());
+        tabsel.state.input_format = InputFormat::Csv;
+
+        // One column as CSV, which the specs don't fit; two as TSV, neither
+        // named age; three split on whitespace
+        assert_eq!(tabsel.reparse_next_format(), Ok(InputFormat::AnyDelimiter));
+        assert_eq!(tabsel.state.input_format, InputFormat::AnyDelimiter);
+        assert_eq!(tabsel.state.table.width(), 3);
+        assert_eq!(tabsel.state.visible_columns, vec![0, 2]);
+        assert_eq!(tabsel.state.id_column, Some(0));
+        assert_eq!(tabsel.state.mru_key, 0);
+
+        // Nothing else reads the input with an age column
+        assert!(tabsel.reparse_next_format().is_err());
+        assert_eq!(tabsel.state.input_format, InputFormat::AnyDelimiter);
+        assert_eq!(tabsel.state.table.width(), 3);
+    }
+}
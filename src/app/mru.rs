@@ -0,0 +1,78 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+/// How many keys the `--mru` list keeps; the least recent fall off.
+pub const MAX_ENTRIES: usize = 200;
+
+/// Where the `--mru` list is kept unless `--mru-file` says otherwise.
+pub fn default_path() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("tabsel").join("mru.json"))
+}
+
+/// Read the list of keys, most recent first. A missing file is an empty
+/// list.
+pub fn load(path: &Path) -> Result<Vec<String>> {
+    let json = match fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(anyhow!("{}: {err}", path.display())),
+    };
+    serde_json::from_str(&json).map_err(|err| anyhow!("{}: {err}", path.display()))
+}
+
+/// Write the list of keys, creating the directory it goes in.
+pub fn save(path: &Path, keys: &[String]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|err| anyhow!("{}: {err}", dir.display()))?;
+    }
+    let json = serde_json::to_string(keys)?;
+    fs::write(path, json).map_err(|err| anyhow!("{}: {err}", path.display()))
+}
+
+/// Move `key` to the front of the list, dropping an older copy of it and
+/// keeping at most [`MAX_ENTRIES`] keys.
+pub fn record(keys: &mut Vec<String>, key: &str) {
+    keys.retain(|k| k != key);
+    keys.insert(0, key.to_string());
+    keys.truncate(MAX_ENTRIES);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn record_moves_keys_to_the_front_once() {
+        let mut keys = Vec::new();
+        record(&mut keys, "firefox");
+        record(&mut keys, "kitty");
+        record(&mut keys, "firefox");
+        assert_eq!(keys, vec!["firefox", "kitty"]);
+    }
+
+    #[test]
+    fn record_caps_the_list() {
+        let mut keys: Vec<String> = (0..MAX_ENTRIES).map(|i| i.to_string()).collect();
+        record(&mut keys, "new");
+        assert_eq!(keys.len(), MAX_ENTRIES);
+        assert_eq!(keys[0], "new");
+        assert_eq!(keys.last().map(String::as_str), Some("198"));
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let dir = std::env::temp_dir().join(format!("tabsel-mru-{}", std::process::id()));
+        let path = dir.join("nested").join("mru.json");
+        assert_eq!(load(&path).unwrap(), Vec::<String>::new());
+
+        let keys = vec!["a, b".to_string(), "line\nbreak".to_string()];
+        save(&path, &keys).unwrap();
+        assert_eq!(load(&path).unwrap(), keys);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
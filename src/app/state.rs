@@ -1,18 +1,179 @@
-use crate::data::{OutputFormat, SelectionMode, Table};
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+use crate::app::clipboard::Selection;
+use crate::app::mru;
+use crate::app::style::ConfirmEmpty;
+use crate::data::select::{self, SelectionConfig};
+use crate::data::template::Template;
+use crate::data::{
+    fuzzy, output, parse, Aggregate, CaseFold, Decode, FilterMode, InputFormat, ModeFormats,
+    MultiEnter, OutputFormat, OutputOptions, ParseOptions, SelectionMode, SortKey, Table,
+};
+
+/// What a nested cell was opened from, restored when going back up.
+#[derive(Debug)]
+pub struct DrillFrame {
+    /// Where the nested table came from, e.g. `address[2]`.
+    pub breadcrumb: String,
+    table: Table,
+    visible_columns: Vec<usize>,
+    more_columns: usize,
+    numeric_columns: Vec<bool>,
+    column_weights: Vec<f64>,
+    sort_keys: Vec<SortKey>,
+    id_column: Option<usize>,
+    available_modes: Vec<SelectionMode>,
+    active_mode: SelectionMode,
+    filter_text: String,
+    selected_rows: BTreeSet<usize>,
+    selected_cols: BTreeSet<usize>,
+    selected_row: usize,
+    selected_col: usize,
+}
+
+/// The experimental `--null-input` mode: the filter input is a prompt whose
+/// lines are added as rows, and confirming outputs every row built so far.
+#[derive(Debug, Clone, Default)]
+pub struct DataEntry {
+    /// How a line typed at the prompt is split into cells.
+    pub format: InputFormat,
+    pub options: ParseOptions,
+    /// The next line becomes the header row instead (`--header`).
+    pub header_pending: bool,
+    /// The actual (row, column) of the cell being edited at the prompt.
+    pub editing: Option<(usize, usize)>,
+}
+
+impl DataEntry {
+    /// Split one prompt line into cells. JSON lines are read as CSV.
+    fn split(&self, line: &str) -> Result<Vec<String>, String> {
+        let format = match self.format {
+            InputFormat::Json | InputFormat::Ndjson => InputFormat::Csv,
+            format => format,
+        };
+        let table = parse::parse_string(line, format, false, &self.options)
+            .map_err(|err| format!("Invalid row: {err}"))?;
+        Ok(table.rows.into_iter().next().unwrap_or_default())
+    }
+}
+
+/// The visible part of the rows scrollable, as last reported by `on_scroll`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrollViewport {
+    pub offset_y: f32,
+    pub height: f32,
+    pub content_height: f32,
+}
 
 #[derive(Debug)]
 pub struct State {
     pub selected_row: usize,
+    /// The header row is selected instead of a data row (Row mode only).
+    pub header_selected: bool,
     pub selected_col: usize,
     pub active_mode: SelectionMode,
     pub available_modes: Vec<SelectionMode>,
+    /// The configured modes, set aside while a table without columns
+    /// narrows `available_modes` to Row mode.
+    pub configured_modes: Option<Vec<SelectionMode>>,
     pub table: Table,
     pub filter_enabled: bool,
+    /// Focus the filter input on launch (disabled by `--no-focus`).
+    pub focus_filter: bool,
     pub filter_text: String,
+    pub filter_mode: FilterMode,
+    pub case_fold: CaseFold,
     pub filtered_indices: Vec<usize>,
-    pub output_format: OutputFormat,
+    /// Fuzzy match score of each entry in `filtered_indices` (empty in other modes).
+    pub filter_scores: Vec<f64>,
+    /// Fuzzy scoring weight per actual column; missing entries weigh 1.0.
+    pub column_weights: Vec<f64>,
+    /// `--sort` keys, primary first, applied to the filtered rows.
+    pub sort_keys: Vec<SortKey>,
+    pub output_formats: ModeFormats,
+    /// The format a `--format-keys` binding confirms with, in place of the
+    /// mode's own and any `--template`. Only set while confirming.
+    pub format_override: Option<OutputFormat>,
+    pub output_options: OutputOptions,
     /// Indices of columns that are visible (not hidden). Maps visible position to actual column index.
     pub visible_columns: Vec<usize>,
+    /// Show at most this many of the non-hidden columns (`--limit-columns`).
+    pub column_limit: Option<usize>,
+    /// Non-hidden columns cut off by `column_limit`.
+    pub more_columns: usize,
+    /// Row output only includes the visible columns.
+    pub output_visible_only: bool,
+    /// Row output is only this actual column's value (`--id-column`).
+    pub id_column: Option<usize>,
+    /// Row output fills in this template instead of an output format.
+    pub template: Option<Template>,
+    /// Whether each actual column holds only numbers, computed on load.
+    pub numeric_columns: Vec<bool>,
+    /// True until the input has been parsed in the background.
+    pub loading: bool,
+    /// Selections the confirmed output is also copied to.
+    pub copy_to: Vec<Selection>,
+    /// Short-lived status text, e.g. after yanking to the clipboard.
+    pub flash: Option<String>,
+    /// Estimated number of rows that fit in the window at once.
+    pub page_rows: usize,
+    /// Unknown until the rows have been scrolled or laid out once.
+    pub viewport: Option<ScrollViewport>,
+    /// Rows can be checked with Tab and are all output on confirm.
+    pub multi_select: bool,
+    /// Actual indices of the checked rows.
+    pub selected_rows: BTreeSet<usize>,
+    /// Actual indices of the checked columns, in Column mode.
+    pub selected_cols: BTreeSet<usize>,
+    /// What Enter does while rows can be checked.
+    pub multi_enter: MultiEnter,
+    /// Confirming a cell writes its value decoded to raw bytes (`--decode`).
+    pub decode: Option<Decode>,
+    /// Digits 1-9 select and confirm the Nth visible row (`--quick-keys`).
+    pub quick_keys: bool,
+    /// Rows with a cell equal to one of these lead the list (`--pin`).
+    pub pins: Vec<String>,
+    /// Pinned rows stay listed even when the filter excludes them.
+    pub pin_sticky: bool,
+    /// A query narrowing the rows once, within which the filter then works
+    /// (`--scope`).
+    pub scope: Option<String>,
+    /// Actual indices of the rows matching `scope`, worked out on load.
+    pub scope_indices: Vec<usize>,
+    /// Keys of recently picked rows, most recent first (`--mru`).
+    pub mru: Vec<String>,
+    /// The actual column holding a row's `--mru` key.
+    pub mru_key: usize,
+    /// Render the header row. Hiding it keeps `table.headers` for output.
+    pub show_header: bool,
+    /// Aggregate shown in the footer row (`--footer-agg`).
+    pub footer_aggregate: Aggregate,
+    /// Cancel after this long without a selection (`--timeout`).
+    pub timeout: Option<Duration>,
+    /// Key presses and filter edits push the deadline back.
+    pub timeout_reset_on_input: bool,
+    /// When the picker cancels itself, if a timeout is set.
+    pub deadline: Option<Instant>,
+    /// Set while a nested JSON cell is open in place of the table.
+    pub drilled: Option<DrillFrame>,
+    /// Set in `--null-input` mode, where the filter input adds rows.
+    pub entry: Option<DataEntry>,
+    /// The decoded input, kept so it can be read again in another format.
+    /// `None` for several files, or until the input has been parsed.
+    pub raw_input: Option<String>,
+    /// The format the table was last read as.
+    pub input_format: InputFormat,
+    pub has_header: bool,
+    pub parse_options: ParseOptions,
+    /// Confirming prints the selection and stays open for another (`--loop`).
+    pub loop_mode: bool,
+    /// Rows picked in loop mode are taken out of the list.
+    pub loop_remove: bool,
+    /// Actual indices of the rows picked so far in loop mode.
+    pub picked: BTreeSet<usize>,
+    /// How many selections have been confirmed without exiting.
+    pub confirmed: usize,
 }
 
 impl State {
@@ -34,14 +195,276 @@ impl State {
         self.filtered_indices[filtered_pos]
     }
 
+    /// The visible columns in the order they are laid out, as pairs of
+    /// visible and actual column index: reversed for right-to-left themes.
+    pub fn display_columns(&self, rtl: bool) -> Vec<(usize, usize)> {
+        let mut columns: Vec<(usize, usize)> =
+            self.visible_columns.iter().copied().enumerate().collect();
+        if rtl {
+            columns.reverse();
+        }
+        columns
+    }
+
+    /// Whether an actual column was detected as numeric.
+    pub fn column_is_numeric(&self, col: usize) -> bool {
+        self.numeric_columns.get(col).copied().unwrap_or(false)
+    }
+
+    /// The full text of the selection for the detail pane: every field of
+    /// the selected row as `column: value` lines in Row mode, or the value
+    /// of the selected cell. `None` when there is nothing selected.
+    pub fn detail_text(&self) -> Option<String> {
+        if self.header_selected || self.visible_rows() == 0 || self.num_columns() == 0 {
+            return None;
+        }
+
+        let row = self.actual_row_index(self.selected_row);
+        match self.active_mode {
+            SelectionMode::Row => {
+                let headers = self.table.headers.as_deref();
+                let lines: Vec<String> = self
+                    .visible_columns
+                    .iter()
+                    .map(|&col| {
+                        let name = headers
+                            .and_then(|h| h.get(col).cloned())
+                            .unwrap_or_else(|| col.to_string());
+                        let value = self.table.cell(row, col).unwrap_or_default();
+                        format!("{name}: {value}")
+                    })
+                    .collect();
+                Some(lines.join("\n"))
+            }
+            SelectionMode::Column | SelectionMode::Cell => {
+                let col = self.actual_col_index(self.selected_col);
+                Some(self.table.cell(row, col).unwrap_or_default().to_string())
+            }
+        }
+    }
+
+    /// The header badge for an actual column's detected type.
+    pub fn column_type_badge(&self, col: usize) -> &'static str {
+        if self.column_is_numeric(col) {
+            "#"
+        } else {
+            "A"
+        }
+    }
+
+    /// Whether navigating up from the first row may select the header row.
+    pub fn header_selectable(&self) -> bool {
+        self.active_mode == SelectionMode::Row && self.show_header && self.table.headers.is_some()
+    }
+
+    /// Whether the cursor is on this filtered row: any of its cells is
+    /// selected, which in column mode holds for every row.
+    pub fn row_is_cursor(&self, filtered_pos: usize) -> bool {
+        (0..self.num_columns()).any(|col| self.cell_is_selected(filtered_pos, col))
+    }
+
+    /// Whether this filtered row has been checked in multi-select mode.
+    pub fn row_is_checked(&self, filtered_pos: usize) -> bool {
+        self.filtered_indices
+            .get(filtered_pos)
+            .is_some_and(|idx| self.selected_rows.contains(idx))
+    }
+
+    /// Whether this visible column has been checked in Column mode.
+    pub fn col_is_checked(&self, col: usize) -> bool {
+        self.active_mode == SelectionMode::Column
+            && self.selected_cols.contains(&self.actual_col_index(col))
+    }
+
+    /// Check or uncheck the row under the cursor, or the selected column in
+    /// Column mode.
+    pub fn toggle_checked(&mut self) {
+        if !self.multi_select || self.header_selected {
+            return;
+        }
+        if self.active_mode == SelectionMode::Column {
+            if self.num_columns() > 0 {
+                let col = self.actual_col_index(self.selected_col);
+                if !self.selected_cols.remove(&col) {
+                    self.selected_cols.insert(col);
+                }
+            }
+            return;
+        }
+        if let Some(&idx) = self.filtered_indices.get(self.selected_row) {
+            if !self.selected_rows.remove(&idx) {
+                self.selected_rows.insert(idx);
+            }
+        }
+    }
+
+    /// Handle Enter under the `--multi-enter` policy: with `ToggleRow`,
+    /// plain Enter checks or unchecks the cursor row and returns true.
+    /// Otherwise Enter confirms and this returns false.
+    pub fn enter_checks_row(&mut self, control: bool) -> bool {
+        if !self.multi_select || control || self.multi_enter != MultiEnter::ToggleRow {
+            return false;
+        }
+        self.toggle_checked();
+        true
+    }
+
+    /// Select the visible row a quick key ("1" to "9") stands for. Returns
+    /// false for other keys and for digits past the last visible row.
+    pub fn quick_select(&mut self, key: &str) -> bool {
+        let Some(pos) = key.parse::<usize>().ok().filter(|n| (1..=9).contains(n)) else {
+            return false;
+        };
+        if pos > self.visible_rows() {
+            return false;
+        }
+        self.select_row(pos - 1);
+        true
+    }
+
+    /// The field-by-field diff of the two checked rows, in the active output
+    /// format, or why there is none.
+    pub fn diff_output(&self) -> Result<String, String> {
+        let rows: Vec<usize> = self.selected_rows.iter().copied().collect();
+        let [i, j] = rows[..] else {
+            return Err("Check exactly two rows to diff".to_string());
+        };
+        let changes = output::diff_rows(&self.table, &self.output_options, i, j);
+        Ok(output::format_diff(&changes, self.output_format(), &self.output_options))
+    }
+
+    /// In data entry, add the prompt line as a row (or the header row), or
+    /// store it in the cell being edited, and clear the prompt. A line that
+    /// can't be split into cells is left in the prompt.
+    pub fn submit_entry(&mut self) -> Result<(), String> {
+        let Some(entry) = &mut self.entry else {
+            return Ok(());
+        };
+
+        if let Some((row, col)) = entry.editing.take() {
+            let cells = &mut self.table.rows[row];
+            if cells.len() <= col {
+                cells.resize(col + 1, String::new());
+            }
+            cells[col] = std::mem::take(&mut self.filter_text);
+            self.entry_changed();
+            return Ok(());
+        }
+
+        let cells = entry.split(&self.filter_text)?;
+        if entry.header_pending {
+            entry.header_pending = false;
+            self.table.headers = Some(cells);
+        } else {
+            self.table.rows.push(cells);
+        }
+        self.filter_text.clear();
+        self.entry_changed();
+        self.select_row(self.visible_rows().saturating_sub(1));
+        Ok(())
+    }
+
+    /// In data entry, put the selected cell's value in the prompt to edit
+    /// it. Only works in Cell mode with a row selected.
+    pub fn begin_edit(&mut self) -> bool {
+        if self.entry.is_none()
+            || self.active_mode != SelectionMode::Cell
+            || self.visible_rows() == 0
+            || self.visible_columns.is_empty()
+        {
+            return false;
+        }
+        let row = self.actual_row_index(self.selected_row);
+        let col = self.actual_col_index(self.selected_col);
+        self.filter_text = self.table.cell(row, col).unwrap_or_default().to_string();
+        if let Some(entry) = &mut self.entry {
+            entry.editing = Some((row, col));
+        }
+        true
+    }
+
+    /// Abandon a cell edit, clearing the prompt. False if none is going on.
+    pub fn cancel_edit(&mut self) -> bool {
+        match self.entry.as_mut().and_then(|entry| entry.editing.take()) {
+            Some(_) => {
+                self.filter_text.clear();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.entry.as_ref().is_some_and(|entry| entry.editing.is_some())
+    }
+
+    /// Every row built in data entry, in the row output format, or `None`
+    /// before the first row is added.
+    pub fn entry_output(&self) -> Option<String> {
+        if self.table.is_empty() {
+            return None;
+        }
+        let rows: Vec<usize> = (0..self.table.rows.len()).collect();
+        Some(self.format_rows(&rows))
+    }
+
+    /// Refresh what depends on the table's shape after a data entry edit.
+    fn entry_changed(&mut self) {
+        let width = self.table.width();
+        self.visible_columns = (0..width).collect();
+        self.numeric_columns = (0..width)
+            .map(|c| self.table.column_is_numeric(c))
+            .collect();
+        self.update_filtered_indices();
+    }
+
+    /// Handle a confirm while no row is visible according to `policy`.
+    /// Returns whether tabsel should give up and exit.
+    pub fn confirm_empty(&mut self, policy: ConfirmEmpty) -> bool {
+        match policy {
+            ConfirmEmpty::Cancel => true,
+            ConfirmEmpty::Ignore => false,
+            ConfirmEmpty::ClearFilter => {
+                self.clear_filter();
+                false
+            }
+        }
+    }
+
+    /// Flip every row passing the filter between checked and unchecked.
+    /// Checked rows the filter hides stay checked.
+    pub fn invert_checked(&mut self) {
+        if !self.multi_select {
+            return;
+        }
+        let visible: BTreeSet<usize> = self.filtered_indices.iter().copied().collect();
+        self.selected_rows = self
+            .selected_rows
+            .symmetric_difference(&visible)
+            .copied()
+            .collect();
+    }
+
     pub fn cell_is_selected(&self, filtered_pos: usize, col: usize) -> bool {
+        if self.header_selected {
+            return false;
+        }
         match self.active_mode {
             SelectionMode::Row => filtered_pos == self.selected_row,
-            SelectionMode::Column => col == self.selected_col,
+            SelectionMode::Column => col == self.selected_col || self.col_is_checked(col),
             SelectionMode::Cell => filtered_pos == self.selected_row && col == self.selected_col,
         }
     }
 
+    /// The label of the mode badge for the active selection mode.
+    pub fn mode_badge(&self) -> &'static str {
+        match self.active_mode {
+            SelectionMode::Row => "R",
+            SelectionMode::Column => "C",
+            SelectionMode::Cell => "□",
+        }
+    }
+
     pub fn cycle_mode(&mut self) {
         if self.available_modes.len() <= 1 {
             return;
@@ -53,6 +476,9 @@ impl State {
             .unwrap_or(0);
         let next_idx = (current_idx + 1) % self.available_modes.len();
         self.active_mode = self.available_modes[next_idx];
+        if !self.header_selectable() {
+            self.header_selected = false;
+        }
     }
 
     pub fn clamp_col(&mut self) {
@@ -62,46 +488,2527 @@ impl State {
         }
     }
 
+    /// Move the selection one column on. With `flow_wrap` in Cell mode the
+    /// last column leads to the first column of the next row. Returns
+    /// whether the row changed.
+    pub fn next_col(&mut self, flow_wrap: bool) -> bool {
+        let num_cols = self.num_columns();
+        if num_cols > 0 && self.selected_col < num_cols - 1 {
+            self.selected_col += 1;
+        } else if self.flow_wraps(flow_wrap) && self.selected_row + 1 < self.visible_rows() {
+            self.selected_row += 1;
+            self.selected_col = 0;
+            return true;
+        }
+        false
+    }
+
+    /// Move the selection one column back. With `flow_wrap` in Cell mode the
+    /// first column leads to the last column of the previous row. Returns
+    /// whether the row changed.
+    pub fn prev_col(&mut self, flow_wrap: bool) -> bool {
+        if self.selected_col > 0 {
+            self.selected_col -= 1;
+        } else if self.flow_wraps(flow_wrap) && self.selected_row > 0 {
+            self.selected_row -= 1;
+            self.selected_col = self.num_columns().saturating_sub(1);
+            return true;
+        }
+        false
+    }
+
+    fn flow_wraps(&self, flow_wrap: bool) -> bool {
+        flow_wrap && self.active_mode == SelectionMode::Cell
+    }
+
     pub fn update_filtered_indices(&mut self) {
-        if self.filter_text.is_empty() {
-            self.filtered_indices = (0..self.table.rows.len()).collect();
+        // The prompt text is a row being typed, not a filter, in data entry
+        let query = if self.entry.is_some() { "" } else { &self.filter_text };
+        let config = SelectionConfig {
+            query,
+            scope: self.scope.as_ref().map(|_| self.scope_indices.as_slice()),
+            filter_mode: self.filter_mode,
+            case_fold: self.case_fold,
+            column_weights: &self.column_weights,
+            numeric_columns: &self.numeric_columns,
+            sort_keys: &self.sort_keys,
+            select: 0,
+        };
+        (self.filtered_indices, self.filter_scores) = select::filter_rows(&self.table, &config);
+        self.mru_rows();
+        self.pin_rows();
+        self.drop_picked();
+    }
+
+    /// Move rows whose key was picked recently to the front of the filtered
+    /// rows, most recent first, keeping the filter's order otherwise. Rows
+    /// sharing a key keep their order among themselves.
+    fn mru_rows(&mut self) {
+        if self.mru.is_empty() {
+            return;
+        }
+
+        let rank = |idx: usize| {
+            let key = self.table.cell(idx, self.mru_key)?;
+            self.mru.iter().position(|k| k == key)
+        };
+        let scored = !self.filter_scores.is_empty();
+        let mut entries: Vec<(usize, f64, usize)> = self
+            .filtered_indices
+            .iter()
+            .enumerate()
+            .map(|(pos, &idx)| {
+                let score = self.filter_scores.get(pos).copied().unwrap_or(0.0);
+                (idx, score, rank(idx).unwrap_or(usize::MAX))
+            })
+            .collect();
+        entries.sort_by_key(|&(_, _, rank)| rank);
+
+        self.filtered_indices = entries.iter().map(|&(idx, _, _)| idx).collect();
+        if scored {
+            self.filter_scores = entries.iter().map(|&(_, score, _)| score).collect();
+        }
+    }
+
+    /// The `--mru` keys of the rows a confirm picks (the checked rows, or
+    /// the cursor row), in the order [`State::record_mru`] takes them.
+    pub fn mru_keys(&self) -> Vec<String> {
+        // Rows of a drilled-into cell are not rows of the table
+        let row_mode = matches!(self.active_mode, SelectionMode::Row | SelectionMode::Cell);
+        if !row_mode || self.header_selected || self.visible_rows() == 0 || self.drilled.is_some()
+        {
+            return Vec::new();
+        }
+
+        let rows: Vec<usize> = if self.selected_rows.is_empty() {
+            vec![self.actual_row_index(self.selected_row)]
         } else {
-            let query = self.filter_text.to_lowercase();
-            self.filtered_indices = self
-                .table
-                .rows
+            // The first checked row ends up most recent
+            self.selected_rows.iter().rev().copied().collect()
+        };
+        rows.into_iter()
+            .filter_map(|row| self.table.cell(row, self.mru_key))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Put `keys` at the front of the `--mru` list, the last one first.
+    pub fn record_mru(&mut self, keys: &[String]) {
+        for key in keys {
+            mru::record(&mut self.mru, key);
+        }
+    }
+
+    /// With `loop_remove`, take the rows picked in loop mode out of the
+    /// filtered rows, keeping the scores aligned.
+    fn drop_picked(&mut self) {
+        if !self.loop_remove || self.picked.is_empty() {
+            return;
+        }
+
+        let scored = !self.filter_scores.is_empty();
+        let mut scores = std::mem::take(&mut self.filter_scores).into_iter();
+        let mut kept_scores = Vec::new();
+        let picked = &self.picked;
+        self.filtered_indices.retain(|idx| {
+            let score = scores.next();
+            let keep = !picked.contains(idx);
+            if keep && scored {
+                kept_scores.extend(score);
+            }
+            keep
+        });
+        self.filter_scores = kept_scores;
+    }
+
+    /// Whether an actual row has a cell equal to a `--pin` value.
+    pub fn is_pinned(&self, actual_row: usize) -> bool {
+        self.table
+            .row(actual_row)
+            .is_some_and(|row| row.iter().any(|cell| self.pins.contains(cell)))
+    }
+
+    /// Move pinned rows to the front of the filtered rows, keeping the
+    /// filter's order within the pinned and the other rows. With
+    /// `pin_sticky`, pinned rows the filter excluded are added back.
+    fn pin_rows(&mut self) {
+        if self.pins.is_empty() {
+            return;
+        }
+
+        let scored = !self.filter_scores.is_empty();
+        let mut entries: Vec<(usize, f64)> = self
+            .filtered_indices
+            .iter()
+            .enumerate()
+            .map(|(pos, &idx)| (idx, self.filter_scores.get(pos).copied().unwrap_or(0.0)))
+            .collect();
+        if self.pin_sticky {
+            let listed: BTreeSet<usize> = self.filtered_indices.iter().copied().collect();
+            let hidden = (0..self.table.rows.len())
+                .filter(|idx| !listed.contains(idx) && self.is_pinned(*idx))
+                .map(|idx| (idx, 0.0));
+            entries.extend(hidden.collect::<Vec<_>>());
+        }
+
+        let (mut pinned, rest): (Vec<_>, Vec<_>) =
+            entries.into_iter().partition(|(idx, _)| self.is_pinned(*idx));
+        pinned.extend(rest);
+        self.filtered_indices = pinned.iter().map(|(idx, _)| *idx).collect();
+        if scored {
+            self.filter_scores = pinned.iter().map(|(_, score)| *score).collect();
+        }
+    }
+
+    /// The char positions of a cell the fuzzy filter matched, for drawing
+    /// them highlighted. `None` outside fuzzy filtering, for columns the
+    /// filter skips and for cells that don't match.
+    pub fn fuzzy_match_positions(&self, row: usize, col: usize) -> Option<Vec<usize>> {
+        if self.filter_mode != FilterMode::Fuzzy
+            || self.filter_text.is_empty()
+            || self.entry.is_some()
+            || self.column_weight(col) <= 0.0
+        {
+            return None;
+        }
+        let query = self.case_fold.fold(&self.filter_text);
+        fuzzy::match_positions(self.table.cell(row, col)?, &query, self.case_fold)
+    }
+
+    /// Fuzzy scoring weight of an actual column.
+    pub fn column_weight(&self, col: usize) -> f64 {
+        self.column_weights.get(col).copied().unwrap_or(1.0)
+    }
+
+    /// Install a freshly parsed table and leave the loading state. Any filter
+    /// typed while loading is applied to the new rows.
+    pub fn load_table(&mut self, table: Table, hidden_columns: &[usize]) {
+        let num_cols = table.width();
+        self.visible_columns = (0..num_cols)
+            .filter(|c| !hidden_columns.contains(c))
+            .collect();
+        let shown = self.column_limit.unwrap_or(usize::MAX);
+        self.more_columns = self.visible_columns.len().saturating_sub(shown);
+        self.visible_columns.truncate(shown);
+        // Column and Cell selections need a column to point at, except in
+        // data entry where the columns are yet to be typed
+        if let Some(modes) = self.configured_modes.take() {
+            if !modes.contains(&self.active_mode) {
+                self.active_mode = modes.first().copied().unwrap_or_default();
+            }
+            self.available_modes = modes;
+        }
+        if self.visible_columns.is_empty() && self.entry.is_none() {
+            let modes = std::mem::replace(&mut self.available_modes, vec![SelectionMode::Row]);
+            self.configured_modes = Some(modes);
+            self.active_mode = SelectionMode::Row;
+        }
+        self.numeric_columns = (0..num_cols)
+            .map(|c| table.column_is_numeric(c))
+            .collect();
+        self.table = table;
+        self.loading = false;
+        self.selected_row = 0;
+        self.update_scope();
+        self.update_filtered_indices();
+    }
+
+    /// Match the `--scope` query against every row, in the filter mode.
+    fn update_scope(&mut self) {
+        let Some(scope) = &self.scope else {
+            return;
+        };
+        let config = SelectionConfig {
+            query: scope,
+            filter_mode: self.filter_mode,
+            case_fold: self.case_fold,
+            column_weights: &self.column_weights,
+            ..SelectionConfig::default()
+        };
+        let (mut rows, _) = select::filter_rows(&self.table, &config);
+        rows.sort_unstable();
+        self.scope_indices = rows;
+    }
+
+    /// Drop the `--scope`, so the filter works over every row again.
+    pub fn clear_scope(&mut self) {
+        self.scope = None;
+        self.scope_indices.clear();
+        self.update_filtered_indices();
+        self.select_row(0);
+    }
+
+    /// Parse the raw input again as `format`, splitting on runs of spaces
+    /// and tabs for [`InputFormat::AnyDelimiter`] unless delimiters were
+    /// given.
+    pub fn parse_as(&self, format: InputFormat) -> Result<Table, String> {
+        let Some(raw) = &self.raw_input else {
+            return Err("Only stdin, a command or a single file can be read again".to_string());
+        };
+        let mut options = self.parse_options.clone();
+        if format == InputFormat::AnyDelimiter && options.delimiters.is_empty() {
+            options.delimiters = vec![' ', '\t'];
+            options.collapse_delimiters = true;
+        }
+        parse::parse_string(raw, format, self.has_header, &options)
+            .map_err(|err| format!("Not valid {}: {err}", format.name()))
+    }
+
+    /// Select a filtered row, clamped to the rows currently visible.
+    pub fn select_row(&mut self, filtered_pos: usize) {
+        self.selected_row = filtered_pos.min(self.visible_rows().saturating_sub(1));
+        self.header_selected = false;
+    }
+
+    /// Select a filtered row and the visible column showing actual column
+    /// `col`, as a click on that cell does. The column is kept if `col` isn't
+    /// shown.
+    pub fn select_cell(&mut self, filtered_pos: usize, col: usize) {
+        self.select_row(filtered_pos);
+        if let Some(vis_col) = self.visible_columns.iter().position(|&c| c == col) {
+            self.selected_col = vis_col;
+        }
+    }
+
+    /// The output format of the active selection mode, unless a confirm key
+    /// overrides it.
+    pub fn output_format(&self) -> OutputFormat {
+        self.format_override
+            .unwrap_or_else(|| self.output_formats.get(self.active_mode))
+    }
+
+    /// Confirm in `--loop` mode: the selection's output, like
+    /// `selection_output`, after which the picked rows are remembered (and
+    /// hidden with `loop_remove`) and the checks are cleared for the next
+    /// pick.
+    pub fn loop_confirm(&mut self) -> Option<String> {
+        let output = self.selection_output()?;
+        self.confirmed += 1;
+
+        // Rows of a drilled-into cell are not rows of the table
+        let row_mode = matches!(self.active_mode, SelectionMode::Row | SelectionMode::Cell);
+        if row_mode && !self.header_selected && self.drilled.is_none() {
+            if self.selected_rows.is_empty() {
+                self.picked.insert(self.actual_row_index(self.selected_row));
+            } else {
+                self.picked.append(&mut self.selected_rows);
+            }
+        }
+        self.selected_rows.clear();
+
+        if self.loop_remove {
+            self.update_filtered_indices();
+            self.selected_row = self.selected_row.min(self.visible_rows().saturating_sub(1));
+        }
+        Some(output)
+    }
+
+    /// Whether there is anything to confirm: a visible row, or in a table
+    /// with a header but no rows, a column or the header row itself.
+    fn has_selection(&self) -> bool {
+        let header_only = self.table.is_empty() && self.table.headers.is_some();
+        let picks_header = self.active_mode == SelectionMode::Column || self.header_selected;
+        self.visible_rows() > 0 || (header_only && picks_header && self.num_columns() > 0)
+    }
+
+    /// Shown in place of the rows when there are none to show.
+    pub fn empty_message(&self) -> Option<&'static str> {
+        if self.loading || self.entry.is_some() || self.visible_rows() > 0 {
+            return None;
+        }
+        Some(if self.table.is_empty() { "No rows" } else { "No matches" })
+    }
+
+    /// The text confirming the current selection would output, or `None`
+    /// when there is nothing to select.
+    pub fn selection_output(&self) -> Option<String> {
+        if !self.has_selection() {
+            return None;
+        }
+
+        let fmt = self.output_format();
+        let opts = &self.output_options;
+        if fmt == OutputFormat::Summary {
+            return Some(output::format_summary(&self.summary_values(), opts));
+        }
+
+        let table = &self.table;
+        let result = match self.active_mode {
+            SelectionMode::Row if self.header_selected && self.output_visible_only => {
+                output::format_header(&self.visible_projection(&[]), fmt, opts)
+            }
+            SelectionMode::Row if self.header_selected => output::format_header(table, fmt, opts),
+            // Checked rows are output in input order, one per line
+            SelectionMode::Row if !self.selected_rows.is_empty() => {
+                let rows: Vec<usize> = self.selected_rows.iter().copied().collect();
+                self.format_rows(&rows)
+            }
+            SelectionMode::Row => self.format_rows(&[self.actual_row_index(self.selected_row)]),
+            // Checked columns are output together over the filtered rows
+            SelectionMode::Column if !self.selected_cols.is_empty() => {
+                let cols: Vec<usize> = self.selected_cols.iter().copied().collect();
+                let projected = self.projection(&cols, &self.filtered_indices);
+                output::format_table(&projected, fmt, opts)
+            }
+            SelectionMode::Column => {
+                let actual_col = self.actual_col_index(self.selected_col);
+                output::format_column(table, fmt, opts, actual_col)
+            }
+            SelectionMode::Cell => {
+                let actual_idx = self.actual_row_index(self.selected_row);
+                let actual_col = self.actual_col_index(self.selected_col);
+                output::format_cell(table, fmt, opts, actual_idx, actual_col)
+            }
+        };
+        Some(result)
+    }
+
+    /// The selected cell decoded by `--decode`, in Cell mode. `None` without
+    /// `--decode` or a cell to decode, `Err` when the cell doesn't decode.
+    pub fn decoded_cell(&self) -> Option<anyhow::Result<Vec<u8>>> {
+        let decode = self.decode?;
+        if self.active_mode != SelectionMode::Cell || !self.has_selection() {
+            return None;
+        }
+        let row = self.actual_row_index(self.selected_row);
+        let col = self.actual_col_index(self.selected_col);
+        Some(decode.apply(self.table.cell(row, col).unwrap_or_default()))
+    }
+
+    /// The values `summary` output lists: a label for each checked row (or
+    /// the cursor row), the column's name or the cell's value. A row's label
+    /// is its `--template` text, or else its id column or first shown cell.
+    fn summary_values(&self) -> Vec<String> {
+        let label_col = self
+            .id_column
+            .or(self.visible_columns.first().copied())
+            .unwrap_or(0);
+        let label = |row: usize| match &self.template {
+            Some(template) => {
+                let cells = self.table.row(row).map_or(&[][..], Vec::as_slice);
+                let headers = self.table.headers.as_deref();
+                template.fill(cells, headers, true).unwrap_or_default()
+            }
+            None => self.table.cell(row, label_col).unwrap_or_default().to_string(),
+        };
+        let opts = &self.output_options;
+        let column = |col| output::format_column(&self.table, OutputFormat::Plain, opts, col);
+
+        match self.active_mode {
+            SelectionMode::Row if self.header_selected => {
+                let headers = self.table.headers.as_deref().unwrap_or_default();
+                vec![headers.get(label_col).cloned().unwrap_or_default()]
+            }
+            SelectionMode::Row if !self.selected_rows.is_empty() => {
+                self.selected_rows.iter().map(|&row| label(row)).collect()
+            }
+            SelectionMode::Row => vec![label(self.actual_row_index(self.selected_row))],
+            SelectionMode::Column if !self.selected_cols.is_empty() => self
+                .selected_cols
+                .iter()
+                .map(|&col| column(col))
+                .collect(),
+            SelectionMode::Column => vec![column(self.actual_col_index(self.selected_col))],
+            SelectionMode::Cell => {
+                let row = self.actual_row_index(self.selected_row);
+                let col = self.actual_col_index(self.selected_col);
+                vec![self.table.cell(row, col).unwrap_or_default().to_string()]
+            }
+        }
+    }
+
+    /// The position of the current selection in the input, as written to
+    /// `--index-fd`: the actual row index, one per line for checked rows,
+    /// "row col" in Cell mode, the column index (one per line for checked
+    /// columns) in Column mode and "header" for the header row, counted from
+    /// 1 with `--one-based`. `None` when there is nothing to select.
+    pub fn index_payload(&self) -> Option<String> {
+        if !self.has_selection() {
+            return None;
+        }
+
+        let index = |idx: usize| self.output_options.index(idx).to_string();
+
+        let payload = match self.active_mode {
+            SelectionMode::Row if self.header_selected => "header".to_string(),
+            SelectionMode::Row if !self.selected_rows.is_empty() => self
+                .selected_rows
+                .iter()
+                .map(|&row| index(row))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            SelectionMode::Row => index(self.actual_row_index(self.selected_row)),
+            SelectionMode::Column if !self.selected_cols.is_empty() => self
+                .selected_cols
                 .iter()
-                .enumerate()
-                .filter(|(_, row)| {
-                    row.iter()
-                        .any(|cell| cell.to_lowercase().contains(&query))
+                .map(|&col| index(col))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            SelectionMode::Column => index(self.actual_col_index(self.selected_col)),
+            SelectionMode::Cell => format!(
+                "{} {}",
+                index(self.actual_row_index(self.selected_row)),
+                index(self.actual_col_index(self.selected_col))
+            ),
+        };
+        Some(payload)
+    }
+
+    /// Format actual rows for Row mode output, one per line. A `--template`
+    /// replaces the output format, unless a confirm key overrides it.
+    /// Otherwise rows are cut down to the id column, or with
+    /// `output_visible_only` to the visible columns, first. Several rows of
+    /// CSV start with the header record if `multi_header` is set.
+    fn format_rows(&self, rows: &[usize]) -> String {
+        if let Some(template) = self.template.as_ref().filter(|_| self.format_override.is_none()) {
+            // Placeholders were checked against the table on load; what is
+            // still missing is a short ragged row, left empty
+            let headers = self.table.headers.as_deref();
+            return rows
+                .iter()
+                .map(|&idx| {
+                    let cells = self.table.row(idx).map_or(&[][..], Vec::as_slice);
+                    template.fill(cells, headers, true).unwrap_or_default()
                 })
-                .map(|(idx, _)| idx)
-                .collect();
+                .collect::<Vec<_>>()
+                .join("\n");
+        }
+
+        let fmt = self.output_format();
+        let opts = &self.output_options;
+        let projected = match self.id_column {
+            Some(col) => Some(self.projection(&[col], rows)),
+            None if self.output_visible_only => Some(self.visible_projection(rows)),
+            None => None,
+        };
+        let mut formatted: Vec<String> = if let Some(projected) = &projected {
+            (0..rows.len())
+                .map(|idx| output::format_row(projected, fmt, opts, idx))
+                .collect()
+        } else {
+            rows.iter()
+                .map(|&idx| output::format_row(&self.table, fmt, opts, idx))
+                .collect()
+        };
+
+        let table = projected.as_ref().unwrap_or(&self.table);
+        let with_header = opts.multi_header && rows.len() > 1 && fmt == OutputFormat::Csv;
+        if with_header && table.headers.is_some() {
+            formatted.insert(0, output::format_header(table, fmt, opts));
         }
+        formatted.join("\n")
     }
 
-    pub fn init_filtered_indices(&mut self) {
-        self.filtered_indices = (0..self.table.rows.len()).collect();
+    /// A table of the given actual rows holding only the visible columns.
+    fn visible_projection(&self, rows: &[usize]) -> Table {
+        self.projection(&self.visible_columns, rows)
     }
-}
 
-impl Default for State {
-    fn default() -> Self {
-        State {
-            selected_row: 0,
-            selected_col: 0,
-            active_mode: SelectionMode::Row,
-            available_modes: vec![SelectionMode::Row],
-            table: Table {
-                headers: None,
-                rows: Vec::new(),
-            },
-            filter_enabled: true,
-            filter_text: String::new(),
-            filtered_indices: Vec::new(),
-            output_format: OutputFormat::Plain,
-            visible_columns: Vec::new(),
+    /// A table of the given actual rows holding only the given actual columns.
+    fn projection(&self, columns: &[usize], rows: &[usize]) -> Table {
+        let project = |cells: &[String]| -> Vec<String> {
+            columns
+                .iter()
+                .map(|&col| cells.get(col).cloned().unwrap_or_default())
+                .collect()
+        };
+        Table::new(
+            self.table.headers.as_deref().map(project),
+            rows.iter()
+                .map(|&idx| project(self.table.row(idx).map_or(&[], Vec::as_slice)))
+                .collect(),
+        )
+    }
+
+    /// Aggregate of a numeric actual column over the rows passing the
+    /// filter. `None` for non-numeric columns or when no row has a value.
+    pub fn column_aggregate(&self, col: usize, aggregate: Aggregate) -> Option<f64> {
+        if !self.column_is_numeric(col) {
+            return None;
+        }
+        let values: Vec<f64> = self
+            .filtered_indices
+            .iter()
+            .filter_map(|&idx| self.table.cell(idx, col))
+            .filter_map(|cell| cell.trim().parse().ok())
+            .collect();
+        aggregate.apply(&values)
+    }
+
+    /// The footer text for an actual column: its aggregate, or blank.
+    pub fn footer_label(&self, col: usize) -> String {
+        match self.column_aggregate(col, self.footer_aggregate) {
+            Some(value) if value.fract() == 0.0 => format!("{value:.0}"),
+            Some(value) => format!("{value:.2}"),
+            None => String::new(),
+        }
+    }
+
+    /// The "+K more" note for the last header cell when `--limit-columns`
+    /// cut columns off.
+    pub fn more_columns_label(&self) -> Option<String> {
+        (self.more_columns > 0).then(|| format!("+{} more", self.more_columns))
+    }
+
+    /// The text yanked for the current selection without confirming: the
+    /// formatted row in Row mode, the visible values of the selected column in
+    /// Column mode and the raw cell value in Cell mode.
+    pub fn yank_text(&self) -> Option<String> {
+        if self.visible_rows() == 0 {
+            return None;
+        }
+
+        let fmt = self.output_format();
+        let opts = &self.output_options;
+        let table = &self.table;
+        let result = match self.active_mode {
+            SelectionMode::Row => return self.selection_output(),
+            SelectionMode::Column => {
+                let actual_col = self.actual_col_index(self.selected_col);
+                output::format_column_values(table, fmt, opts, actual_col, &self.filtered_indices)
+            }
+            SelectionMode::Cell => {
+                let actual_idx = self.actual_row_index(self.selected_row);
+                let actual_col = self.actual_col_index(self.selected_col);
+                table.cell(actual_idx, actual_col).unwrap_or_default().to_string()
+            }
+        };
+        Some(result)
+    }
+
+    /// The scroll offset that brings the selected row into view, or `None`
+    /// when it is already fully visible or the viewport is unknown.
+    ///
+    /// Row heights aren't measured, so every row (the header included) is
+    /// assumed to take an equal share of the content height. Rows that wrap
+    /// onto several lines can make the estimate drift slightly.
+    pub fn edge_scroll_offset(&self, row_height: Option<f32>) -> Option<f32> {
+        let viewport = self.viewport?;
+        let (top, bottom) = match row_height {
+            Some(height) => self.fixed_row_span(&viewport, height),
+            None => self.estimated_row_span(&viewport)?,
+        };
+
+        if top < viewport.offset_y {
+            Some(top)
+        } else if bottom > viewport.offset_y + viewport.height {
+            Some(bottom - viewport.height)
+        } else {
+            None
+        }
+    }
+
+    /// Where the selected row (or header) starts and ends in the scrollable
+    /// content when every data row is `height` tall. Whatever the rows
+    /// don't take up is the header above them.
+    fn fixed_row_span(&self, viewport: &ScrollViewport, height: f32) -> (f32, f32) {
+        let header = (viewport.content_height - self.visible_rows() as f32 * height).max(0.0);
+        if self.header_selected {
+            (0.0, header)
+        } else {
+            let top = header + self.selected_row as f32 * height;
+            (top, top + height)
         }
     }
+
+    /// Like `fixed_row_span`, but with rows of unknown height taken to be
+    /// the content height shared evenly between the header and the rows.
+    fn estimated_row_span(&self, viewport: &ScrollViewport) -> Option<(f32, f32)> {
+        let header_rows = usize::from(self.table.headers.is_some());
+        let total = self.visible_rows() + header_rows;
+        if total == 0 {
+            return None;
+        }
+
+        let row_height = viewport.content_height / total as f32;
+        let position = if self.header_selected {
+            0
+        } else {
+            self.selected_row + header_rows
+        };
+        let top = position as f32 * row_height;
+        Some((top, top + row_height))
+    }
+
+    /// The scroll offset placing the selected row proportionally in the
+    /// viewport, its position in the list matching its position on screen,
+    /// with every data row `row_height` tall. `None` until the viewport is
+    /// known.
+    pub fn proportional_scroll_offset(&self, row_height: f32) -> Option<f32> {
+        let viewport = self.viewport?;
+        let (top, bottom) = self.fixed_row_span(&viewport, row_height);
+        let total = self.visible_rows();
+        let fraction = if total <= 1 || self.header_selected {
+            0.0
+        } else {
+            self.selected_row as f32 / (total - 1) as f32
+        };
+        let y = top - fraction * (viewport.height - (bottom - top));
+        Some(y.clamp(0.0, (viewport.content_height - viewport.height).max(0.0)))
+    }
+
+    /// "row X of Y" for the selected row, or `None` while the visible rows
+    /// fit on a single page.
+    pub fn position_label(&self) -> Option<String> {
+        let total = self.visible_rows();
+        if total <= self.page_rows {
+            return None;
+        }
+        Some(format!("row {} of {total}", self.selected_row + 1))
+    }
+
+    /// Every row passing the filter as one JSON array, holding only the
+    /// visible columns in display order (`--output json-rows`).
+    pub fn json_rows_output(&self) -> String {
+        output::format_json_rows(&self.visible_projection(&self.filtered_indices))
+    }
+
+    /// A human-readable summary of the filter and selection, for `--dry-run`.
+    pub fn describe_selection(&self) -> String {
+        let filter = if self.filter_text.is_empty() {
+            "no filter".to_string()
+        } else {
+            format!("{} filter {:?}", self.filter_mode.name(), self.filter_text)
+        };
+        let matched = format!(
+            "{filter} matched {} of {} rows",
+            self.visible_rows(),
+            self.table.rows.len()
+        );
+
+        if !self.has_selection() {
+            return format!("{matched}; nothing would be selected");
+        }
+
+        // Only read while a row is visible; a header-only table has none
+        let row = || self.actual_row_index(self.selected_row);
+        let col = self.actual_col_index(self.selected_col);
+        let column = self
+            .table
+            .headers
+            .as_ref()
+            .and_then(|h| h.get(col))
+            .map_or_else(|| format!("column {col}"), |name| format!("column {name:?}"));
+        let target = match self.active_mode {
+            SelectionMode::Row if self.header_selected => "the header row".to_string(),
+            SelectionMode::Row => format!("row {}", row()),
+            SelectionMode::Column => column,
+            SelectionMode::Cell => format!("row {}, {column}", row()),
+        };
+
+        format!("{matched}; would select {target}")
+    }
+
+    /// Replace the filter text, re-filter and go back to the first row.
+    pub fn set_filter_text(&mut self, value: String) {
+        self.filter_text = value;
+        self.refilter();
+    }
+
+    /// Empty the filter (Ctrl+U), showing every row again from the first.
+    pub fn clear_filter(&mut self) {
+        self.set_filter_text(String::new());
+    }
+
+    /// Delete the word before the end of the filter text, along with any
+    /// whitespace after it (Ctrl+W).
+    pub fn delete_filter_word(&mut self) {
+        let value = delete_word_back(&self.filter_text).to_string();
+        self.set_filter_text(value);
+    }
+
+    /// Switch to the next filter mode and re-filter.
+    pub fn cycle_filter_mode(&mut self) {
+        self.filter_mode = self.filter_mode.next();
+        self.refilter();
+    }
+
+    /// The filter text extended to the longest common prefix of what follows
+    /// it in the matching cells, or `None` if the matches diverge right away.
+    /// A single match completes to the end of its cell. Only the given actual
+    /// column is considered if there is one, otherwise every visible column.
+    /// Matching is case-insensitive, so the extension is lowercased.
+    pub fn filter_completion(&self, column: Option<usize>) -> Option<String> {
+        let query = self.filter_text.to_lowercase();
+        let columns = match &column {
+            Some(col) => std::slice::from_ref(col),
+            None => self.visible_columns.as_slice(),
+        };
+        let mut prefix: Option<String> = None;
+        for &row in &self.filtered_indices {
+            for &col in columns {
+                let cell = self.table.cell(row, col).unwrap_or_default().to_lowercase();
+                let Some(start) = cell.find(&query) else {
+                    continue;
+                };
+                let rest = &cell[start + query.len()..];
+                prefix = Some(match prefix {
+                    None => rest.to_string(),
+                    Some(prefix) => common_prefix(&prefix, rest).to_string(),
+                });
+            }
+        }
+        prefix
+            .filter(|prefix| !prefix.is_empty())
+            .map(|prefix| format!("{}{prefix}", self.filter_text))
+    }
+
+    /// Open the selected cell as a table of its own if it holds a nested JSON
+    /// object or array (Cell mode only, one level deep). Returns whether it
+    /// did.
+    pub fn drill_down(&mut self) -> bool {
+        if self.active_mode != SelectionMode::Cell
+            || self.drilled.is_some()
+            || self.entry.is_some()
+            || self.visible_rows() == 0
+        {
+            return false;
+        }
+        let row = self.actual_row_index(self.selected_row);
+        let col = self.actual_col_index(self.selected_col);
+        let Some(nested) = self.table.cell(row, col).and_then(parse::parse_nested) else {
+            return false;
+        };
+
+        let key = self.table.headers.as_ref().and_then(|h| h.get(col)).cloned();
+        self.drilled = Some(DrillFrame {
+            breadcrumb: format!("{}[{row}]", key.unwrap_or_else(|| col.to_string())),
+            table: std::mem::replace(&mut self.table, Table::new(None, Vec::new())),
+            visible_columns: std::mem::take(&mut self.visible_columns),
+            more_columns: self.more_columns,
+            numeric_columns: std::mem::take(&mut self.numeric_columns),
+            column_weights: std::mem::take(&mut self.column_weights),
+            sort_keys: std::mem::take(&mut self.sort_keys),
+            id_column: self.id_column.take(),
+            available_modes: self.available_modes.clone(),
+            active_mode: self.active_mode,
+            filter_text: std::mem::take(&mut self.filter_text),
+            selected_rows: std::mem::take(&mut self.selected_rows),
+            selected_cols: std::mem::take(&mut self.selected_cols),
+            selected_row: self.selected_row,
+            selected_col: self.selected_col,
+        });
+        self.header_selected = false;
+        self.selected_col = 0;
+        self.load_table(nested, &[]);
+        true
+    }
+
+    /// Go back to the table a nested cell was opened from, with its filter
+    /// and selection as they were. Returns whether there was one.
+    pub fn drill_up(&mut self) -> bool {
+        let Some(frame) = self.drilled.take() else {
+            return false;
+        };
+        self.table = frame.table;
+        self.visible_columns = frame.visible_columns;
+        self.more_columns = frame.more_columns;
+        self.numeric_columns = frame.numeric_columns;
+        self.column_weights = frame.column_weights;
+        self.sort_keys = frame.sort_keys;
+        self.id_column = frame.id_column;
+        self.available_modes = frame.available_modes;
+        self.active_mode = frame.active_mode;
+        self.filter_text = frame.filter_text;
+        self.selected_rows = frame.selected_rows;
+        self.selected_cols = frame.selected_cols;
+        self.header_selected = false;
+        self.update_filtered_indices();
+        self.selected_row = frame.selected_row;
+        self.selected_col = frame.selected_col;
+        true
+    }
+
+    /// Re-filter after the filter text or how it matches changed, and go
+    /// back to the first row since the old position may no longer exist.
+    fn refilter(&mut self) {
+        self.update_filtered_indices();
+        self.selected_row = 0;
+        self.header_selected = false;
+    }
+
+    /// Start the `--timeout` countdown from `now`.
+    pub fn start_timeout(&mut self, now: Instant) {
+        self.deadline = self.timeout.map(|timeout| now + timeout);
+    }
+
+    /// Restart the countdown after user input, if configured to.
+    pub fn note_input(&mut self, now: Instant) {
+        if self.timeout_reset_on_input {
+            self.start_timeout(now);
+        }
+    }
+
+    pub fn timed_out(&self, now: Instant) -> bool {
+        self.deadline.is_some_and(|deadline| now >= deadline)
+    }
+
+    /// Show or hide the header row, moving a selection off a hidden header.
+    pub fn toggle_header(&mut self) {
+        self.show_header = !self.show_header;
+        if !self.show_header {
+            self.header_selected = false;
+        }
+    }
+
+    pub fn init_filtered_indices(&mut self) {
+        self.filtered_indices = (0..self.table.rows.len()).collect();
+    }
+}
+
+/// The text shown for a cell. Empty cells show the theme placeholder; the
+/// table itself is untouched, so filtering and output still see "".
+pub fn cell_display_text<'a>(value: &'a str, empty_placeholder: &'a str) -> &'a str {
+    if value.is_empty() {
+        empty_placeholder
+    } else {
+        value
+    }
+}
+
+/// Split `value` into runs of plain and highlighted text, with `true` marking
+/// the runs made of the chars at `positions` (sorted char positions, as from
+/// the fuzzy matcher).
+pub fn highlight_runs(value: &str, positions: &[usize]) -> Vec<(String, bool)> {
+    let mut runs: Vec<(String, bool)> = Vec::new();
+    let mut marks = positions.iter().peekable();
+    for (pos, c) in value.chars().enumerate() {
+        let highlighted = marks.next_if_eq(&&pos).is_some();
+        match runs.last_mut() {
+            Some((run, lit)) if *lit == highlighted => run.push(c),
+            _ => runs.push((c.to_string(), highlighted)),
+        }
+    }
+    runs
+}
+
+/// `value` without its last whitespace-separated word and trailing whitespace.
+fn delete_word_back(value: &str) -> &str {
+    let trimmed = value.trim_end();
+    let word_start = trimmed
+        .rfind(char::is_whitespace)
+        .map_or(0, |idx| idx + trimmed[idx..].chars().next().map_or(1, char::len_utf8));
+    &trimmed[..word_start]
+}
+
+/// The longest common prefix of two strings, on character boundaries.
+fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+    let len = a
+        .char_indices()
+        .zip(b.chars())
+        .find(|((_, x), y)| x != y)
+        .map_or_else(|| a.len().min(b.len()), |((idx, _), _)| idx);
+    &a[..len]
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            selected_row: 0,
+            header_selected: false,
+            selected_col: 0,
+            active_mode: SelectionMode::Row,
+            available_modes: vec![SelectionMode::Row],
+            configured_modes: None,
+            table: Table::new(None, Vec::new()),
+            filter_enabled: true,
+            focus_filter: true,
+            filter_text: String::new(),
+            filter_mode: FilterMode::default(),
+            case_fold: CaseFold::default(),
+            filtered_indices: Vec::new(),
+            filter_scores: Vec::new(),
+            column_weights: Vec::new(),
+            sort_keys: Vec::new(),
+            output_formats: ModeFormats::default(),
+            format_override: None,
+            output_options: OutputOptions::default(),
+            visible_columns: Vec::new(),
+            column_limit: None,
+            more_columns: 0,
+            output_visible_only: false,
+            id_column: None,
+            numeric_columns: Vec::new(),
+            loading: false,
+            copy_to: Vec::new(),
+            flash: None,
+            page_rows: usize::MAX,
+            viewport: None,
+            multi_select: false,
+            multi_enter: MultiEnter::default(),
+            decode: None,
+            selected_rows: BTreeSet::new(),
+            selected_cols: BTreeSet::new(),
+            quick_keys: false,
+            show_header: true,
+            footer_aggregate: Aggregate::default(),
+            timeout: None,
+            timeout_reset_on_input: false,
+            deadline: None,
+            drilled: None,
+            pins: Vec::new(),
+            pin_sticky: false,
+            scope: None,
+            scope_indices: Vec::new(),
+            mru: Vec::new(),
+            mru_key: 0,
+            entry: None,
+            raw_input: None,
+            input_format: InputFormat::default(),
+            has_header: true,
+            parse_options: ParseOptions::default(),
+            template: None,
+            loop_mode: false,
+            loop_remove: false,
+            picked: BTreeSet::new(),
+            confirmed: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::RowSlice;
+    use pretty_assertions::assert_eq;
+
+    fn sample_table() -> Table {
+        Table {
+            headers: Some(vec!["name".to_string(), "age".to_string()]),
+            rows: vec![
+                vec!["Alice".to_string(), "30".to_string()],
+                vec!["Bob".to_string(), "25".to_string()],
+                vec!["Carol".to_string(), "35".to_string()],
+            ],
+        }
+    }
+
+    #[test]
+    fn load_table_applies_filter_typed_while_loading() {
+        let mut state = State {
+            loading: true,
+            ..Default::default()
+        };
+        state.filter_text = "bo".to_string();
+        state.update_filtered_indices();
+        assert_eq!(state.visible_rows(), 0);
+
+        state.load_table(sample_table(), &[1]);
+
+        assert!(!state.loading);
+        assert_eq!(state.visible_columns, vec![0]);
+        assert_eq!(state.filtered_indices, vec![1]);
+    }
+
+    fn wide_table() -> Table {
+        Table::from_records(
+            Some(vec!["a".into(), "b".into(), "c".into(), "d".into()]),
+            vec![vec!["1", "2", "3", "4"]],
+        )
+    }
+
+    #[test]
+    fn column_limit_caps_visible_columns_after_hiding() {
+        let mut state = State {
+            column_limit: Some(2),
+            ..Default::default()
+        };
+        state.load_table(wide_table(), &[0]);
+
+        assert_eq!(state.visible_columns, vec![1, 2]);
+        assert_eq!(state.more_columns_label(), Some("+1 more".to_string()));
+        assert_eq!(state.selection_output(), Some("1,2,3,4".to_string()));
+    }
+
+    #[test]
+    fn template_replaces_the_row_output_format() {
+        let mut state = State {
+            multi_select: true,
+            template: Some(Template::parse("{name} ({1})").unwrap()),
+            output_formats: ModeFormats::all(OutputFormat::Json),
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+        assert_eq!(state.selection_output(), Some("Alice (30)".to_string()));
+
+        state.toggle_checked();
+        state.select_row(1);
+        state.toggle_checked();
+        assert_eq!(state.selection_output(), Some("Alice (30)\nBob (25)".to_string()));
+    }
+
+    #[test]
+    fn format_override_replaces_the_mode_format_and_template() {
+        let mut state = State {
+            template: Some(Template::parse("{name} ({1})").unwrap()),
+            output_formats: ModeFormats::all(OutputFormat::Plain),
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+        state.select_row(1);
+
+        state.format_override = Some(OutputFormat::Json);
+        assert_eq!(
+            state.selection_output(),
+            Some(r#"{"name":"Bob","age":"25"}"#.to_string())
+        );
+        state.format_override = Some(OutputFormat::Csv);
+        assert_eq!(state.selection_output(), Some("Bob,25".to_string()));
+        state.format_override = None;
+        assert_eq!(state.selection_output(), Some("Bob (25)".to_string()));
+    }
+
+    #[test]
+    fn id_column_outputs_only_that_value() {
+        let mut state = State {
+            id_column: Some(1),
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[1]);
+        state.select_row(1);
+        assert_eq!(state.selection_output(), Some("25".to_string()));
+
+        state.output_formats = ModeFormats::all(OutputFormat::Json);
+        assert_eq!(state.selection_output(), Some(r#"{"age":"25"}"#.to_string()));
+    }
+
+    #[test]
+    fn id_column_by_index_without_headers() {
+        let mut state = State {
+            id_column: Some(0),
+            multi_select: true,
+            ..Default::default()
+        };
+        state.load_table(Table::new(None, sample_table().rows), &[]);
+        state.toggle_checked();
+        state.select_row(2);
+        state.toggle_checked();
+        assert_eq!(state.selection_output(), Some("Alice\nCarol".to_string()));
+    }
+
+    #[test]
+    fn json_rows_follow_the_filter_and_skip_hidden_columns() {
+        let mut state = State {
+            filter_text: "o".to_string(),
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[1]);
+
+        assert_eq!(state.json_rows_output(), r#"[{"name":"Bob"},{"name":"Carol"}]"#);
+    }
+
+    #[test]
+    fn right_to_left_display_keeps_logical_selection() {
+        let mut state = State {
+            active_mode: SelectionMode::Cell,
+            ..Default::default()
+        };
+        state.load_table(wide_table(), &[1]);
+
+        let display = state.display_columns(true);
+        assert_eq!(display, vec![(2, 3), (1, 2), (0, 0)]);
+
+        // The rightmost column is the first visible one, "a"
+        let (rightmost, actual) = display[2];
+        assert!(state.cell_is_selected(0, rightmost));
+        assert_eq!(state.selection_output(), state.table.cell(0, actual).map(str::to_string));
+    }
+
+    #[test]
+    fn column_aggregates_follow_the_filter() {
+        let mut state = State::default();
+        state.load_table(sample_table(), &[]);
+
+        assert_eq!(state.column_aggregate(1, Aggregate::Sum), Some(90.0));
+        assert_eq!(state.column_aggregate(1, Aggregate::Avg), Some(30.0));
+        assert_eq!(state.column_aggregate(1, Aggregate::Min), Some(25.0));
+        assert_eq!(state.column_aggregate(1, Aggregate::Max), Some(35.0));
+        assert_eq!(state.column_aggregate(0, Aggregate::Sum), None);
+
+        state.set_filter_text("o".to_string());
+        assert_eq!(state.column_aggregate(1, Aggregate::Sum), Some(60.0));
+        state.set_filter_text("zzz".to_string());
+        assert_eq!(state.column_aggregate(1, Aggregate::Sum), None);
+    }
+
+    #[test]
+    fn footer_labels() {
+        let mut state = State {
+            footer_aggregate: Aggregate::Avg,
+            ..Default::default()
+        };
+        state.load_table(
+            Table::from_records(None, vec![vec!["a", "1"], vec!["b", "2"], vec!["c", ""]]),
+            &[],
+        );
+
+        assert_eq!(state.footer_label(0), "");
+        assert_eq!(state.footer_label(1), "1.50");
+    }
+
+    #[test]
+    fn summary_output_names_the_selection() {
+        let mut state = State {
+            available_modes: vec![SelectionMode::Row, SelectionMode::Cell],
+            output_formats: ModeFormats::all(OutputFormat::Summary),
+            multi_select: true,
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+
+        assert_eq!(state.selection_output(), Some("Selected: Alice".to_string()));
+        state.selected_rows.extend([0, 2]);
+        assert_eq!(
+            state.selection_output(),
+            Some("2 rows selected: Alice, Carol".to_string())
+        );
+
+        state.cycle_mode();
+        state.selected_col = 1;
+        assert_eq!(state.selection_output(), Some("Selected: 30".to_string()));
+
+        state.set_filter_text("zzz".to_string());
+        assert_eq!(state.selection_output(), None);
+    }
+
+    #[test]
+    fn confirm_uses_the_active_modes_format() {
+        let mut state = State {
+            available_modes: vec![SelectionMode::Row, SelectionMode::Cell],
+            output_formats: ModeFormats::parse("row=json,cell=plain").unwrap(),
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+
+        assert_eq!(
+            state.selection_output(),
+            Some(r#"{"name":"Alice","age":"30"}"#.to_string())
+        );
+        state.cycle_mode();
+        assert_eq!(state.selection_output(), Some("Alice".to_string()));
+    }
+
+    #[test]
+    fn mode_badge_follows_the_active_mode() {
+        let mut state = State {
+            available_modes: vec![SelectionMode::Row, SelectionMode::Column, SelectionMode::Cell],
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+
+        assert_eq!(state.mode_badge(), "R");
+        state.cycle_mode();
+        assert_eq!(state.mode_badge(), "C");
+        state.cycle_mode();
+        assert_eq!(state.mode_badge(), "□");
+        state.cycle_mode();
+        assert_eq!(state.mode_badge(), "R");
+    }
+
+    #[test]
+    fn initial_mode_keeps_the_cycle_order() {
+        let mut state = State {
+            available_modes: vec![SelectionMode::Row, SelectionMode::Column, SelectionMode::Cell],
+            active_mode: SelectionMode::Cell,
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+
+        assert_ne!(state.active_mode, state.available_modes[0]);
+        assert_eq!(state.mode_badge(), "□");
+        assert_eq!(state.selection_output(), Some("Alice".to_string()));
+        // Cycling carries on from the initial mode in the given order
+        state.cycle_mode();
+        assert_eq!(state.active_mode, SelectionMode::Row);
+        state.cycle_mode();
+        assert_eq!(state.active_mode, SelectionMode::Column);
+    }
+
+    #[test]
+    fn index_payload_uses_actual_positions() {
+        let mut state = State {
+            available_modes: vec![SelectionMode::Row, SelectionMode::Column, SelectionMode::Cell],
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[0]);
+        state.set_filter_text("o".to_string());
+        state.select_row(1);
+
+        assert_eq!(state.index_payload(), Some("2".to_string()));
+        state.cycle_mode();
+        assert_eq!(state.index_payload(), Some("1".to_string()));
+        state.cycle_mode();
+        assert_eq!(state.index_payload(), Some("2 1".to_string()));
+
+        state.active_mode = SelectionMode::Row;
+        state.selected_rows.extend([0, 2].iter().copied());
+        assert_eq!(state.index_payload(), Some("0\n2".to_string()));
+        state.header_selected = true;
+        assert_eq!(state.index_payload(), Some("header".to_string()));
+
+        state.set_filter_text("zzz".to_string());
+        assert_eq!(state.index_payload(), None);
+    }
+
+    #[test]
+    fn one_based_index_payload_counts_from_1() {
+        let mut state = State {
+            active_mode: SelectionMode::Cell,
+            output_options: OutputOptions {
+                one_based: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+        assert_eq!(state.index_payload(), Some("1 1".to_string()));
+    }
+
+    #[test]
+    fn sliced_tables_output_the_kept_rows() {
+        let mut tail = sample_table();
+        RowSlice::Tail(2).apply(&mut tail);
+        let mut state = State::default();
+        state.load_table(tail, &[]);
+        state.select_row(0);
+        assert_eq!(state.selection_output(), Some("Bob,25".to_string()));
+
+        let mut head = sample_table();
+        RowSlice::Head(2).apply(&mut head);
+        state.load_table(head, &[]);
+        state.select_row(5);
+        assert_eq!(state.selection_output(), Some("Bob,25".to_string()));
+    }
+
+    #[test]
+    fn zero_column_table_falls_back_to_row_mode() {
+        let mut state = State {
+            active_mode: SelectionMode::Column,
+            available_modes: vec![SelectionMode::Column, SelectionMode::Cell],
+            ..Default::default()
+        };
+        state.load_table(Table::from_records(None, vec![Vec::<&str>::new(); 2]), &[]);
+
+        assert_eq!(state.available_modes, vec![SelectionMode::Row]);
+        assert_eq!(state.active_mode, SelectionMode::Row);
+        assert_eq!(state.selection_output(), Some(String::new()));
+        state.cycle_mode();
+        assert_eq!(state.yank_text(), Some(String::new()));
+
+        // A later table with columns gets the configured modes back
+        state.load_table(sample_table(), &[]);
+        assert_eq!(state.available_modes, vec![SelectionMode::Column, SelectionMode::Cell]);
+        assert_eq!(state.active_mode, SelectionMode::Column);
+    }
+
+    #[test]
+    fn no_more_label_when_everything_fits() {
+        let mut state = State {
+            column_limit: Some(4),
+            ..Default::default()
+        };
+        state.load_table(wide_table(), &[]);
+
+        assert_eq!(state.visible_columns.len(), 4);
+        assert_eq!(state.more_columns_label(), None);
+    }
+
+    #[test]
+    fn output_visible_only_projects_rows_and_header() {
+        let mut state = State {
+            column_limit: Some(2),
+            output_visible_only: true,
+            output_formats: ModeFormats::all(OutputFormat::Json),
+            ..Default::default()
+        };
+        state.load_table(wide_table(), &[0]);
+
+        assert_eq!(state.selection_output(), Some(r#"{"b":"2","c":"3"}"#.to_string()));
+        state.header_selected = true;
+        assert_eq!(state.selection_output(), Some(r#"["b","c"]"#.to_string()));
+    }
+
+    fn filter_table() -> Table {
+        Table {
+            headers: Some(vec!["city".to_string()]),
+            rows: vec![
+                vec!["New York".to_string()],
+                vec!["Newark".to_string()],
+                vec!["Renew-Ark".to_string()],
+                vec!["York".to_string()],
+            ],
+        }
+    }
+
+    fn filtered(mode: FilterMode, query: &str) -> Vec<usize> {
+        let mut state = State {
+            filter_mode: mode,
+            ..Default::default()
+        };
+        state.load_table(filter_table(), &[]);
+        state.filter_text = query.to_string();
+        state.update_filtered_indices();
+        state.filtered_indices
+    }
+
+    #[test]
+    fn substring_filter_matches_anywhere() {
+        assert_eq!(filtered(FilterMode::Substring, "new"), vec![0, 1, 2]);
+        assert_eq!(filtered(FilterMode::Substring, "york"), vec![0, 3]);
+    }
+
+    #[test]
+    fn prefix_filter_matches_cell_start() {
+        assert_eq!(filtered(FilterMode::Prefix, "new"), vec![0, 1]);
+        assert_eq!(filtered(FilterMode::Prefix, "york"), vec![3]);
+    }
+
+    #[test]
+    fn word_filter_matches_whole_words() {
+        assert_eq!(filtered(FilterMode::Word, "new"), vec![0]);
+        assert_eq!(filtered(FilterMode::Word, "ark"), vec![2]);
+        assert_eq!(filtered(FilterMode::Word, "NEW york"), vec![0]);
+        assert_eq!(filtered(FilterMode::Word, "york new"), Vec::<usize>::new());
+    }
+
+    fn notes_table() -> Table {
+        Table {
+            headers: Some(vec!["name".to_string(), "notes".to_string()]),
+            rows: vec![
+                vec!["Zed".to_string(), "ask bob".to_string()],
+                vec!["Bob".to_string(), "".to_string()],
+                vec!["Rob".to_string(), "bob's friend".to_string()],
+            ],
+        }
+    }
+
+    fn fuzzy_state(weights: Vec<f64>) -> State {
+        let mut state = State {
+            filter_mode: FilterMode::Fuzzy,
+            column_weights: weights,
+            ..Default::default()
+        };
+        state.load_table(notes_table(), &[]);
+        state.filter_text = "bob".to_string();
+        state.update_filtered_indices();
+        state
+    }
+
+    #[test]
+    fn fuzzy_filter_ties_keep_original_order() {
+        let state = fuzzy_state(Vec::new());
+
+        assert_eq!(state.filtered_indices, vec![0, 1, 2]);
+        assert_eq!(state.filter_scores.len(), 3);
+        assert!(state.filter_scores.windows(2).all(|w| w[0] >= w[1]));
+    }
+
+    #[test]
+    fn fuzzy_filter_ranks_weighted_columns_higher() {
+        let state = fuzzy_state(vec![2.0, 0.5]);
+        assert_eq!(state.filtered_indices, vec![1, 0, 2]);
+        assert!(state.filter_scores[0] > state.filter_scores[1]);
+
+        let state = fuzzy_state(vec![0.5, 2.0]);
+        assert_eq!(state.filtered_indices, vec![0, 2, 1]);
+    }
+
+    #[test]
+    fn fuzzy_filter_skips_zero_weight_columns() {
+        let state = fuzzy_state(vec![1.0, 0.0]);
+        assert_eq!(state.filtered_indices, vec![1]);
+    }
+
+    fn sorted_state(keys: &[(usize, bool)]) -> State {
+        let mut state = State {
+            sort_keys: keys
+                .iter()
+                .map(|&(col, descending)| SortKey { col, descending })
+                .collect(),
+            ..Default::default()
+        };
+        state.load_table(
+            Table::from_records(
+                Some(vec!["name".to_string(), "age".to_string()]),
+                vec![
+                    vec!["bob", "9"],
+                    vec!["Alice", "30"],
+                    vec!["bob", "25"],
+                    vec!["alice", "30"],
+                    vec!["Bob", "100"],
+                ],
+            ),
+            &[],
+        );
+        state
+    }
+
+    #[test]
+    fn sort_breaks_ties_with_later_keys() {
+        // name ties (ignoring case), broken by age as numbers: 9 < 25 < 100
+        let state = sorted_state(&[(0, false), (1, false)]);
+        assert_eq!(state.filtered_indices, vec![1, 3, 0, 2, 4]);
+
+        // name ascending, age descending
+        let state = sorted_state(&[(0, false), (1, true)]);
+        assert_eq!(state.filtered_indices, vec![1, 3, 4, 2, 0]);
+
+        // Rows equal on every key keep their input order
+        let state = sorted_state(&[(1, true)]);
+        assert_eq!(state.filtered_indices, vec![4, 1, 3, 2, 0]);
+    }
+
+    #[test]
+    fn sort_applies_after_filtering() {
+        let mut state = sorted_state(&[(1, true)]);
+        state.set_filter_text("b".to_string());
+        assert_eq!(state.filtered_indices, vec![4, 2, 0]);
+    }
+
+    #[test]
+    fn fuzzy_match_positions_mark_matched_chars() {
+        let mut state = fuzzy_state(Vec::new());
+        assert_eq!(state.fuzzy_match_positions(0, 1), Some(vec![4, 5, 6]));
+        assert_eq!(state.fuzzy_match_positions(1, 0), Some(vec![0, 1, 2]));
+        assert_eq!(state.fuzzy_match_positions(0, 0), None);
+
+        let state_skipping = fuzzy_state(vec![1.0, 0.0]);
+        assert_eq!(state_skipping.fuzzy_match_positions(0, 1), None);
+
+        state.filter_mode = FilterMode::Substring;
+        assert_eq!(state.fuzzy_match_positions(1, 0), None);
+    }
+
+    #[test]
+    fn highlight_runs_split_at_matched_chars() {
+        let runs = |value, positions| -> Vec<(String, bool)> { highlight_runs(value, positions) };
+        let owned = |parts: &[(&str, bool)]| -> Vec<(String, bool)> {
+            parts.iter().map(|&(text, lit)| (text.to_string(), lit)).collect()
+        };
+
+        assert_eq!(runs("ask bob", &[4, 5, 6]), owned(&[("ask ", false), ("bob", true)]));
+        assert_eq!(
+            runs("New City", &[0, 4]),
+            owned(&[("N", true), ("ew ", false), ("C", true), ("ity", false)])
+        );
+        assert_eq!(runs("café", &[3]), owned(&[("caf", false), ("é", true)]));
+        assert_eq!(runs("plain", &[]), owned(&[("plain", false)]));
+        assert_eq!(runs("", &[]), owned(&[]));
+    }
+
+    #[test]
+    fn filter_completion_extends_to_the_shared_prefix() {
+        let mut state = State::default();
+        state.load_table(filter_table(), &[]);
+        state.set_filter_text("Ne".to_string());
+
+        // "New York", "Newark" and "Renew-Ark" all continue with "w"
+        assert_eq!(state.filter_completion(None), Some("New".to_string()));
+
+        state.set_filter_text("New".to_string());
+        assert_eq!(state.filter_completion(None), None);
+    }
+
+    #[test]
+    fn filter_completion_of_a_single_match_is_the_whole_cell() {
+        let mut state = State::default();
+        state.load_table(filter_table(), &[]);
+        state.set_filter_text("wa".to_string());
+
+        assert_eq!(state.filter_completion(None), Some("wark".to_string()));
+    }
+
+    #[test]
+    fn filter_completion_within_a_column() {
+        let mut state = State::default();
+        state.load_table(
+            Table::from_records(
+                Some(vec!["name".into(), "city".into()]),
+                vec![vec!["Anna", "Anchorage"], vec!["Annie", "Annecy"]],
+            ),
+            &[],
+        );
+        state.set_filter_text("an".to_string());
+
+        // The cities diverge right after "an", the names share another "n"
+        assert_eq!(state.filter_completion(None), None);
+        assert_eq!(state.filter_completion(Some(0)), Some("ann".to_string()));
+    }
+
+    #[test]
+    fn drilling_into_a_nested_object_cell_and_back() {
+        let mut state = State {
+            active_mode: SelectionMode::Cell,
+            available_modes: vec![SelectionMode::Row, SelectionMode::Cell],
+            ..Default::default()
+        };
+        state.load_table(
+            Table::from_records(
+                Some(vec!["name".into(), "address".into()]),
+                vec![
+                    vec!["Alice", r#"{"city":"Paris"}"#],
+                    vec!["Bob", r#"{"city":"Oslo","zip":"0150"}"#],
+                ],
+            ),
+            &[],
+        );
+        state.set_filter_text("bob".to_string());
+
+        // A plain cell stays put
+        assert!(!state.drill_down());
+
+        state.selected_col = 1;
+        assert!(state.drill_down());
+        assert_eq!(state.drilled.as_ref().unwrap().breadcrumb, "address[1]");
+        assert_eq!(state.filter_text, "");
+        assert_eq!(state.visible_rows(), 2);
+        state.select_row(1);
+        state.selected_col = 1;
+        assert_eq!(state.selection_output(), Some("0150".to_string()));
+
+        // Only one level deep
+        assert!(!state.drill_down());
+
+        assert!(state.drill_up());
+        assert_eq!(state.filter_text, "bob");
+        assert_eq!(state.filtered_indices, vec![1]);
+        assert_eq!(state.selected_col, 1);
+        assert!(!state.drill_up());
+    }
+
+    #[test]
+    fn cycling_filter_mode_refilters() {
+        let mut state = State::default();
+        state.load_table(filter_table(), &[]);
+        state.filter_text = "new".to_string();
+        state.update_filtered_indices();
+        state.selected_row = 2;
+
+        state.cycle_filter_mode();
+
+        assert_eq!(state.filter_mode, FilterMode::Prefix);
+        assert_eq!(state.filtered_indices, vec![0, 1]);
+        assert_eq!(state.selected_row, 0);
+
+        state.selected_row = 1;
+        state.header_selected = true;
+        state.cycle_filter_mode();
+
+        assert_eq!(state.filter_mode, FilterMode::Word);
+        assert_eq!(state.filtered_indices, vec![0]);
+        assert_eq!(state.selected_row, 0);
+        assert!(!state.header_selected);
+    }
+
+    fn dry_run_state(
+        mode: SelectionMode,
+        format: OutputFormat,
+        query: &str,
+        select: usize,
+    ) -> State {
+        let mut state = State {
+            active_mode: mode,
+            available_modes: vec![mode],
+            output_formats: ModeFormats::all(format),
+            filter_text: query.to_string(),
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+        state.select_row(select);
+        state
+    }
+
+    #[test]
+    fn selection_output_applies_query_and_select() {
+        let state = dry_run_state(SelectionMode::Row, OutputFormat::Plain, "o", 1);
+
+        assert_eq!(state.selection_output(), Some("Carol,35".to_string()));
+        assert_eq!(
+            state.describe_selection(),
+            "substring filter \"o\" matched 2 of 3 rows; would select row 2"
+        );
+    }
+
+    #[test]
+    fn confirming_an_empty_filter_follows_the_policy() {
+        let mut state = dry_run_state(SelectionMode::Row, OutputFormat::Plain, "zzz", 0);
+        assert!(state.confirm_empty(ConfirmEmpty::Cancel));
+
+        assert!(!state.confirm_empty(ConfirmEmpty::Ignore));
+        assert_eq!(state.filter_text, "zzz");
+        assert_eq!(state.visible_rows(), 0);
+
+        assert!(!state.confirm_empty(ConfirmEmpty::ClearFilter));
+        assert_eq!(state.filter_text, "");
+        assert_eq!(state.visible_rows(), 3);
+        assert_eq!(state.selection_output(), Some("Alice,30".to_string()));
+    }
+
+    #[test]
+    fn selection_output_clamps_select_and_honours_mode() {
+        let mut state = dry_run_state(SelectionMode::Cell, OutputFormat::Json, "", 10);
+        state.selected_col = 1;
+
+        assert_eq!(
+            state.selection_output(),
+            Some(r#"{"value":"35","row":2,"column":"age"}"#.to_string())
+        );
+        assert_eq!(
+            state.describe_selection(),
+            "no filter matched 3 of 3 rows; would select row 2, column \"age\""
+        );
+    }
+
+    #[test]
+    fn selection_output_is_none_without_matches() {
+        let state = dry_run_state(SelectionMode::Column, OutputFormat::Plain, "zzz", 0);
+
+        assert_eq!(state.selection_output(), None);
+        assert_eq!(
+            state.describe_selection(),
+            "substring filter \"zzz\" matched 0 of 3 rows; nothing would be selected"
+        );
+    }
+
+    #[test]
+    fn yank_text_per_mode() {
+        let mut state = dry_run_state(SelectionMode::Row, OutputFormat::Plain, "o", 1);
+        state.selected_col = 1;
+        assert_eq!(state.yank_text(), Some("Carol,35".to_string()));
+
+        state.active_mode = SelectionMode::Column;
+        assert_eq!(state.yank_text(), Some("25\n35".to_string()));
+
+        state.active_mode = SelectionMode::Cell;
+        assert_eq!(state.yank_text(), Some("35".to_string()));
+
+        state.filter_text = "zzz".to_string();
+        state.update_filtered_indices();
+        assert_eq!(state.yank_text(), None);
+    }
+
+    #[test]
+    fn cell_flow_wrap_moves_across_row_ends() {
+        let mut state = State {
+            active_mode: SelectionMode::Cell,
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+
+        // Without flow wrap the row ends stop
+        state.selected_col = 1;
+        assert!(!state.next_col(false));
+        assert_eq!((state.selected_row, state.selected_col), (0, 1));
+
+        assert!(state.next_col(true));
+        assert_eq!((state.selected_row, state.selected_col), (1, 0));
+        assert!(!state.next_col(true));
+        assert_eq!((state.selected_row, state.selected_col), (1, 1));
+
+        assert!(!state.prev_col(true));
+        assert!(state.prev_col(true));
+        assert_eq!((state.selected_row, state.selected_col), (0, 1));
+    }
+
+    #[test]
+    fn cell_flow_wrap_stops_at_table_corners_and_outside_cell_mode() {
+        let mut state = State {
+            active_mode: SelectionMode::Cell,
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+
+        assert!(!state.prev_col(true));
+        assert_eq!((state.selected_row, state.selected_col), (0, 0));
+
+        state.selected_row = 2;
+        state.selected_col = 1;
+        assert!(!state.next_col(true));
+        assert_eq!((state.selected_row, state.selected_col), (2, 1));
+
+        state.active_mode = SelectionMode::Column;
+        state.selected_row = 0;
+        assert!(!state.next_col(true));
+        assert_eq!((state.selected_row, state.selected_col), (0, 1));
+    }
+
+    #[test]
+    fn empty_cell_placeholder_is_display_only() {
+        assert_eq!(cell_display_text("", "–"), "–");
+        assert_eq!(cell_display_text("x", "–"), "x");
+
+        let mut state = State {
+            active_mode: SelectionMode::Cell,
+            selected_col: 1,
+            ..Default::default()
+        };
+        state.load_table(notes_table(), &[]);
+        state.select_row(1);
+        assert_eq!(state.selection_output(), Some(String::new()));
+
+        state.set_filter_text("–".to_string());
+        assert_eq!(state.visible_rows(), 0);
+    }
+
+    #[test]
+    fn hidden_header_is_kept_for_output() {
+        let mut state = State {
+            output_formats: ModeFormats::all(OutputFormat::Json),
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+        state.header_selected = true;
+
+        state.toggle_header();
+
+        assert!(!state.show_header);
+        assert!(!state.header_selected);
+        assert!(!state.header_selectable());
+        assert_eq!(
+            state.selection_output(),
+            Some(r#"{"name":"Alice","age":"30"}"#.to_string())
+        );
+    }
+
+    #[test]
+    fn timeout_deadline_and_reset() {
+        let start = Instant::now();
+        let mut state = State {
+            timeout: Some(Duration::from_secs(30)),
+            ..Default::default()
+        };
+        state.start_timeout(start);
+
+        assert!(!state.timed_out(start + Duration::from_secs(29)));
+        assert!(state.timed_out(start + Duration::from_secs(30)));
+
+        state.note_input(start + Duration::from_secs(20));
+        assert!(state.timed_out(start + Duration::from_secs(30)));
+
+        state.timeout_reset_on_input = true;
+        state.note_input(start + Duration::from_secs(20));
+        assert!(!state.timed_out(start + Duration::from_secs(30)));
+        assert!(state.timed_out(start + Duration::from_secs(50)));
+    }
+
+    #[test]
+    fn no_timeout_never_times_out() {
+        let mut state = State::default();
+        state.start_timeout(Instant::now());
+        assert!(!state.timed_out(Instant::now() + Duration::from_secs(3600)));
+    }
+
+    #[test]
+    fn delete_word_back_strips_last_word() {
+        assert_eq!(delete_word_back("new york"), "new ");
+        assert_eq!(delete_word_back("new york  "), "new ");
+        assert_eq!(delete_word_back("new"), "");
+        assert_eq!(delete_word_back("  "), "");
+        assert_eq!(delete_word_back("café\u{a0}crème"), "café\u{a0}");
+    }
+
+    #[test]
+    fn filter_edits_refilter() {
+        let mut state = State::default();
+        state.load_table(sample_table(), &[]);
+        state.set_filter_text("35 bo".to_string());
+        assert_eq!(state.visible_rows(), 0);
+
+        state.delete_filter_word();
+        assert_eq!(state.filter_text, "35 ");
+        assert_eq!(state.filtered_indices, Vec::<usize>::new());
+
+        state.delete_filter_word();
+        assert_eq!(state.filter_text, "");
+        assert_eq!(state.filtered_indices, vec![0, 1, 2]);
+
+        state.set_filter_text("bo".to_string());
+        assert_eq!(state.filtered_indices, vec![1]);
+        state.set_filter_text(String::new());
+        assert_eq!(state.visible_rows(), 3);
+    }
+
+    #[test]
+    fn loop_confirms_keep_the_picker_open() {
+        let mut state = State {
+            loop_mode: true,
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+
+        let mut lines = Vec::new();
+        lines.extend(state.loop_confirm());
+        state.select_row(2);
+        lines.extend(state.loop_confirm());
+
+        assert_eq!(lines, vec!["Alice,30", "Carol,35"]);
+        assert_eq!(state.confirmed, 2);
+        assert_eq!(state.visible_rows(), 3);
+    }
+
+    #[test]
+    fn loop_remove_hides_picked_rows() {
+        let mut state = State {
+            loop_mode: true,
+            loop_remove: true,
+            multi_select: true,
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+
+        state.selected_rows.extend([0, 2]);
+        assert_eq!(state.loop_confirm(), Some("Alice,30\nCarol,35".to_string()));
+        assert_eq!(state.filtered_indices, vec![1]);
+        assert!(state.selected_rows.is_empty());
+
+        assert_eq!(state.loop_confirm(), Some("Bob,25".to_string()));
+        assert_eq!(state.visible_rows(), 0);
+        assert_eq!(state.loop_confirm(), None);
+
+        // The picked rows stay hidden while filtering
+        state.set_filter_text("o".to_string());
+        assert_eq!(state.visible_rows(), 0);
+    }
+
+    #[test]
+    fn column_type_badges_follow_detection() {
+        let mut state = State::default();
+        state.load_table(
+            Table::from_records(
+                Some(vec!["name".into(), "age".into(), "note".into()]),
+                vec![vec!["Alice", "30", ""], vec!["Bob", " 2.5 ", ""]],
+            ),
+            &[],
+        );
+
+        assert_eq!(state.column_type_badge(0), "A");
+        assert_eq!(state.column_type_badge(1), "#");
+        // A column with no values to go by is text
+        assert_eq!(state.column_type_badge(2), "A");
+    }
+
+    #[test]
+    fn multi_enter_confirm_all_outputs_checked_or_cursor_row() {
+        let mut state = State {
+            multi_select: true,
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+
+        assert!(!state.enter_checks_row(false));
+        assert_eq!(state.selection_output(), Some("Alice,30".to_string()));
+
+        state.selected_rows.extend([1, 2]);
+        assert!(!state.enter_checks_row(false));
+        assert_eq!(state.selection_output(), Some("Bob,25\nCarol,35".to_string()));
+    }
+
+    #[test]
+    fn multi_enter_toggle_row_checks_until_ctrl_enter() {
+        let mut state = State {
+            multi_select: true,
+            multi_enter: MultiEnter::ToggleRow,
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+
+        assert!(state.enter_checks_row(false));
+        state.select_row(2);
+        assert!(state.enter_checks_row(false));
+        assert_eq!(state.selected_rows, BTreeSet::from([0, 2]));
+
+        assert!(!state.enter_checks_row(true));
+        assert_eq!(state.selection_output(), Some("Alice,30\nCarol,35".to_string()));
+
+        // Without --multi, Enter always confirms
+        state.multi_select = false;
+        assert!(!state.enter_checks_row(false));
+    }
+
+    #[test]
+    fn mru_rows_lead_most_recent_first() {
+        let mut state = State {
+            mru: vec!["Carol".to_string(), "Gone".to_string(), "Bob".to_string()],
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+        assert_eq!(state.filtered_indices, vec![2, 1, 0]);
+
+        // Within the filter's matches, and under pinned rows
+        state.set_filter_text("o".to_string());
+        assert_eq!(state.filtered_indices, vec![2, 1]);
+        state.pins = vec!["Bob".to_string()];
+        state.set_filter_text(String::new());
+        assert_eq!(state.filtered_indices, vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn mru_keeps_fuzzy_scores_aligned() {
+        let mut state = State {
+            filter_mode: FilterMode::Fuzzy,
+            mru: vec!["Carol".to_string()],
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+        state.set_filter_text("o".to_string());
+
+        assert_eq!(state.filtered_indices[0], 2);
+        assert_eq!(state.filter_scores.len(), state.filtered_indices.len());
+    }
+
+    #[test]
+    fn record_mru_takes_the_confirmed_rows_key() {
+        let mut state = State {
+            multi_select: true,
+            mru: vec!["Alice".to_string()],
+            mru_key: 1,
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+        state.select_row(1);
+        state.record_mru(&state.mru_keys());
+        assert_eq!(state.mru, vec!["25", "Alice"]);
+
+        state.selected_rows.extend([0, 2]);
+        state.record_mru(&state.mru_keys());
+        assert_eq!(state.mru, vec!["30", "35", "25", "Alice"]);
+    }
+
+    #[test]
+    fn no_mru_keys_inside_a_drilled_cell() {
+        let mut state = State {
+            active_mode: SelectionMode::Cell,
+            available_modes: vec![SelectionMode::Row, SelectionMode::Cell],
+            ..Default::default()
+        };
+        state.load_table(
+            Table::from_records(
+                Some(vec!["name".into(), "address".into()]),
+                vec![vec!["Alice", r#"{"city":"Paris"}"#]],
+            ),
+            &[],
+        );
+        state.selected_col = 1;
+        assert_eq!(state.mru_keys(), vec!["Alice"]);
+
+        assert!(state.drill_down());
+        assert_eq!(state.mru_keys(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn detail_text_follows_the_selection() {
+        let mut state = State {
+            available_modes: vec![SelectionMode::Row, SelectionMode::Cell],
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+        state.select_row(1);
+        assert_eq!(state.detail_text(), Some("name: Bob\nage: 25".to_string()));
+
+        state.cycle_mode();
+        state.selected_col = 1;
+        assert_eq!(state.detail_text(), Some("25".to_string()));
+
+        state.set_filter_text("zzz".to_string());
+        assert_eq!(state.detail_text(), None);
+    }
+
+    fn header_only_state(mode: SelectionMode) -> State {
+        let mut state = State {
+            active_mode: mode,
+            available_modes: vec![mode],
+            ..Default::default()
+        };
+        state.load_table(Table::new(Some(vec!["name".into(), "age".into()]), Vec::new()), &[]);
+        state
+    }
+
+    #[test]
+    fn header_only_row_mode_can_pick_the_header() {
+        let mut state = header_only_state(SelectionMode::Row);
+        assert_eq!(state.empty_message(), Some("No rows"));
+        assert_eq!(state.selection_output(), None);
+
+        assert!(state.header_selectable());
+        state.header_selected = true;
+        assert_eq!(state.selection_output(), Some("name,age".to_string()));
+        assert_eq!(state.index_payload(), Some("header".to_string()));
+    }
+
+    #[test]
+    fn header_only_column_mode_picks_columns() {
+        let mut state = header_only_state(SelectionMode::Column);
+        state.selected_col = 1;
+        assert_eq!(state.selection_output(), Some("age".to_string()));
+        assert_eq!(state.index_payload(), Some("1".to_string()));
+        assert_eq!(
+            state.describe_selection(),
+            "no filter matched 0 of 0 rows; would select column \"age\""
+        );
+    }
+
+    #[test]
+    fn header_only_cell_mode_has_nothing_to_select() {
+        let state = header_only_state(SelectionMode::Cell);
+        assert_eq!(state.selection_output(), None);
+        assert_eq!(state.index_payload(), None);
+        assert_eq!(state.detail_text(), None);
+        assert!(!state.header_selectable());
+    }
+
+    #[test]
+    fn empty_message_tells_no_rows_from_no_matches() {
+        let mut state = State::default();
+        state.load_table(sample_table(), &[]);
+        assert_eq!(state.empty_message(), None);
+        state.set_filter_text("zzz".to_string());
+        assert_eq!(state.empty_message(), Some("No matches"));
+    }
+
+    #[test]
+    fn clear_filter_restores_every_row() {
+        let mut state = State::default();
+        state.load_table(sample_table(), &[]);
+        state.set_filter_text("o".to_string());
+        state.select_row(1);
+        assert_eq!(state.filtered_indices, vec![1, 2]);
+
+        state.clear_filter();
+        assert_eq!(state.filter_text, "");
+        assert_eq!(state.filtered_indices, vec![0, 1, 2]);
+        assert_eq!(state.selected_row, 0);
+    }
+
+    #[test]
+    fn position_label_only_when_paged() {
+        let mut state = State::default();
+        state.load_table(sample_table(), &[]);
+        assert_eq!(state.position_label(), None);
+
+        state.page_rows = 2;
+        state.select_row(1);
+        assert_eq!(state.position_label(), Some("row 2 of 3".to_string()));
+
+        state.set_filter_text("o".to_string());
+        assert_eq!(state.position_label(), None);
+    }
+
+    #[test]
+    fn edge_scroll_only_when_selection_leaves_viewport() {
+        let mut state = State::default();
+        state.load_table(sample_table(), &[]);
+        assert_eq!(state.edge_scroll_offset(None), None);
+
+        // Header plus three rows at 10px each, two rows visible
+        state.viewport = Some(ScrollViewport {
+            offset_y: 0.0,
+            height: 20.0,
+            content_height: 40.0,
+        });
+        state.select_row(0);
+        assert_eq!(state.edge_scroll_offset(None), None);
+
+        state.select_row(2);
+        assert_eq!(state.edge_scroll_offset(None), Some(20.0));
+
+        state.viewport = Some(ScrollViewport {
+            offset_y: 20.0,
+            height: 20.0,
+            content_height: 40.0,
+        });
+        state.select_row(0);
+        assert_eq!(state.edge_scroll_offset(None), Some(10.0));
+
+        state.header_selected = true;
+        assert_eq!(state.edge_scroll_offset(None), Some(0.0));
+    }
+
+    #[test]
+    fn fixed_row_height_places_rows_exactly() {
+        let mut state = State::default();
+        state.load_table(sample_table(), &[]);
+        // A 16px header and three 12px rows, 24px visible
+        state.viewport = Some(ScrollViewport {
+            offset_y: 0.0,
+            height: 24.0,
+            content_height: 52.0,
+        });
+
+        state.select_row(1);
+        assert_eq!(state.edge_scroll_offset(Some(12.0)), Some(16.0));
+        state.select_row(2);
+        assert_eq!(state.edge_scroll_offset(Some(12.0)), Some(28.0));
+
+        // First row at the top, last at the bottom, the middle one centred
+        state.select_row(0);
+        assert_eq!(state.proportional_scroll_offset(12.0), Some(16.0));
+        state.select_row(1);
+        assert_eq!(state.proportional_scroll_offset(12.0), Some(22.0));
+        state.select_row(2);
+        assert_eq!(state.proportional_scroll_offset(12.0), Some(28.0));
+    }
+
+    #[test]
+    fn cursor_and_checked_rows_are_distinct() {
+        let mut state = State {
+            multi_select: true,
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+
+        state.select_row(2);
+        state.toggle_checked();
+        state.select_row(0);
+
+        assert!(state.row_is_cursor(0));
+        assert!(!state.row_is_checked(0));
+        assert!(!state.row_is_cursor(2));
+        assert!(state.row_is_checked(2));
+
+        state.toggle_checked();
+        assert_eq!(state.selection_output(), Some("Alice,30\nCarol,35".to_string()));
+
+        state.toggle_checked();
+        assert!(!state.row_is_checked(0));
+    }
+
+    fn checked_columns_state(format: &str) -> State {
+        let mut state = State {
+            available_modes: vec![SelectionMode::Column],
+            active_mode: SelectionMode::Column,
+            multi_select: true,
+            output_formats: ModeFormats::parse(format).unwrap(),
+            ..Default::default()
+        };
+        state.load_table(
+            Table::from_records(
+                Some(vec!["name".to_string(), "age".to_string(), "city".to_string()]),
+                vec![vec!["Alice", "30", "Paris"], vec!["Bob", "25", "Oslo"]],
+            ),
+            &[],
+        );
+        // Check city, then name: output keeps the input column order
+        state.selected_col = 2;
+        state.toggle_checked();
+        state.selected_col = 0;
+        state.toggle_checked();
+        state.selected_col = 1;
+        state
+    }
+
+    #[test]
+    fn checked_columns_output_as_a_csv_projection() {
+        let state = checked_columns_state("csv");
+        assert!(state.col_is_checked(0) && state.col_is_checked(2));
+        assert!(!state.col_is_checked(1));
+        assert_eq!(
+            state.selection_output(),
+            Some("name,city\nAlice,Paris\nBob,Oslo".to_string())
+        );
+        assert_eq!(state.index_payload(), Some("0\n2".to_string()));
+    }
+
+    #[test]
+    fn checked_columns_output_as_json_and_plain() {
+        let mut state = checked_columns_state("json");
+        assert_eq!(
+            state.selection_output(),
+            Some(r#"[{"name":"Alice","city":"Paris"},{"name":"Bob","city":"Oslo"}]"#.to_string())
+        );
+
+        state.output_formats = ModeFormats::all(OutputFormat::Plain);
+        state.set_filter_text("bob".to_string());
+        assert_eq!(state.selection_output(), Some("Bob\tOslo".to_string()));
+
+        // Unchecking both goes back to the selected column's name
+        state.selected_col = 0;
+        state.toggle_checked();
+        state.selected_col = 2;
+        state.toggle_checked();
+        assert_eq!(state.selection_output(), Some("city".to_string()));
+    }
+
+    #[test]
+    fn checked_rows_survive_filtering() {
+        let mut state = State {
+            multi_select: true,
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+        state.set_filter_text("bo".to_string());
+        state.toggle_checked();
+        state.set_filter_text(String::new());
+
+        assert!(state.row_is_checked(1));
+        assert_eq!(state.selection_output(), Some("Bob,25".to_string()));
+    }
+
+    #[test]
+    fn quick_keys_pick_the_nth_visible_row() {
+        let mut state = State {
+            quick_keys: true,
+            ..Default::default()
+        };
+        state.load_table(filter_table(), &[]);
+        state.set_filter_text("york".to_string());
+
+        assert!(!state.quick_select("3"));
+        assert!(!state.quick_select("0"));
+        assert!(!state.quick_select("x"));
+
+        state.set_filter_text(String::new());
+        assert!(state.quick_select("3"));
+        assert_eq!(state.actual_row_index(state.selected_row), state.filtered_indices[2]);
+        assert_eq!(state.selection_output(), Some("Renew-Ark".to_string()));
+    }
+
+    fn multi_csv_state(multi_header: bool) -> State {
+        let mut state = State {
+            multi_select: true,
+            output_formats: ModeFormats::all(OutputFormat::Csv),
+            output_options: OutputOptions {
+                multi_header,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+        state
+    }
+
+    #[test]
+    fn multi_header_starts_several_csv_rows_with_the_header() {
+        let mut state = multi_csv_state(true);
+        state.toggle_checked();
+        assert_eq!(state.selection_output(), Some("Alice,30".to_string()));
+
+        state.select_row(2);
+        state.toggle_checked();
+        assert_eq!(
+            state.selection_output(),
+            Some("name,age\nAlice,30\nCarol,35".to_string())
+        );
+    }
+
+    #[test]
+    fn several_csv_rows_are_headerless_by_default() {
+        let mut state = multi_csv_state(false);
+        state.toggle_checked();
+        state.select_row(2);
+        state.toggle_checked();
+        assert_eq!(state.selection_output(), Some("Alice,30\nCarol,35".to_string()));
+    }
+
+    #[test]
+    fn clicking_a_cell_selects_its_row_and_visible_column() {
+        let mut state = State {
+            active_mode: SelectionMode::Cell,
+            header_selected: true,
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[0]);
+        state.select_cell(2, 1);
+        assert_eq!(state.selected_row, 2);
+        assert_eq!(state.selected_col, 0);
+        assert!(!state.header_selected);
+        assert_eq!(state.selection_output(), Some("35".to_string()));
+
+        // A hidden column leaves the column where it was
+        state.select_cell(1, 0);
+        assert_eq!((state.selected_row, state.selected_col), (1, 0));
+    }
+
+    #[test]
+    fn filter_only_matches_within_the_scope() {
+        let mut state = State {
+            scope: Some("a".to_string()),
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+        assert_eq!(state.scope_indices, vec![0, 2]);
+        assert_eq!(state.filtered_indices, vec![0, 2]);
+
+        // Bob matches "o" too, but is outside the scope
+        state.set_filter_text("o".to_string());
+        assert_eq!(state.filtered_indices, vec![2]);
+
+        state.clear_scope();
+        assert_eq!(state.filtered_indices, vec![1, 2]);
+    }
+
+    #[test]
+    fn parse_as_reads_the_raw_input_again() {
+        let mut state = State {
+            raw_input: Some("name  age\nAlice 30\nBob,Jr 25\n".to_string()),
+            ..Default::default()
+        };
+        let csv = state.parse_as(InputFormat::Csv).unwrap();
+        assert_eq!(csv.width(), 2);
+        assert_eq!(csv.cell(1, 0), Some("Bob"));
+
+        // Without --any-delimiter, runs of spaces and tabs split the cells
+        let spaced = state.parse_as(InputFormat::AnyDelimiter).unwrap();
+        assert_eq!(spaced.headers, Some(vec!["name".to_string(), "age".to_string()]));
+        assert_eq!(spaced.cell(1, 0), Some("Bob,Jr"));
+
+        assert!(state.parse_as(InputFormat::Json).is_err());
+        state.raw_input = None;
+        assert!(state.parse_as(InputFormat::Csv).is_err());
+    }
+
+    #[test]
+    fn diff_needs_exactly_two_checked_rows() {
+        let mut state = State {
+            multi_select: true,
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+        state.toggle_checked();
+        assert!(state.diff_output().is_err());
+
+        state.select_row(2);
+        state.toggle_checked();
+        assert_eq!(
+            state.diff_output(),
+            Ok("name: Alice -> Carol\nage: 30 -> 35".to_string())
+        );
+
+        state.select_row(1);
+        state.toggle_checked();
+        assert!(state.diff_output().is_err());
+    }
+
+    fn entry_state(header: bool) -> State {
+        let mut state = State {
+            available_modes: vec![SelectionMode::Row, SelectionMode::Cell],
+            entry: Some(DataEntry {
+                header_pending: header,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        state.load_table(Table::new(None, Vec::new()), &[]);
+        state
+    }
+
+    #[test]
+    fn data_entry_adds_typed_rows_and_outputs_them() {
+        let mut state = entry_state(true);
+        state.output_formats = ModeFormats::all(OutputFormat::Csv);
+        assert_eq!(state.entry_output(), None);
+
+        for line in ["name,age", "Alice,30", "\"Bob, Jr\",25"] {
+            state.set_filter_text(line.to_string());
+            assert_eq!(state.visible_rows(), state.table.rows.len());
+            state.submit_entry().unwrap();
+        }
+
+        assert_eq!(state.filter_text, "");
+        assert_eq!(state.selected_row, 1);
+        assert_eq!(state.table.headers, Some(vec!["name".to_string(), "age".to_string()]));
+        assert_eq!(
+            state.entry_output(),
+            Some("Alice,30\n\"Bob, Jr\",25".to_string())
+        );
+    }
+
+    #[test]
+    fn data_entry_edits_the_selected_cell() {
+        let mut state = entry_state(false);
+        state.set_filter_text("a,b".to_string());
+        state.submit_entry().unwrap();
+        assert!(!state.begin_edit());
+
+        state.active_mode = SelectionMode::Cell;
+        state.selected_col = 1;
+        assert!(state.begin_edit());
+        assert_eq!(state.filter_text, "b");
+
+        state.set_filter_text("b, edited".to_string());
+        state.submit_entry().unwrap();
+        assert!(!state.is_editing());
+        assert_eq!(state.table.rows, vec![vec!["a".to_string(), "b, edited".to_string()]]);
+
+        assert!(state.begin_edit());
+        assert!(state.cancel_edit());
+        assert_eq!(state.filter_text, "");
+        assert_eq!(state.table.rows.len(), 1);
+    }
+
+    fn pinned_state(pins: &[&str], sticky: bool) -> State {
+        let mut state = State {
+            pins: pins.iter().map(|pin| pin.to_string()).collect(),
+            pin_sticky: sticky,
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+        state
+    }
+
+    #[test]
+    fn pinned_rows_lead_the_list() {
+        let mut state = pinned_state(&["Carol", "25"], false);
+        assert_eq!(state.filtered_indices, vec![1, 2, 0]);
+        assert_eq!(state.selection_output(), Some("Bob,25".to_string()));
+
+        // Pinned rows the filter excludes still hide
+        state.set_filter_text("a".to_string());
+        assert_eq!(state.filtered_indices, vec![2, 0]);
+    }
+
+    #[test]
+    fn pinned_rows_lead_fuzzy_ordering_and_keep_their_scores() {
+        let mut state = pinned_state(&["Carol"], false);
+        state.filter_mode = FilterMode::Fuzzy;
+        state.set_filter_text("b".to_string());
+        assert_eq!(state.filtered_indices, vec![1]);
+
+        state.set_filter_text("o".to_string());
+        assert_eq!(state.filtered_indices, vec![2, 1]);
+        assert_eq!(state.filter_scores.len(), 2);
+    }
+
+    #[test]
+    fn sticky_pins_survive_the_filter() {
+        let mut state = pinned_state(&["Alice"], true);
+        state.set_filter_text("bob".to_string());
+
+        assert_eq!(state.filtered_indices, vec![0, 1]);
+    }
+
+    #[test]
+    fn inverting_checked_rows_only_touches_filtered_rows() {
+        let mut state = State {
+            multi_select: true,
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+        state.toggle_checked();
+        state.set_filter_text("o".to_string());
+        state.select_row(1);
+        state.toggle_checked();
+
+        // Bob and Carol pass the filter; Carol was checked, Alice is hidden
+        state.invert_checked();
+
+        assert_eq!(state.selected_rows, BTreeSet::from([0, 1]));
+    }
+
+    #[test]
+    fn header_selection_deselects_rows_and_ends_with_row_mode() {
+        let mut state = State {
+            available_modes: vec![SelectionMode::Row, SelectionMode::Cell],
+            ..Default::default()
+        };
+        state.load_table(sample_table(), &[]);
+
+        assert!(state.header_selectable());
+        state.header_selected = true;
+        assert!(!state.cell_is_selected(0, 0));
+
+        state.cycle_mode();
+        assert_eq!(state.active_mode, SelectionMode::Cell);
+        assert!(!state.header_selectable());
+        assert!(!state.header_selected);
+    }
 }
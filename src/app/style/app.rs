@@ -13,7 +13,7 @@ use iced_style::container::{Appearance, StyleSheet};
 // Transparent corner and avoid weird behavior with the scrollable widget
 pub struct AppWrapperStyle;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct AppContainerStyles {
     // Style
     pub background: OnagreColor,
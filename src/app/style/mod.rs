@@ -1,10 +1,13 @@
 use crate::app::style::app::AppContainerStyles;
+use crate::app::style::rows::cell_rule::CellRule;
+use crate::app::style::rows::generic::GenericContainerStyle;
 use crate::app::style::scrollable::scroller::ScrollerStyles;
 use crate::app::style::search::input::SearchInputStyles;
 use crate::app::style::search::SearchContainerStyles;
 use crate::config::color::OnagreColor;
 use crate::config::padding::OnagrePadding;
-use crate::THEME_PATH;
+use crate::data::OutputFormat;
+use crate::THEME_PATHS;
 use crate::THEME_SCALE;
 use iced::widget::container::Appearance;
 use iced::Background;
@@ -19,14 +22,21 @@ pub mod search;
 
 impl Theme {
     pub fn load() -> Self {
-        let buf = THEME_PATH.lock().unwrap().clone();
-        let theme = crate::config::parse_file(&buf);
-        if let Err(err) = &theme {
-            error!("Failed to parse theme {buf:?}: {err}");
-            warn!("Failing back to default theme");
-        };
+        // Each file is layered on the ones before it
+        let mut layered: Option<Theme> = None;
+        for path in THEME_PATHS.lock().unwrap().iter() {
+            // A broken file is skipped without losing the layers below it
+            let below = layered.clone().unwrap_or_else(Theme::base);
+            match crate::config::parse_layer(below, path) {
+                Ok(theme) => layered = Some(theme),
+                Err(err) => error!("Failed to parse theme {path:?}: {err}"),
+            }
+        }
 
-        let mut theme = theme.unwrap_or_default();
+        if layered.is_none() {
+            warn!("Failing back to default theme");
+        }
+        let mut theme = layered.unwrap_or_default();
         if let Some(scale) = THEME_SCALE.get() {
             theme = theme.scale(*scale)
         }
@@ -90,7 +100,55 @@ impl Scale for SizeSpec {
     }
 }
 
-#[derive(Debug, PartialEq)]
+/// How the rows scroll to follow the selection.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ScrollPolicy {
+    /// Scroll proportionally to the selected row's position on every move.
+    #[default]
+    AlwaysSnap,
+    /// Only scroll when the selected row would leave the viewport.
+    OnEdge,
+}
+
+/// How lines are drawn around and between the table's cells.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum GridStyle {
+    /// No lines; cells are set apart by spacing alone.
+    #[default]
+    Borderless,
+    /// A line around the table and between every column and row, like a
+    /// box-drawn table in a terminal. Each cell is outlined with half a line,
+    /// so neighbouring cells make up a whole one, and the rows' padding moves
+    /// into their cells so the lines meet.
+    Boxed,
+}
+
+/// What Tab does (outside multi-select, where it checks rows).
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum TabBehavior {
+    /// Switch to the next selection mode, like Shift+Tab.
+    #[default]
+    CycleMode,
+    /// Move focus between the filter input and the table, so typed keys no
+    /// longer edit the filter.
+    FocusTable,
+    /// Extend the filter to the longest common prefix of the matches.
+    AcceptCompletion,
+}
+
+/// What confirming does when the filter matches no rows.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ConfirmEmpty {
+    /// Exit unsuccessfully without output.
+    #[default]
+    Cancel,
+    /// Do nothing and stay open, so the filter can be fixed.
+    Ignore,
+    /// Clear the filter and show every row again.
+    ClearFilter,
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub struct Theme {
     // Layout
     pub exit_unfocused: bool,
@@ -101,6 +159,45 @@ pub struct Theme {
     pub font: Option<String>,
     pub font_size: u16,
     pub padding: OnagrePadding,
+    pub scroll_policy: ScrollPolicy,
+    pub tab_behavior: TabBehavior,
+    pub confirm_empty: ConfirmEmpty,
+    /// Wrap rows in a button for click handling. When false rows are plain
+    /// containers and clicks are caught by a mouse area without any chrome.
+    pub row_as_button: bool,
+    /// Shown in place of empty cell values; display only.
+    pub empty_cell_placeholder: String,
+    /// Show a footer row aggregating the numeric columns (`--footer-agg`).
+    pub show_footer: bool,
+    /// Lay columns out right to left and right-align text, for RTL scripts.
+    /// Column indices, selection and output keep the input order.
+    pub rtl: bool,
+    /// Give every data row this fixed height, so scrolling can place rows
+    /// exactly. Rows shrink to their content when unset.
+    pub row_height: Option<f32>,
+    /// Show a badge with the active selection mode next to the position.
+    pub show_mode_badge: bool,
+    /// Mark each header with its column's detected type: `#` for numeric
+    /// columns, `A` for text.
+    pub show_column_types: bool,
+    /// Show the full value of the selected cell (or row) under the table.
+    pub show_detail: bool,
+    /// In cell mode, Right past the last column moves to the first column of
+    /// the next row, and Left past the first to the end of the previous one.
+    pub cell_flow_wrap: bool,
+    /// Ctrl+key bindings that confirm with an output format in place of the
+    /// mode's own (and any `--template`).
+    pub format_keys: Vec<(String, OutputFormat)>,
+    /// `.cell-rule` blocks, in the order they were read; the first matching
+    /// a cell overrides its colors. Rules from every theme file are kept.
+    pub cell_rules: Vec<CellRule>,
+    pub grid_style: GridStyle,
+    /// Thickness of the `Boxed` grid's lines.
+    pub grid_width: f32,
+    pub grid_color: OnagreColor,
+    /// Show a handle strip above the window's content that moves the
+    /// undecorated window when dragged.
+    pub draggable: bool,
 
     // Style
     pub background: OnagreColor,
@@ -111,17 +208,26 @@ pub struct Theme {
 
     // Children
     pub app_container: AppContainerStyles,
+    pub mode_badge: GenericContainerStyle,
+    pub detail: GenericContainerStyle,
 }
 
 impl Scale for Theme {
     fn scale(mut self, scale: f32) -> Self {
         self.app_container = self.app_container.scale(scale);
+        self.mode_badge = self.mode_badge.scale(scale);
+        self.detail = self.detail.scale(scale);
         self.min_width = self.min_width.scale(scale);
         self.max_width = self.max_width.scale(scale);
         self.min_height = self.min_height.scale(scale);
         self.max_height = self.max_height.scale(scale);
         self.padding = self.padding * scale;
         self.font_size = (self.font_size as f32 * scale) as u16;
+        self.row_height = self.row_height.map(|height| height * scale);
+        self.grid_width = self.grid_width.scale(scale);
+        for rule in &mut self.cell_rules {
+            rule.border_width = rule.border_width.map(|width| width.scale(scale));
+        }
         self
     }
 }
@@ -181,11 +287,76 @@ impl Default for Theme {
             border_radius: 0.0,
             border_width: 0.0,
             padding: OnagrePadding::ZERO,
+            scroll_policy: ScrollPolicy::default(),
+            tab_behavior: TabBehavior::default(),
+            confirm_empty: ConfirmEmpty::default(),
+            row_as_button: true,
+            empty_cell_placeholder: String::new(),
+            show_footer: false,
+            rtl: false,
+            row_height: None,
+            show_mode_badge: false,
+            show_column_types: false,
+            show_detail: false,
+            cell_flow_wrap: false,
+            format_keys: vec![("j".to_string(), OutputFormat::Json)],
+            cell_rules: Vec::new(),
+            grid_style: GridStyle::default(),
+            grid_width: 1.0,
+            grid_color: OnagreColor::DEFAULT_BORDER,
+            draggable: false,
             app_container: AppContainerStyles::default(),
+            mode_badge: GenericContainerStyle::default_mode_badge(),
+            detail: GenericContainerStyle::default_detail(),
         }
     }
 }
 
+impl Theme {
+    /// The height of a data row's container.
+    pub fn row_container_height(&self) -> Length {
+        self.row_height.map_or(Length::Shrink, Length::Fixed)
+    }
+
+    /// A cell's style in a `Boxed` grid: `base` padded with its row's
+    /// padding and outlined with half a grid line. An `outlined` cell, the
+    /// selected one in cell mode, keeps its own border.
+    pub fn boxed_cell(
+        &self,
+        base: &GenericContainerStyle,
+        row_padding: &OnagrePadding,
+        outlined: bool,
+    ) -> GenericContainerStyle {
+        let mut style = base.clone();
+        style.padding = base.padding.clone() + row_padding.clone();
+        if !outlined {
+            style.border_color = self.grid_color;
+            style.border_width = self.grid_width / 2.0;
+            style.border_radius = 0.0;
+        }
+        style
+    }
+
+    /// The other half of the `Boxed` grid's outer line, around the table.
+    pub fn grid_frame(&self) -> GenericContainerStyle {
+        GenericContainerStyle {
+            background: OnagreColor::TRANSPARENT,
+            border_color: self.grid_color,
+            border_width: self.grid_width / 2.0,
+            padding: OnagrePadding::ZERO,
+            ..Default::default()
+        }
+    }
+
+    /// The output format Ctrl plus `key` confirms with, if it is bound.
+    pub fn format_for_key(&self, key: &str) -> Option<OutputFormat> {
+        self.format_keys
+            .iter()
+            .find(|(bound, _)| bound.eq_ignore_ascii_case(key))
+            .map(|&(_, format)| format)
+    }
+}
+
 impl iced::widget::container::StyleSheet for &Theme {
     type Style = iced::Theme;
 
@@ -202,3 +373,20 @@ impl iced::widget::container::StyleSheet for &Theme {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_shrink_unless_a_height_is_set() {
+        assert_eq!(Theme::default().row_container_height(), Length::Shrink);
+
+        let theme = Theme {
+            row_height: Some(24.0),
+            ..Default::default()
+        };
+        assert_eq!(theme.row_container_height(), Length::Fixed(24.0));
+        assert_eq!(theme.scale(1.5).row_container_height(), Length::Fixed(36.0));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::app::style::rows::generic::GenericContainerStyle;
+use crate::config::color::OnagreColor;
+use crate::data::Table;
+use regex::Regex;
+
+/// How a `.cell-rule` matches a cell's value.
+#[derive(Debug, Clone)]
+pub enum CellMatch {
+    /// The value contains this text.
+    Substring(String),
+    Regex(Regex),
+}
+
+impl PartialEq for CellMatch {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (CellMatch::Substring(a), CellMatch::Substring(b)) => a == b,
+            (CellMatch::Regex(a), CellMatch::Regex(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
+/// A `.cell-rule` theme block: cells whose value it matches are drawn with
+/// its colors over the row's own cell style.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CellRule {
+    /// The header name, or 0-based index in a table without one, of the
+    /// only column the rule looks at. Every column when unset.
+    pub column: Option<String>,
+    /// A rule without a match is dropped when the theme is read.
+    pub matcher: Option<CellMatch>,
+    pub color: Option<OnagreColor>,
+    pub background: Option<OnagreColor>,
+    pub border_color: Option<OnagreColor>,
+    pub border_width: Option<f32>,
+    pub border_radius: Option<f32>,
+}
+
+impl CellRule {
+    /// Whether the rule applies to `value` in actual column `col`.
+    pub fn matches(&self, table: &Table, col: usize, value: &str) -> bool {
+        let in_column = match (&self.column, &table.headers) {
+            (None, _) => true,
+            (Some(spec), Some(headers)) => headers.get(col) == Some(spec),
+            (Some(spec), None) => spec.parse() == Ok(col),
+        };
+        in_column
+            && match &self.matcher {
+                Some(CellMatch::Substring(text)) => value.contains(text.as_str()),
+                Some(CellMatch::Regex(regex)) => regex.is_match(value),
+                None => false,
+            }
+    }
+
+    /// `base` with the colors and border the rule sets.
+    pub fn apply_to(&self, base: &GenericContainerStyle) -> GenericContainerStyle {
+        GenericContainerStyle {
+            color: self.color.unwrap_or(base.color),
+            background: self.background.unwrap_or(base.background),
+            border_color: self.border_color.unwrap_or(base.border_color),
+            border_width: self.border_width.unwrap_or(base.border_width),
+            border_radius: self.border_radius.unwrap_or(base.border_radius),
+            ..base.clone()
+        }
+    }
+}
+
+/// The style of a cell after `rules`: the first rule matching it, in order,
+/// applied to `base`. `None` when no rule matches.
+pub fn style_cell(
+    rules: &[CellRule],
+    base: &GenericContainerStyle,
+    table: &Table,
+    col: usize,
+    value: &str,
+) -> Option<GenericContainerStyle> {
+    rules
+        .iter()
+        .find(|rule| rule.matches(table, col, value))
+        .map(|rule| rule.apply_to(base))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn table() -> Table {
+        Table::new(
+            Some(vec!["test".to_string(), "status".to_string()]),
+            vec![vec!["FAIL-safe".to_string(), "FAIL".to_string()]],
+        )
+    }
+
+    fn rule(column: Option<&str>, matcher: CellMatch, color: OnagreColor) -> CellRule {
+        CellRule {
+            column: column.map(str::to_string),
+            matcher: Some(matcher),
+            color: Some(color),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_styles_the_cell() {
+        let rules = [
+            rule(
+                Some("status"),
+                CellMatch::Regex(Regex::new("^FAIL$").unwrap()),
+                OnagreColor::RED,
+            ),
+            rule(None, CellMatch::Substring("FAIL".into()), OnagreColor::WHITE),
+        ];
+        let base = GenericContainerStyle::default();
+        let table = table();
+
+        let status = style_cell(&rules, &base, &table, 1, "FAIL").unwrap();
+        assert_eq!(status.color, OnagreColor::RED);
+        assert_eq!(status.font_size, base.font_size);
+
+        // The first rule only looks at the status column
+        let test = style_cell(&rules, &base, &table, 0, "FAIL-safe").unwrap();
+        assert_eq!(test.color, OnagreColor::WHITE);
+
+        assert_eq!(style_cell(&rules, &base, &table, 1, "PASS"), None);
+    }
+
+    #[test]
+    fn columns_are_indices_without_a_header() {
+        let table = Table::new(None, vec![vec!["a".to_string(), "FAIL".to_string()]]);
+        let rule = rule(Some("1"), CellMatch::Substring("FAIL".into()), OnagreColor::RED);
+        assert!(rule.matches(&table, 1, "FAIL"));
+        assert!(!rule.matches(&table, 0, "FAIL"));
+    }
+}
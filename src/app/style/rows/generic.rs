@@ -71,7 +71,56 @@ impl StyleSheet for &GenericContainerStyle {
     }
 }
 
+/// A style made up while drawing, such as a cell's after a `.cell-rule`.
+impl StyleSheet for GenericContainerStyle {
+    type Style = iced::Theme;
+
+    fn appearance(&self, style: &Self::Style) -> Appearance {
+        (&self).appearance(style)
+    }
+}
+
 impl GenericContainerStyle {
+    /// The selected cell in Cell mode: outlined, and transparent so the
+    /// selected row's background shows through.
+    pub fn default_cell_selected() -> Self {
+        Self {
+            background: OnagreColor::TRANSPARENT,
+            color: OnagreColor::WHITE,
+            border_width: 1.0,
+            border_color: OnagreColor::DEFAULT_BORDER,
+            ..Default::default()
+        }
+    }
+
+    /// The active selection mode badge: a small outlined label.
+    pub fn default_mode_badge() -> Self {
+        Self {
+            font_size: 11,
+            width: Length::Shrink,
+            padding: OnagrePadding {
+                top: 2,
+                right: 6,
+                bottom: 2,
+                left: 6,
+            },
+            border_radius: 3.0,
+            border_width: 1.0,
+            border_color: OnagreColor::DEFAULT_BORDER,
+            ..Default::default()
+        }
+    }
+
+    /// The detail pane with the full selected value: padded and outlined.
+    pub fn default_detail() -> Self {
+        Self {
+            padding: OnagrePadding::from(6),
+            border_width: 1.0,
+            border_color: OnagreColor::DEFAULT_BORDER,
+            ..Default::default()
+        }
+    }
+
     pub fn description_default() -> Self {
         Self {
             font_size: 11,
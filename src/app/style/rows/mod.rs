@@ -9,6 +9,7 @@ use iced_core::{Background, Border};
 use iced_style::container::{Appearance, StyleSheet};
 
 pub mod button;
+pub mod cell_rule;
 pub mod generic;
 
 #[derive(Debug, PartialEq, Clone)]
@@ -84,6 +85,16 @@ impl Default for RowStyles {
 }
 
 impl RowStyles {
+    /// The keyboard cursor row in multi-select mode: an unselected row with
+    /// an outline.
+    pub fn default_cursor() -> Self {
+        Self {
+            border_width: 1.0,
+            border_color: OnagreColor::DEFAULT_BORDER,
+            ..Default::default()
+        }
+    }
+
     pub fn default_selected() -> Self {
         Self {
             color: OnagreColor::WHITE,
@@ -1,7 +1,9 @@
+use crate::app::style::rows::generic::GenericContainerStyle;
 use crate::app::style::rows::{HeaderRowStyle, RowStyles};
 use crate::app::style::Scale;
 use crate::config::color::OnagreColor;
 use crate::config::padding::OnagrePadding;
+use iced::alignment::Horizontal;
 use iced::Length;
 use iced_core::border::Radius;
 use iced_core::{Background, Border};
@@ -24,11 +26,17 @@ pub struct RowContainerStyle {
     pub height: Length,
 
     pub column_spacing: u16,
+    /// Per-column alignment overrides, by actual column index.
+    pub column_align: Vec<Horizontal>,
 
     // Children
     pub header: HeaderRowStyle,
     pub row: RowStyles,
     pub row_selected: RowStyles,
+    /// The cursor row in multi-select mode when it isn't checked.
+    pub row_cursor: RowStyles,
+    /// The one selected cell in Cell mode, drawn inside the selected row.
+    pub cell_selected: GenericContainerStyle,
 }
 
 impl Scale for RowContainerStyle {
@@ -41,12 +49,27 @@ impl Scale for RowContainerStyle {
         self.header = self.header.scale(scale);
         self.row = self.row.scale(scale);
         self.row_selected = self.row_selected.scale(scale);
+        self.row_cursor = self.row_cursor.scale(scale);
+        self.cell_selected = self.cell_selected.scale(scale);
         self
     }
 }
 
 impl Eq for RowContainerStyle {}
 
+impl RowContainerStyle {
+    /// Alignment for an actual column index: the `--column-align` override
+    /// if the theme sets one, otherwise right for numeric columns and left
+    /// for text.
+    pub fn column_alignment(&self, col: usize, numeric: bool) -> Horizontal {
+        self.column_align.get(col).copied().unwrap_or(if numeric {
+            Horizontal::Right
+        } else {
+            Horizontal::Left
+        })
+    }
+}
+
 impl StyleSheet for &RowContainerStyle {
     type Style = iced::Theme;
 
@@ -81,9 +104,38 @@ impl Default for RowContainerStyle {
             width: Length::Fill,
             height: Length::FillPortion(8),
             column_spacing: 0,
+            column_align: Vec::new(),
             header: HeaderRowStyle::default(),
             row: RowStyles::default(),
             row_selected: RowStyles::default_selected(),
+            row_cursor: RowStyles::default_cursor(),
+            cell_selected: GenericContainerStyle::default_cell_selected(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numeric_columns_align_right_by_default() {
+        let style = RowContainerStyle::default();
+
+        assert_eq!(style.column_alignment(0, true), Horizontal::Right);
+        assert_eq!(style.column_alignment(0, false), Horizontal::Left);
+    }
+
+    #[test]
+    fn theme_override_wins_over_detection() {
+        let style = RowContainerStyle {
+            column_align: vec![Horizontal::Center, Horizontal::Left],
+            ..Default::default()
+        };
+
+        assert_eq!(style.column_alignment(0, false), Horizontal::Center);
+        assert_eq!(style.column_alignment(1, true), Horizontal::Left);
+        // Columns past the override list fall back to detection
+        assert_eq!(style.column_alignment(2, true), Horizontal::Right);
+    }
+}
@@ -7,7 +7,7 @@ use iced_core::Border;
 use iced_style::scrollable::{Appearance, StyleSheet};
 use iced_style::theme::Scrollable;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct ScrollerStyles {
     pub background: OnagreColor,
     pub border_color: OnagreColor,
@@ -20,6 +20,10 @@ pub struct ScrollerStyles {
     pub scrollbar_margin: u16,
     pub scrollbar_width: u16,
     pub scroller_width: u16,
+    /// Show a "row X of Y" label under tables taller than the window.
+    pub show_position: bool,
+    pub position_color: OnagreColor,
+    pub position_font_size: u16,
 }
 
 impl Scale for ScrollerStyles {
@@ -29,6 +33,7 @@ impl Scale for ScrollerStyles {
         self.scrollbar_margin = self.scrollbar_margin.scale(scale);
         self.scrollbar_width = self.scrollbar_width.scale(scale);
         self.scroller_width = self.scroller_width.scale(scale);
+        self.position_font_size = self.position_font_size.scale(scale);
         self
     }
 }
@@ -49,6 +54,9 @@ impl Default for ScrollerStyles {
             scrollbar_margin: 0,
             scrollbar_width: 4,
             scroller_width: 6,
+            show_position: true,
+            position_color: OnagreColor::DEFAULT_TEXT,
+            position_font_size: 12,
         }
     }
 }
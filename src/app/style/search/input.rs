@@ -7,7 +7,7 @@ use iced_core::border::Radius;
 use iced_core::{Background, Border, Color};
 use iced_style::text_input::{Appearance, StyleSheet};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct SearchInputStyles {
     // Style
     pub background: OnagreColor,
@@ -16,7 +16,9 @@ pub struct SearchInputStyles {
     pub border_color: OnagreColor,
     pub placeholder_color: OnagreColor,
     pub value_color: OnagreColor,
-    pub selection_color: OnagreColor,
+    /// Highlight behind selected filter text. Unset, it is the value color
+    /// at 30% opacity so it stays visible on any background the text is.
+    pub selection_color: Option<OnagreColor>,
     pub text_width: Length,
 
     // Layout
@@ -73,7 +75,9 @@ impl StyleSheet for &SearchInputStyles {
     }
 
     fn selection_color(&self, _: &Self::Style) -> Color {
-        self.selection_color.into()
+        self.selection_color
+            .unwrap_or_else(|| self.value_color.with_alpha(0.3))
+            .into()
     }
 
     fn disabled(&self, _style: &Self::Style) -> Appearance {
@@ -98,7 +102,7 @@ impl Default for SearchInputStyles {
             background: OnagreColor::WHITE,
             placeholder_color: OnagreColor::DEFAULT_TEXT,
             value_color: OnagreColor::DEFAULT_TEXT,
-            selection_color: OnagreColor::DEFAULT_BORDER,
+            selection_color: None,
             text_width: Length::Fill,
             font_size: 14,
             width: Length::Fill,
@@ -12,7 +12,7 @@ use crate::config::padding::OnagrePadding;
 
 pub mod input;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct SearchContainerStyles {
     // Style
     pub background: OnagreColor,
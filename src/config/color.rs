@@ -72,6 +72,13 @@ impl OnagreColor {
         },
     };
 
+    /// The same color with its opacity replaced.
+    pub(crate) fn with_alpha(self, a: f32) -> Self {
+        OnagreColor {
+            color: Color { a, ..self.color },
+        }
+    }
+
     pub(crate) fn from(hex_color: &str) -> Result<Self, ConfigError> {
         let r = if let Some(red) = hex_color.get(1..3) {
             OnagreColor::f32_from_str_hex(red)
@@ -16,4 +16,6 @@ pub enum ConfigError {
     ParseFloat(#[from] ParseFloatError),
     #[error("Failed to parse '{0}' as hex color")]
     ParseColor(String),
+    #[error("Invalid regex: {0}")]
+    Regex(#[from] regex::Error),
 }
@@ -1,10 +1,14 @@
-use crate::app::style::{SizeSpec, SizeUnit};
+use crate::app::style::{
+    ConfirmEmpty, GridStyle, ScrollPolicy, SizeSpec, SizeUnit, TabBehavior,
+};
 use crate::config::color::OnagreColor;
 use crate::config::error::ConfigError;
 use crate::config::Rule;
+use crate::data::OutputFormat;
 use iced::alignment::{Horizontal, Vertical};
 use iced::Length;
 use pest::iterators::Pair;
+use tracing::warn;
 
 // Helper functions to get values from the css like theme config file.
 // We don't mind unwrapping here since pest
@@ -43,6 +47,32 @@ pub fn unwrap_attr_str(pair: Pair<'_, Rule>) -> &str {
     pair.into_inner().last().unwrap().into_inner().as_str()
 }
 
+/// A string attribute with its escapes (`\"`, `\\`, `\/`, `\n`, `\r` and
+/// `\t`) resolved. Other escapes are kept as written.
+pub fn unwrap_attr_unescaped(pair: Pair<'_, Rule>) -> String {
+    let raw = unwrap_attr_str(pair);
+    let mut value = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some(escaped @ ('"' | '\\' | '/')) => value.push(escaped),
+            Some('n') => value.push('\n'),
+            Some('r') => value.push('\r'),
+            Some('t') => value.push('\t'),
+            Some(other) => {
+                value.push('\\');
+                value.push(other);
+            }
+            None => value.push('\\'),
+        }
+    }
+    value
+}
+
 pub fn unwrap_attr_bool(pair: Pair<'_, Rule>) -> bool {
     let value = pair.into_inner().last().unwrap().as_str();
 
@@ -77,6 +107,65 @@ pub fn unwrap_size_spec(pair: Pair<'_, Rule>) -> Result<SizeSpec, ConfigError> {
     }
 }
 
+pub fn unwrap_scroll_policy(pair: Pair<'_, Rule>) -> ScrollPolicy {
+    let value = pair.into_inner().next().unwrap().into_inner().next().unwrap();
+    match value.as_rule() {
+        Rule::always_snap => ScrollPolicy::AlwaysSnap,
+        Rule::on_edge => ScrollPolicy::OnEdge,
+        _ => unreachable!(),
+    }
+}
+
+pub fn unwrap_tab_behavior(pair: Pair<'_, Rule>) -> TabBehavior {
+    let value = pair.into_inner().next().unwrap().into_inner().next().unwrap();
+    match value.as_rule() {
+        Rule::cycle_mode => TabBehavior::CycleMode,
+        Rule::focus_table => TabBehavior::FocusTable,
+        Rule::accept_completion => TabBehavior::AcceptCompletion,
+        _ => unreachable!(),
+    }
+}
+
+pub fn unwrap_confirm_empty(pair: Pair<'_, Rule>) -> ConfirmEmpty {
+    let value = pair.into_inner().next().unwrap().into_inner().next().unwrap();
+    match value.as_rule() {
+        Rule::cancel => ConfirmEmpty::Cancel,
+        Rule::ignore => ConfirmEmpty::Ignore,
+        Rule::clear_filter => ConfirmEmpty::ClearFilter,
+        _ => unreachable!(),
+    }
+}
+
+pub fn unwrap_grid_style(pair: Pair<'_, Rule>) -> GridStyle {
+    let value = pair.into_inner().next().unwrap().into_inner().next().unwrap();
+    match value.as_rule() {
+        Rule::borderless => GridStyle::Borderless,
+        Rule::boxed => GridStyle::Boxed,
+        _ => unreachable!(),
+    }
+}
+
+/// Keys with a built-in Ctrl binding, which `--format-keys` can't take.
+/// Ctrl+Y is left out on purpose: a binding may replace yanking.
+const RESERVED_FORMAT_KEYS: &[&str] = &["a", "d", "e", "f", "h", "l", "r", "u", "w"];
+
+/// The `"key" format` bindings of `--format-keys`. Keys with a built-in
+/// Ctrl binding are logged and skipped.
+pub fn unwrap_format_keys(pair: Pair<'_, Rule>) -> Vec<(String, OutputFormat)> {
+    pair.into_inner()
+        .filter_map(|binding| {
+            let mut binding = binding.into_inner();
+            let key = binding.next().unwrap().into_inner().as_str().to_string();
+            let format = OutputFormat::from_name(binding.next().unwrap().as_str()).unwrap();
+            if RESERVED_FORMAT_KEYS.iter().any(|reserved| reserved.eq_ignore_ascii_case(&key)) {
+                warn!("Ignoring --format-keys binding \"{key}\": Ctrl+{key} is a built-in key");
+                return None;
+            }
+            Some((key, format))
+        })
+        .collect()
+}
+
 pub fn unwrap_x(pair: Pair<'_, Rule>) -> Result<Horizontal, ConfigError> {
     let alignment = pair.into_inner().last().unwrap();
     let pair = alignment.into_inner().next().unwrap();
@@ -89,6 +178,20 @@ pub fn unwrap_x(pair: Pair<'_, Rule>) -> Result<Horizontal, ConfigError> {
     }
 }
 
+pub fn unwrap_x_list(pair: Pair<'_, Rule>) -> Result<Vec<Horizontal>, ConfigError> {
+    pair.into_inner()
+        .map(|alignment| {
+            let pair = alignment.into_inner().next().unwrap();
+            match pair.as_rule() {
+                Rule::left => Ok(Horizontal::Left),
+                Rule::center => Ok(Horizontal::Center),
+                Rule::right => Ok(Horizontal::Right),
+                _ => unreachable!(),
+            }
+        })
+        .collect()
+}
+
 pub fn unwrap_y(pair: Pair<'_, Rule>) -> Result<Vertical, ConfigError> {
     let alignment = pair.into_inner().last().unwrap();
     let pair = alignment.into_inner().next().unwrap();
@@ -124,10 +227,12 @@ pub fn unwrap_length(pair: Pair<'_, Rule>) -> Result<Length, ConfigError> {
 
 #[cfg(test)]
 mod test {
+    use crate::app::style::{ConfirmEmpty, ScrollPolicy, TabBehavior};
     use crate::config::color::OnagreColor;
     use crate::config::helpers::{
-        unwrap_attr_bool, unwrap_attr_f32, unwrap_attr_str, unwrap_attr_u16, unwrap_hex_color,
-        unwrap_length, unwrap_x, unwrap_y,
+        unwrap_attr_bool, unwrap_attr_f32, unwrap_attr_str, unwrap_attr_u16, unwrap_confirm_empty,
+        unwrap_hex_color, unwrap_length, unwrap_scroll_policy, unwrap_tab_behavior, unwrap_x,
+        unwrap_x_list, unwrap_y,
     };
     use crate::config::{Rule, ThemeParser};
     use iced::alignment::{Horizontal, Vertical};
@@ -150,6 +255,111 @@ mod test {
             .is_equal_to(Horizontal::Center);
     }
 
+    #[test]
+    fn should_parse_column_align() {
+        let pair = ThemeParser::parse(Rule::column_align, "--column-align: right left center;")
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let align = unwrap_x_list(pair);
+
+        asserting!("Should parse '--column-align' attribute")
+            .that(&align)
+            .is_ok()
+            .is_equal_to(vec![Horizontal::Right, Horizontal::Left, Horizontal::Center]);
+    }
+
+    #[test]
+    fn should_parse_scrollable_position() {
+        use crate::app::style::scrollable::scroller::ScrollerStyles;
+        use crate::config::ApplyConfig;
+
+        let pair = ThemeParser::parse(
+            Rule::scrollable,
+            ".scrollable {\n  --show-position: false;\n  .position { color: #ff0000; font-size: 10px; }\n}",
+        )
+        .unwrap()
+        .next()
+        .unwrap();
+
+        let mut styles = ScrollerStyles::default();
+        styles.apply(pair);
+
+        asserting!("Should parse '--show-position' attribute")
+            .that(&styles.show_position)
+            .is_false();
+        asserting!("Should parse '.position' font size")
+            .that(&styles.position_font_size)
+            .is_equal_to(10);
+    }
+
+    #[test]
+    fn should_parse_search_input_colors() {
+        use crate::app::style::search::input::SearchInputStyles;
+        use crate::config::ApplyConfig;
+
+        let pair = ThemeParser::parse(
+            Rule::bar,
+            ".input {\n  color: #ffffff;\n  --selection-color: #ff000080;\n}",
+        )
+        .unwrap()
+        .next()
+        .unwrap();
+
+        let mut styles = SearchInputStyles::default();
+        styles.apply(pair);
+
+        asserting!("Should parse '--selection-color' attribute")
+            .that(&styles.selection_color)
+            .is_equal_to(Some(OnagreColor::from("#ff000080").unwrap()));
+        asserting!("Should parse the value color")
+            .that(&styles.value_color)
+            .is_equal_to(OnagreColor::WHITE);
+    }
+
+    #[test]
+    fn should_parse_scroll_policy() {
+        let pair = ThemeParser::parse(Rule::scroll_policy, "--scroll-policy: on-edge;")
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let policy = unwrap_scroll_policy(pair);
+
+        asserting!("Should parse '--scroll-policy' attribute")
+            .that(&policy)
+            .is_equal_to(ScrollPolicy::OnEdge);
+    }
+
+    #[test]
+    fn should_parse_tab_behavior() {
+        let pair = ThemeParser::parse(Rule::tab_behavior, "--tab-behavior: accept-completion;")
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let behavior = unwrap_tab_behavior(pair);
+
+        asserting!("Should parse '--tab-behavior' attribute")
+            .that(&behavior)
+            .is_equal_to(TabBehavior::AcceptCompletion);
+    }
+
+    #[test]
+    fn should_parse_confirm_empty() {
+        let pair = ThemeParser::parse(Rule::confirm_empty, "--confirm-empty: clear-filter;")
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let policy = unwrap_confirm_empty(pair);
+
+        asserting!("Should parse '--confirm-empty' attribute")
+            .that(&policy)
+            .is_equal_to(ConfirmEmpty::ClearFilter);
+    }
+
     #[test]
     fn should_parse_align_y() {
         let pair = ThemeParser::parse(Rule::align_y, "--align-y: center;")
@@ -522,4 +732,44 @@ mod test {
             .that(&exit_unfocused)
             .is_true();
     }
+
+    #[test]
+    fn should_parse_empty_cell() {
+        let pair = ThemeParser::parse(Rule::empty_cell, r#"--empty-cell: "–";"#)
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let placeholder = unwrap_attr_str(pair);
+
+        asserting!("Should parse 'empty_cell' attribute")
+            .that(&placeholder)
+            .is_equal_to("–");
+    }
+
+    #[test]
+    fn should_parse_row_as_button() {
+        let pair = ThemeParser::parse(Rule::row_as_button, "--row-as-button: false;")
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let row_as_button = unwrap_attr_bool(pair);
+
+        asserting!("Should parse 'row_as_button' attribute")
+            .that(&row_as_button)
+            .is_false();
+    }
+
+    #[test]
+    fn should_parse_rtl() {
+        let pair = ThemeParser::parse(Rule::rtl, "--rtl: true;")
+            .unwrap()
+            .next()
+            .unwrap();
+
+        let rtl = unwrap_attr_bool(pair);
+
+        asserting!("Should parse 'rtl' attribute").that(&rtl).is_true();
+    }
 }
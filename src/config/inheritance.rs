@@ -66,6 +66,9 @@ impl Inherit for RowContainerStyle {
 
         self.row_selected.background = self.background;
         self.row_selected.propagate_background();
+
+        self.row_cursor.background = self.background;
+        self.row_cursor.propagate_background();
     }
 
     fn propagate_color(&mut self) {
@@ -76,6 +79,11 @@ impl Inherit for RowContainerStyle {
 
         self.row_selected.color = self.color;
         self.row_selected.propagate_color();
+
+        self.row_cursor.color = self.color;
+        self.row_cursor.propagate_color();
+
+        self.cell_selected.color = self.color;
     }
 
     fn propagate_font_size(&mut self) {}
@@ -9,10 +9,11 @@ use inheritance::Inherit;
 use pest::iterators::Pair;
 use pest::Parser;
 use pest_derive::Parser;
-use std::convert::TryFrom;
+use tracing::warn;
 use std::path::Path;
 
 use crate::app::style::app::AppContainerStyles;
+use crate::app::style::rows::cell_rule::{CellMatch, CellRule};
 use crate::app::style::rows::generic::GenericContainerStyle;
 use crate::app::style::rows::{HeaderRowStyle, RowStyles};
 use crate::app::style::scrollable::scroller::ScrollerStyles;
@@ -26,10 +27,15 @@ use padding::OnagrePadding;
 #[grammar = "config/grammar.pest"]
 struct ThemeParser;
 
-pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<Theme, ConfigError> {
+/// Parse the theme file at `path` on top of `theme`: the attributes it sets
+/// override, everything else keeps the value of the layers below.
+pub fn parse_layer<P: AsRef<Path>>(theme: Theme, path: P) -> Result<Theme, ConfigError> {
     let content = std::fs::read_to_string(path)?;
+    parse_layer_str(theme, &content)
+}
 
-    let pairs = ThemeParser::parse(Rule::stylesheet, &content)
+fn parse_layer_str(mut theme: Theme, content: &str) -> Result<Theme, ConfigError> {
+    let pairs = ThemeParser::parse(Rule::stylesheet, content)
         .map_err(Box::new)?
         .next()
         .unwrap();
@@ -37,7 +43,8 @@ pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<Theme, ConfigError> {
     if let Some(pair) = pairs.into_inner().next() {
         match pair.as_rule() {
             Rule::tabsel_style => {
-                return Theme::try_from(pair);
+                theme.apply(pair);
+                return Ok(theme);
             }
             _ => unreachable!(),
         }
@@ -47,96 +54,151 @@ pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<Theme, ConfigError> {
 }
 
 trait ApplyConfig {
-    fn apply(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError>;
+    /// Apply one attribute or nested block of this style.
+    fn apply_attribute(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError>;
+
+    /// Apply every attribute of a block, skipping the ones that fail.
+    fn apply(&mut self, pair: Pair<'_, Rule>) {
+        apply_each(pair, |pair| self.apply_attribute(pair));
+    }
 }
 
-impl TryFrom<Pair<'_, Rule>> for Theme {
-    type Error = ConfigError;
-    fn try_from(pair: Pair<'_, Rule>) -> Result<Self, Self::Error> {
-        let mut theme = Theme::base();
-
-        for pair in pair.into_inner() {
-            match pair.as_rule() {
-                Rule::exit_unfocused => theme.exit_unfocused = helpers::unwrap_attr_bool(pair),
-                Rule::font_family => theme.font = Some(helpers::unwrap_attr_str(pair).to_string()),
-                Rule::font_size => {
-                    theme.font_size = helpers::unwrap_attr_u16(pair)?;
-                    theme.propagate_font_size();
-                }
-                Rule::window_height => {
-                    let val = helpers::unwrap_attr_32(pair)? as f32;
-                    let spec = crate::app::style::SizeSpec::px(val);
-                    theme.min_height = spec;
-                    theme.max_height = spec;
-                }
-                Rule::window_width => {
-                    let val = helpers::unwrap_attr_32(pair)? as f32;
-                    let spec = crate::app::style::SizeSpec::px(val);
-                    theme.min_width = spec;
-                    theme.max_width = spec;
-                }
-                Rule::min_width => theme.min_width = helpers::unwrap_size_spec(pair)?,
-                Rule::max_width => theme.max_width = helpers::unwrap_size_spec(pair)?,
-                Rule::min_height => theme.min_height = helpers::unwrap_size_spec(pair)?,
-                Rule::max_height => theme.max_height = helpers::unwrap_size_spec(pair)?,
-                Rule::background => {
-                    theme.background = helpers::unwrap_hex_color(pair)?;
-                    theme.propagate_background();
-                }
-                Rule::color => {
-                    theme.color = helpers::unwrap_hex_color(pair)?;
-                    theme.propagate_color();
+/// Apply each attribute of a block with `f`. Attributes that are unknown, have
+/// a value they can't take or fail to convert are logged and skipped, keeping
+/// the value of the layers below, instead of failing the whole theme.
+fn apply_each<F>(pair: Pair<'_, Rule>, mut f: F)
+where
+    F: FnMut(Pair<'_, Rule>) -> Result<(), ConfigError>,
+{
+    for pair in pair.into_inner() {
+        let attribute = pair.as_str().trim().to_string();
+        match pair.as_rule() {
+            Rule::unknown_attribute | Rule::unknown_block => {
+                warn!("Ignoring unknown or invalid theme attribute `{attribute}`")
+            }
+            _ => {
+                if let Err(err) = f(pair) {
+                    warn!("Ignoring theme attribute `{attribute}`: {err}");
                 }
-                Rule::border_color => theme.border_color = helpers::unwrap_hex_color(pair)?,
-                Rule::border_width => theme.border_width = helpers::unwrap_attr_f32(pair)?,
-                Rule::border_radius => theme.border_radius = helpers::unwrap_attr_f32(pair)?,
-                Rule::padding => {
-                    theme.padding = OnagrePadding::from(helpers::unwrap_attr_u16(pair)?)
+            }
+        }
+    }
+}
+
+impl ApplyConfig for Theme {
+    fn apply_attribute(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError> {
+        let theme = self;
+
+        match pair.as_rule() {
+            Rule::exit_unfocused => theme.exit_unfocused = helpers::unwrap_attr_bool(pair),
+            Rule::scroll_policy => theme.scroll_policy = helpers::unwrap_scroll_policy(pair),
+            Rule::tab_behavior => theme.tab_behavior = helpers::unwrap_tab_behavior(pair),
+            Rule::confirm_empty => theme.confirm_empty = helpers::unwrap_confirm_empty(pair),
+            Rule::row_as_button => theme.row_as_button = helpers::unwrap_attr_bool(pair),
+            Rule::show_footer => theme.show_footer = helpers::unwrap_attr_bool(pair),
+            Rule::rtl => theme.rtl = helpers::unwrap_attr_bool(pair),
+            Rule::row_height => theme.row_height = Some(helpers::unwrap_attr_f32(pair)?),
+            Rule::show_mode_badge => theme.show_mode_badge = helpers::unwrap_attr_bool(pair),
+            Rule::mode_badge => theme.mode_badge.apply(pair),
+            Rule::show_column_types => {
+                theme.show_column_types = helpers::unwrap_attr_bool(pair)
+            }
+            Rule::show_detail => theme.show_detail = helpers::unwrap_attr_bool(pair),
+            Rule::cell_flow_wrap => theme.cell_flow_wrap = helpers::unwrap_attr_bool(pair),
+            Rule::grid => theme.grid_style = helpers::unwrap_grid_style(pair),
+            Rule::grid_width => theme.grid_width = helpers::unwrap_attr_f32(pair)?,
+            Rule::grid_color => theme.grid_color = helpers::unwrap_hex_color(pair)?,
+            Rule::draggable => theme.draggable = helpers::unwrap_attr_bool(pair),
+            Rule::format_keys => theme.format_keys = helpers::unwrap_format_keys(pair),
+            Rule::cell_rule => {
+                let mut rule = CellRule::default();
+                rule.apply(pair);
+                if rule.matcher.is_some() {
+                    theme.cell_rules.push(rule);
+                } else {
+                    warn!("Ignoring a .cell-rule block with no --match or --regex");
                 }
-                Rule::padding_top => theme.padding.top = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_bottom => theme.padding.bottom = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_right => theme.padding.right = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_left => theme.padding.left = helpers::unwrap_attr_u16(pair)?,
-                Rule::container => theme.app_container.apply(pair)?,
-                Rule::EOI => break,
-                _ => unreachable!(),
             }
+            Rule::detail => theme.detail.apply(pair),
+            Rule::empty_cell => {
+                theme.empty_cell_placeholder = helpers::unwrap_attr_str(pair).to_string()
+            }
+            Rule::font_family => theme.font = Some(helpers::unwrap_attr_str(pair).to_string()),
+            Rule::font_size => {
+                theme.font_size = helpers::unwrap_attr_u16(pair)?;
+                theme.propagate_font_size();
+            }
+            Rule::window_height => {
+                let val = helpers::unwrap_attr_32(pair)? as f32;
+                let spec = crate::app::style::SizeSpec::px(val);
+                theme.min_height = spec;
+                theme.max_height = spec;
+            }
+            Rule::window_width => {
+                let val = helpers::unwrap_attr_32(pair)? as f32;
+                let spec = crate::app::style::SizeSpec::px(val);
+                theme.min_width = spec;
+                theme.max_width = spec;
+            }
+            Rule::min_width => theme.min_width = helpers::unwrap_size_spec(pair)?,
+            Rule::max_width => theme.max_width = helpers::unwrap_size_spec(pair)?,
+            Rule::min_height => theme.min_height = helpers::unwrap_size_spec(pair)?,
+            Rule::max_height => theme.max_height = helpers::unwrap_size_spec(pair)?,
+            Rule::background => {
+                theme.background = helpers::unwrap_hex_color(pair)?;
+                theme.propagate_background();
+            }
+            Rule::color => {
+                theme.color = helpers::unwrap_hex_color(pair)?;
+                theme.propagate_color();
+            }
+            Rule::border_color => theme.border_color = helpers::unwrap_hex_color(pair)?,
+            Rule::border_width => theme.border_width = helpers::unwrap_attr_f32(pair)?,
+            Rule::border_radius => theme.border_radius = helpers::unwrap_attr_f32(pair)?,
+            Rule::padding => {
+                theme.padding = OnagrePadding::from(helpers::unwrap_attr_u16(pair)?)
+            }
+            Rule::padding_top => theme.padding.top = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_bottom => theme.padding.bottom = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_right => theme.padding.right = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_left => theme.padding.left = helpers::unwrap_attr_u16(pair)?,
+            Rule::container => theme.app_container.apply(pair),
+            Rule::EOI => {}
+            _ => unreachable!(),
         }
 
-        Ok(theme)
+        Ok(())
     }
 }
 
 impl ApplyConfig for AppContainerStyles {
-    fn apply(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError> {
-        for pair in pair.into_inner() {
-            match pair.as_rule() {
-                // Style
-                Rule::background => {
-                    self.background = helpers::unwrap_hex_color(pair)?;
-                    self.propagate_background();
-                }
-                Rule::color => {
-                    self.color = helpers::unwrap_hex_color(pair)?;
-                    self.propagate_color();
-                }
-                Rule::border_color => self.border_color = helpers::unwrap_hex_color(pair)?,
-                Rule::border_radius => self.border_radius = helpers::unwrap_attr_f32(pair)?,
-                Rule::border_width => self.border_width = helpers::unwrap_attr_f32(pair)?,
-                // Padding
-                Rule::padding => {
-                    self.padding = OnagrePadding::from(helpers::unwrap_attr_u16(pair)?)
-                }
-                Rule::padding_top => self.padding.top = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_bottom => self.padding.bottom = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_right => self.padding.right = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_left => self.padding.left = helpers::unwrap_attr_u16(pair)?,
-                // Children
-                Rule::search => self.search.apply(pair)?,
-                Rule::rows => self.rows.apply(pair)?,
-                Rule::scrollable => self.scrollable.apply(pair)?,
-                _ => unreachable!(),
+    fn apply_attribute(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError> {
+        match pair.as_rule() {
+            // Style
+            Rule::background => {
+                self.background = helpers::unwrap_hex_color(pair)?;
+                self.propagate_background();
+            }
+            Rule::color => {
+                self.color = helpers::unwrap_hex_color(pair)?;
+                self.propagate_color();
             }
+            Rule::border_color => self.border_color = helpers::unwrap_hex_color(pair)?,
+            Rule::border_radius => self.border_radius = helpers::unwrap_attr_f32(pair)?,
+            Rule::border_width => self.border_width = helpers::unwrap_attr_f32(pair)?,
+            // Padding
+            Rule::padding => {
+                self.padding = OnagrePadding::from(helpers::unwrap_attr_u16(pair)?)
+            }
+            Rule::padding_top => self.padding.top = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_bottom => self.padding.bottom = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_right => self.padding.right = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_left => self.padding.left = helpers::unwrap_attr_u16(pair)?,
+            // Children
+            Rule::search => self.search.apply(pair),
+            Rule::rows => self.rows.apply(pair),
+            Rule::scrollable => self.scrollable.apply(pair),
+            _ => unreachable!(),
         }
 
         Ok(())
@@ -144,38 +206,42 @@ impl ApplyConfig for AppContainerStyles {
 }
 
 impl ApplyConfig for ScrollerStyles {
-    fn apply(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError> {
-        for pair in pair.into_inner() {
-            match pair.as_rule() {
-                // Style
-                Rule::background => self.background = helpers::unwrap_hex_color(pair)?,
-                Rule::border_color => self.border_color = helpers::unwrap_hex_color(pair)?,
-                Rule::border_radius => self.border_radius = helpers::unwrap_attr_f32(pair)?,
-                Rule::border_width => self.border_width = helpers::unwrap_attr_f32(pair)?,
-                Rule::scrollbar_margin => self.scrollbar_margin = helpers::unwrap_attr_u16(pair)?,
-                Rule::scrollbar_width => self.scrollbar_width = helpers::unwrap_attr_u16(pair)?,
-                Rule::scroller => {
-                    for pair in pair.into_inner() {
-                        match pair.as_rule() {
-                            Rule::color => self.scroller_color = helpers::unwrap_hex_color(pair)?,
-                            Rule::border_color => {
-                                self.scroller_border_color = helpers::unwrap_hex_color(pair)?
-                            }
-                            Rule::border_width => {
-                                self.scroller_border_width = helpers::unwrap_attr_f32(pair)?
-                            }
-                            Rule::border_radius => {
-                                self.scroller_border_radius = helpers::unwrap_attr_f32(pair)?
-                            }
-                            Rule::scroller_width => {
-                                self.scroller_width = helpers::unwrap_attr_u16(pair)?
-                            }
-                            _ => unreachable!(),
-                        }
+    fn apply_attribute(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError> {
+        match pair.as_rule() {
+            // Style
+            Rule::background => self.background = helpers::unwrap_hex_color(pair)?,
+            Rule::border_color => self.border_color = helpers::unwrap_hex_color(pair)?,
+            Rule::border_radius => self.border_radius = helpers::unwrap_attr_f32(pair)?,
+            Rule::border_width => self.border_width = helpers::unwrap_attr_f32(pair)?,
+            Rule::scrollbar_margin => self.scrollbar_margin = helpers::unwrap_attr_u16(pair)?,
+            Rule::scrollbar_width => self.scrollbar_width = helpers::unwrap_attr_u16(pair)?,
+            Rule::show_position => self.show_position = helpers::unwrap_attr_bool(pair),
+            Rule::position => apply_each(pair, |pair| {
+                match pair.as_rule() {
+                    Rule::color => self.position_color = helpers::unwrap_hex_color(pair)?,
+                    Rule::font_size => self.position_font_size = helpers::unwrap_attr_u16(pair)?,
+                    _ => unreachable!(),
+                }
+                Ok(())
+            }),
+            Rule::scroller => apply_each(pair, |pair| {
+                match pair.as_rule() {
+                    Rule::color => self.scroller_color = helpers::unwrap_hex_color(pair)?,
+                    Rule::border_color => {
+                        self.scroller_border_color = helpers::unwrap_hex_color(pair)?
+                    }
+                    Rule::border_width => {
+                        self.scroller_border_width = helpers::unwrap_attr_f32(pair)?
+                    }
+                    Rule::border_radius => {
+                        self.scroller_border_radius = helpers::unwrap_attr_f32(pair)?
                     }
+                    Rule::scroller_width => self.scroller_width = helpers::unwrap_attr_u16(pair)?,
+                    _ => unreachable!(),
                 }
-                _ => unreachable!(),
-            }
+                Ok(())
+            }),
+            _ => unreachable!(),
         }
 
         Ok(())
@@ -183,41 +249,39 @@ impl ApplyConfig for ScrollerStyles {
 }
 
 impl ApplyConfig for SearchContainerStyles {
-    fn apply(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError> {
-        for pair in pair.into_inner() {
-            match pair.as_rule() {
-                // Style
-                Rule::background => {
-                    self.background = helpers::unwrap_hex_color(pair)?;
-                    self.propagate_background();
-                }
-                Rule::color => {
-                    self.color = helpers::unwrap_hex_color(pair)?;
-                    self.propagate_color();
-                }
-                Rule::border_color => self.border_color = helpers::unwrap_hex_color(pair)?,
-                Rule::border_radius => self.border_radius = helpers::unwrap_attr_f32(pair)?,
-                Rule::border_width => self.border_width = helpers::unwrap_attr_f32(pair)?,
-
-                // Layout
-                Rule::padding => {
-                    self.padding = OnagrePadding::from(helpers::unwrap_attr_u16(pair)?)
-                }
-                Rule::padding_top => self.padding.top = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_bottom => self.padding.bottom = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_right => self.padding.right = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_left => self.padding.left = helpers::unwrap_attr_u16(pair)?,
-                Rule::spacing => self.spacing = helpers::unwrap_attr_u16(pair)?,
-                Rule::width => self.width = helpers::unwrap_length(pair)?,
-                Rule::height => self.height = helpers::unwrap_length(pair)?,
-                Rule::align_x => self.align_x = helpers::unwrap_x(pair)?,
-                Rule::align_y => self.align_y = helpers::unwrap_y(pair)?,
-
-                // Children
-                Rule::bar => self.input.apply(pair)?,
+    fn apply_attribute(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError> {
+        match pair.as_rule() {
+            // Style
+            Rule::background => {
+                self.background = helpers::unwrap_hex_color(pair)?;
+                self.propagate_background();
+            }
+            Rule::color => {
+                self.color = helpers::unwrap_hex_color(pair)?;
+                self.propagate_color();
+            }
+            Rule::border_color => self.border_color = helpers::unwrap_hex_color(pair)?,
+            Rule::border_radius => self.border_radius = helpers::unwrap_attr_f32(pair)?,
+            Rule::border_width => self.border_width = helpers::unwrap_attr_f32(pair)?,
 
-                _ => unreachable!(),
+            // Layout
+            Rule::padding => {
+                self.padding = OnagrePadding::from(helpers::unwrap_attr_u16(pair)?)
             }
+            Rule::padding_top => self.padding.top = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_bottom => self.padding.bottom = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_right => self.padding.right = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_left => self.padding.left = helpers::unwrap_attr_u16(pair)?,
+            Rule::spacing => self.spacing = helpers::unwrap_attr_u16(pair)?,
+            Rule::width => self.width = helpers::unwrap_length(pair)?,
+            Rule::height => self.height = helpers::unwrap_length(pair)?,
+            Rule::align_x => self.align_x = helpers::unwrap_x(pair)?,
+            Rule::align_y => self.align_y = helpers::unwrap_y(pair)?,
+
+            // Children
+            Rule::bar => self.input.apply(pair),
+
+            _ => unreachable!(),
         }
 
         Ok(())
@@ -225,36 +289,36 @@ impl ApplyConfig for SearchContainerStyles {
 }
 
 impl ApplyConfig for SearchInputStyles {
-    fn apply(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError> {
-        for pair in pair.into_inner() {
-            match pair.as_rule() {
-                // Style
-                Rule::background => self.background = helpers::unwrap_hex_color(pair)?,
-                Rule::color => self.value_color = helpers::unwrap_hex_color(pair)?,
-                Rule::border_color => self.border_color = helpers::unwrap_hex_color(pair)?,
-                Rule::border_radius => self.border_radius = helpers::unwrap_attr_f32(pair)?,
-                Rule::border_width => self.border_width = helpers::unwrap_attr_f32(pair)?,
-                Rule::text_width => self.text_width = helpers::unwrap_length(pair)?,
-                Rule::selection_color => self.selection_color = helpers::unwrap_hex_color(pair)?,
-                Rule::placeholder_color => {
-                    self.placeholder_color = helpers::unwrap_hex_color(pair)?
-                }
-                Rule::font_size => self.font_size = helpers::unwrap_attr_u16(pair)?,
+    fn apply_attribute(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError> {
+        match pair.as_rule() {
+            // Style
+            Rule::background => self.background = helpers::unwrap_hex_color(pair)?,
+            Rule::color => self.value_color = helpers::unwrap_hex_color(pair)?,
+            Rule::border_color => self.border_color = helpers::unwrap_hex_color(pair)?,
+            Rule::border_radius => self.border_radius = helpers::unwrap_attr_f32(pair)?,
+            Rule::border_width => self.border_width = helpers::unwrap_attr_f32(pair)?,
+            Rule::text_width => self.text_width = helpers::unwrap_length(pair)?,
+            Rule::selection_color => {
+                self.selection_color = Some(helpers::unwrap_hex_color(pair)?)
+            }
+            Rule::placeholder_color => {
+                self.placeholder_color = helpers::unwrap_hex_color(pair)?
+            }
+            Rule::font_size => self.font_size = helpers::unwrap_attr_u16(pair)?,
 
-                // Layout
-                Rule::padding => {
-                    self.padding = OnagrePadding::from(helpers::unwrap_attr_u16(pair)?)
-                }
-                Rule::padding_top => self.padding.top = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_bottom => self.padding.bottom = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_right => self.padding.right = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_left => self.padding.left = helpers::unwrap_attr_u16(pair)?,
-                Rule::width => self.width = helpers::unwrap_length(pair)?,
-                Rule::height => self.height = helpers::unwrap_length(pair)?,
-                Rule::align_x => self.align_x = helpers::unwrap_x(pair)?,
-                Rule::align_y => self.align_y = helpers::unwrap_y(pair)?,
-                _ => unreachable!(),
+            // Layout
+            Rule::padding => {
+                self.padding = OnagrePadding::from(helpers::unwrap_attr_u16(pair)?)
             }
+            Rule::padding_top => self.padding.top = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_bottom => self.padding.bottom = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_right => self.padding.right = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_left => self.padding.left = helpers::unwrap_attr_u16(pair)?,
+            Rule::width => self.width = helpers::unwrap_length(pair)?,
+            Rule::height => self.height = helpers::unwrap_length(pair)?,
+            Rule::align_x => self.align_x = helpers::unwrap_x(pair)?,
+            Rule::align_y => self.align_y = helpers::unwrap_y(pair)?,
+            _ => unreachable!(),
         }
 
         Ok(())
@@ -262,40 +326,41 @@ impl ApplyConfig for SearchInputStyles {
 }
 
 impl ApplyConfig for RowContainerStyle {
-    fn apply(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError> {
-        for pair in pair.into_inner() {
-            match pair.as_rule() {
-                // Style
-                Rule::color => {
-                    self.color = helpers::unwrap_hex_color(pair)?;
-                    self.propagate_color();
-                }
-                Rule::background => {
-                    self.background = helpers::unwrap_hex_color(pair)?;
-                    self.propagate_color();
-                }
-                Rule::border_color => self.border_color = helpers::unwrap_hex_color(pair)?,
-                Rule::border_radius => self.border_radius = helpers::unwrap_attr_f32(pair)?,
-                Rule::border_width => self.border_width = helpers::unwrap_attr_f32(pair)?,
+    fn apply_attribute(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError> {
+        match pair.as_rule() {
+            // Style
+            Rule::color => {
+                self.color = helpers::unwrap_hex_color(pair)?;
+                self.propagate_color();
+            }
+            Rule::background => {
+                self.background = helpers::unwrap_hex_color(pair)?;
+                self.propagate_color();
+            }
+            Rule::border_color => self.border_color = helpers::unwrap_hex_color(pair)?,
+            Rule::border_radius => self.border_radius = helpers::unwrap_attr_f32(pair)?,
+            Rule::border_width => self.border_width = helpers::unwrap_attr_f32(pair)?,
 
-                // Padding
-                Rule::padding => {
-                    self.padding = OnagrePadding::from(helpers::unwrap_attr_u16(pair)?)
-                }
-                Rule::padding_top => self.padding.top = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_bottom => self.padding.bottom = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_right => self.padding.right = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_left => self.padding.left = helpers::unwrap_attr_u16(pair)?,
-                Rule::width => self.width = helpers::unwrap_length(pair)?,
-                Rule::height => self.height = helpers::unwrap_length(pair)?,
-                Rule::column_spacing => self.column_spacing = helpers::unwrap_attr_u16(pair)?,
-
-                // Children
-                Rule::header_row => self.header.apply(pair)?,
-                Rule::default_row => self.row.apply(pair)?,
-                Rule::selected_row => self.row_selected.apply(pair)?,
-                _ => unreachable!(),
+            // Padding
+            Rule::padding => {
+                self.padding = OnagrePadding::from(helpers::unwrap_attr_u16(pair)?)
             }
+            Rule::padding_top => self.padding.top = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_bottom => self.padding.bottom = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_right => self.padding.right = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_left => self.padding.left = helpers::unwrap_attr_u16(pair)?,
+            Rule::width => self.width = helpers::unwrap_length(pair)?,
+            Rule::height => self.height = helpers::unwrap_length(pair)?,
+            Rule::column_spacing => self.column_spacing = helpers::unwrap_attr_u16(pair)?,
+            Rule::column_align => self.column_align = helpers::unwrap_x_list(pair)?,
+
+            // Children
+            Rule::header_row => self.header.apply(pair),
+            Rule::default_row => self.row.apply(pair),
+            Rule::selected_row => self.row_selected.apply(pair),
+            Rule::cursor_row => self.row_cursor.apply(pair),
+            Rule::selected_cell => self.cell_selected.apply(pair),
+            _ => unreachable!(),
         }
 
         Ok(())
@@ -303,44 +368,42 @@ impl ApplyConfig for RowContainerStyle {
 }
 
 impl ApplyConfig for RowStyles {
-    fn apply(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError> {
-        for pair in pair.into_inner() {
-            match pair.as_rule() {
-                // Iced style
-                Rule::background => {
-                    self.background = helpers::unwrap_hex_color(pair)?;
-                    self.propagate_background();
-                }
-                Rule::border_color => self.border_color = helpers::unwrap_hex_color(pair)?,
-                Rule::border_radius => self.border_radius = helpers::unwrap_attr_f32(pair)?,
-                Rule::border_width => self.border_width = helpers::unwrap_attr_f32(pair)?,
-                Rule::color => {
-                    self.color = helpers::unwrap_hex_color(pair)?;
-                    self.propagate_color();
-                }
+    fn apply_attribute(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError> {
+        match pair.as_rule() {
+            // Iced style
+            Rule::background => {
+                self.background = helpers::unwrap_hex_color(pair)?;
+                self.propagate_background();
+            }
+            Rule::border_color => self.border_color = helpers::unwrap_hex_color(pair)?,
+            Rule::border_radius => self.border_radius = helpers::unwrap_attr_f32(pair)?,
+            Rule::border_width => self.border_width = helpers::unwrap_attr_f32(pair)?,
+            Rule::color => {
+                self.color = helpers::unwrap_hex_color(pair)?;
+                self.propagate_color();
+            }
 
-                // Iced Layout
-                Rule::padding => {
-                    self.padding = OnagrePadding::from(helpers::unwrap_attr_u16(pair)?)
-                }
-                Rule::padding_top => self.padding.top = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_bottom => self.padding.bottom = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_right => self.padding.right = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_left => self.padding.left = helpers::unwrap_attr_u16(pair)?,
-                Rule::spacing => self.spacing = helpers::unwrap_attr_u16(pair)?,
-                Rule::width => self.width = helpers::unwrap_length(pair)?,
-                Rule::height => self.height = helpers::unwrap_length(pair)?,
-                Rule::align_x => self.align_x = helpers::unwrap_x(pair)?,
-                Rule::align_y => self.align_y = helpers::unwrap_y(pair)?,
-
-                // Children
-                Rule::description_row => {
-                    self.hide_description = false;
-                    self.description.apply(pair)?
-                }
-                Rule::title_row => self.title.apply(pair)?,
-                _ => unreachable!(),
+            // Iced Layout
+            Rule::padding => {
+                self.padding = OnagrePadding::from(helpers::unwrap_attr_u16(pair)?)
+            }
+            Rule::padding_top => self.padding.top = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_bottom => self.padding.bottom = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_right => self.padding.right = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_left => self.padding.left = helpers::unwrap_attr_u16(pair)?,
+            Rule::spacing => self.spacing = helpers::unwrap_attr_u16(pair)?,
+            Rule::width => self.width = helpers::unwrap_length(pair)?,
+            Rule::height => self.height = helpers::unwrap_length(pair)?,
+            Rule::align_x => self.align_x = helpers::unwrap_x(pair)?,
+            Rule::align_y => self.align_y = helpers::unwrap_y(pair)?,
+
+            // Children
+            Rule::description_row => {
+                self.hide_description = false;
+                self.description.apply(pair)
             }
+            Rule::title_row => self.title.apply(pair),
+            _ => unreachable!(),
         }
 
         Ok(())
@@ -348,34 +411,32 @@ impl ApplyConfig for RowStyles {
 }
 
 impl ApplyConfig for HeaderRowStyle {
-    fn apply(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError> {
-        for pair in pair.into_inner() {
-            match pair.as_rule() {
-                // Style
-                Rule::background => self.background = helpers::unwrap_hex_color(pair)?,
-                Rule::color => self.color = helpers::unwrap_hex_color(pair)?,
-                Rule::border_color => self.border_color = helpers::unwrap_hex_color(pair)?,
-                Rule::border_radius => self.border_radius = helpers::unwrap_attr_f32(pair)?,
-                Rule::border_width => self.border_width = helpers::unwrap_attr_f32(pair)?,
-                Rule::font_size => self.font_size = helpers::unwrap_attr_u16(pair)?,
-                Rule::separator_color => self.separator_color = helpers::unwrap_hex_color(pair)?,
-                Rule::separator_width => self.separator_width = helpers::unwrap_attr_f32(pair)?,
-
-                // Layout
-                Rule::padding => {
-                    self.padding = OnagrePadding::from(helpers::unwrap_attr_u16(pair)?)
-                }
-                Rule::padding_top => self.padding.top = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_bottom => self.padding.bottom = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_right => self.padding.right = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_left => self.padding.left = helpers::unwrap_attr_u16(pair)?,
-                Rule::spacing => self.spacing = helpers::unwrap_attr_u16(pair)?,
-                Rule::width => self.width = helpers::unwrap_length(pair)?,
-                Rule::height => self.height = helpers::unwrap_length(pair)?,
-                Rule::align_x => self.align_x = helpers::unwrap_x(pair)?,
-                Rule::align_y => self.align_y = helpers::unwrap_y(pair)?,
-                _ => unreachable!(),
+    fn apply_attribute(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError> {
+        match pair.as_rule() {
+            // Style
+            Rule::background => self.background = helpers::unwrap_hex_color(pair)?,
+            Rule::color => self.color = helpers::unwrap_hex_color(pair)?,
+            Rule::border_color => self.border_color = helpers::unwrap_hex_color(pair)?,
+            Rule::border_radius => self.border_radius = helpers::unwrap_attr_f32(pair)?,
+            Rule::border_width => self.border_width = helpers::unwrap_attr_f32(pair)?,
+            Rule::font_size => self.font_size = helpers::unwrap_attr_u16(pair)?,
+            Rule::separator_color => self.separator_color = helpers::unwrap_hex_color(pair)?,
+            Rule::separator_width => self.separator_width = helpers::unwrap_attr_f32(pair)?,
+
+            // Layout
+            Rule::padding => {
+                self.padding = OnagrePadding::from(helpers::unwrap_attr_u16(pair)?)
             }
+            Rule::padding_top => self.padding.top = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_bottom => self.padding.bottom = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_right => self.padding.right = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_left => self.padding.left = helpers::unwrap_attr_u16(pair)?,
+            Rule::spacing => self.spacing = helpers::unwrap_attr_u16(pair)?,
+            Rule::width => self.width = helpers::unwrap_length(pair)?,
+            Rule::height => self.height = helpers::unwrap_length(pair)?,
+            Rule::align_x => self.align_x = helpers::unwrap_x(pair)?,
+            Rule::align_y => self.align_y = helpers::unwrap_y(pair)?,
+            _ => unreachable!(),
         }
 
         Ok(())
@@ -383,30 +444,51 @@ impl ApplyConfig for HeaderRowStyle {
 }
 
 impl ApplyConfig for GenericContainerStyle {
-    fn apply(&mut self, pair: Pair<Rule>) -> Result<(), ConfigError> {
-        for pair in pair.into_inner() {
-            match pair.as_rule() {
-                // Iced style
-                Rule::background => self.background = helpers::unwrap_hex_color(pair)?,
-                Rule::border_radius => self.border_radius = helpers::unwrap_attr_f32(pair)?,
-                Rule::border_width => self.border_width = helpers::unwrap_attr_f32(pair)?,
-                Rule::border_color => self.border_color = helpers::unwrap_hex_color(pair)?,
-                Rule::color => self.color = helpers::unwrap_hex_color(pair)?,
-                // Iced Layout
-                Rule::padding => {
-                    self.padding = OnagrePadding::from(helpers::unwrap_attr_u16(pair)?)
-                }
-                Rule::padding_top => self.padding.top = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_bottom => self.padding.bottom = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_right => self.padding.right = helpers::unwrap_attr_u16(pair)?,
-                Rule::padding_left => self.padding.left = helpers::unwrap_attr_u16(pair)?,
-                Rule::align_x => self.align_x = helpers::unwrap_x(pair)?,
-                Rule::align_y => self.align_y = helpers::unwrap_y(pair)?,
-                Rule::width => self.width = helpers::unwrap_length(pair)?,
-                Rule::height => self.height = helpers::unwrap_length(pair)?,
-                Rule::font_size => self.font_size = helpers::unwrap_attr_u16(pair)?,
-                _ => unreachable!(),
+    fn apply_attribute(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError> {
+        match pair.as_rule() {
+            // Iced style
+            Rule::background => self.background = helpers::unwrap_hex_color(pair)?,
+            Rule::border_radius => self.border_radius = helpers::unwrap_attr_f32(pair)?,
+            Rule::border_width => self.border_width = helpers::unwrap_attr_f32(pair)?,
+            Rule::border_color => self.border_color = helpers::unwrap_hex_color(pair)?,
+            Rule::color => self.color = helpers::unwrap_hex_color(pair)?,
+            // Iced Layout
+            Rule::padding => {
+                self.padding = OnagrePadding::from(helpers::unwrap_attr_u16(pair)?)
+            }
+            Rule::padding_top => self.padding.top = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_bottom => self.padding.bottom = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_right => self.padding.right = helpers::unwrap_attr_u16(pair)?,
+            Rule::padding_left => self.padding.left = helpers::unwrap_attr_u16(pair)?,
+            Rule::align_x => self.align_x = helpers::unwrap_x(pair)?,
+            Rule::align_y => self.align_y = helpers::unwrap_y(pair)?,
+            Rule::width => self.width = helpers::unwrap_length(pair)?,
+            Rule::height => self.height = helpers::unwrap_length(pair)?,
+            Rule::font_size => self.font_size = helpers::unwrap_attr_u16(pair)?,
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+}
+
+impl ApplyConfig for CellRule {
+    fn apply_attribute(&mut self, pair: Pair<'_, Rule>) -> Result<(), ConfigError> {
+        match pair.as_rule() {
+            Rule::rule_column => self.column = Some(helpers::unwrap_attr_str(pair).to_string()),
+            Rule::rule_match => {
+                self.matcher = Some(CellMatch::Substring(helpers::unwrap_attr_unescaped(pair)))
+            }
+            Rule::rule_regex => {
+                let regex = regex::Regex::new(&helpers::unwrap_attr_unescaped(pair))?;
+                self.matcher = Some(CellMatch::Regex(regex));
             }
+            Rule::background => self.background = Some(helpers::unwrap_hex_color(pair)?),
+            Rule::color => self.color = Some(helpers::unwrap_hex_color(pair)?),
+            Rule::border_color => self.border_color = Some(helpers::unwrap_hex_color(pair)?),
+            Rule::border_radius => self.border_radius = Some(helpers::unwrap_attr_f32(pair)?),
+            Rule::border_width => self.border_width = Some(helpers::unwrap_attr_f32(pair)?),
+            _ => unreachable!(),
         }
 
         Ok(())
@@ -414,7 +496,8 @@ impl ApplyConfig for GenericContainerStyle {
 }
 
 impl Theme {
-    fn base() -> Self {
+    /// What the first theme file is applied to.
+    pub fn base() -> Self {
         Theme {
             app_container: AppContainerStyles {
                 rows: RowContainerStyle {
@@ -426,6 +509,10 @@ impl Theme {
                         hide_description: true,
                         ..Default::default()
                     },
+                    row_cursor: RowStyles {
+                        hide_description: true,
+                        ..RowStyles::default_cursor()
+                    },
                     ..Default::default()
                 },
                 ..Default::default()
@@ -434,3 +521,218 @@ impl Theme {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::inheritance::Inherit;
+    use super::parse_layer_str;
+    use crate::app::style::Theme;
+    use crate::config::color::OnagreColor;
+    use crate::app::style::rows::cell_rule;
+    use crate::app::style::rows::generic::GenericContainerStyle;
+    use crate::app::style::{GridStyle, Scale};
+    use crate::config::padding::OnagrePadding;
+    use crate::data::{OutputFormat, Table};
+    use speculoos::prelude::*;
+
+    #[test]
+    fn only_font_size_set_leaves_everything_else_at_the_base() {
+        let content = ".tabsel {\n  font-size: 22px;\n}\n";
+        let theme = parse_layer_str(Theme::base(), content).unwrap();
+
+        let mut expected = Theme::base();
+        expected.font_size = 22;
+        expected.propagate_font_size();
+        asserting!("Only the font size changes").that(&theme).is_equal_to(expected);
+    }
+
+    #[test]
+    fn unknown_and_invalid_attributes_are_skipped() {
+        let content = ".tabsel {\n  --no-such-thing: 3;\n  font-size: big;\n  \
+                       background: #zzzzzz;\n  --rtl: true;\n  .widgets {\n    color: red;\n  }\n}\n";
+        let theme = parse_layer_str(Theme::base(), content).unwrap();
+
+        let mut expected = Theme::base();
+        expected.rtl = true;
+        asserting!("Only the valid attribute applies").that(&theme).is_equal_to(expected);
+    }
+
+    #[test]
+    fn cell_selected_is_styled_on_its_own() {
+        let content = ".tabsel {\n  .container {\n    .rows {\n      .cell-selected {\n        \
+                       border-width: 2px;\n        border-color: #ffffff;\n      }\n    }\n  }\n}\n";
+        let theme = parse_layer_str(Theme::base(), content).unwrap();
+
+        let rows = &theme.app_container.rows;
+        asserting!("The selected cell gets its own border")
+            .that(&(rows.cell_selected.border_width, rows.cell_selected.border_color))
+            .is_equal_to((2.0, OnagreColor::WHITE));
+        asserting!("The selected row is untouched")
+            .that(&rows.row_selected)
+            .is_equal_to(&Theme::base().app_container.rows.row_selected);
+    }
+
+    #[test]
+    fn mode_badge_is_toggled_and_styled() {
+        let content = ".tabsel {\n  --show-mode-badge: true;\n  .mode-badge {\n    \
+                       font-size: 14px;\n    color: #ffffff;\n  }\n}\n";
+        let theme = parse_layer_str(Theme::base(), content).unwrap();
+
+        asserting!("The badge is shown")
+            .that(&theme.show_mode_badge)
+            .is_true();
+        asserting!("The badge takes its own style")
+            .that(&(theme.mode_badge.font_size, theme.mode_badge.color))
+            .is_equal_to((14, OnagreColor::WHITE));
+        asserting!("Unset badge attributes keep their defaults")
+            .that(&theme.mode_badge.border_radius)
+            .is_equal_to(3.0);
+    }
+
+    #[test]
+    fn detail_pane_is_toggled_and_styled() {
+        let content = ".tabsel {\n  --show-detail: true;\n  .detail {\n    font-size: 16px;\n  }\n}\n";
+        let theme = parse_layer_str(Theme::base(), content).unwrap();
+
+        asserting!("The detail pane is shown")
+            .that(&theme.show_detail)
+            .is_true();
+        asserting!("The detail pane takes its own style")
+            .that(&(theme.detail.font_size, theme.detail.border_width))
+            .is_equal_to((16, 1.0));
+    }
+
+    #[test]
+    fn cell_flow_wrap_is_off_unless_set() {
+        asserting!("Flow wrap is off by default")
+            .that(&Theme::base().cell_flow_wrap)
+            .is_false();
+        let content = ".tabsel {\n  --cell-flow-wrap: true;\n}\n";
+        let theme = parse_layer_str(Theme::base(), content).unwrap();
+        asserting!("Flow wrap is turned on")
+            .that(&theme.cell_flow_wrap)
+            .is_true();
+    }
+
+    #[test]
+    fn drag_handle_is_off_unless_set() {
+        asserting!("Windows aren't draggable by default")
+            .that(&Theme::base().draggable)
+            .is_false();
+        let content = ".tabsel {\n  --draggable: true;\n}\n";
+        let theme = parse_layer_str(Theme::base(), content).unwrap();
+        asserting!("The drag handle is turned on")
+            .that(&theme.draggable)
+            .is_true();
+    }
+
+    #[test]
+    fn format_keys_replace_the_default_bindings() {
+        asserting!("Ctrl+J confirms as JSON by default")
+            .that(&Theme::base().format_for_key("j"))
+            .is_equal_to(Some(OutputFormat::Json));
+        let content = ".tabsel {\n  --format-keys: \"s\" csv, \"K\" kv;\n}\n";
+        let theme = parse_layer_str(Theme::base(), content).unwrap();
+        asserting!("The theme's bindings replace the defaults")
+            .that(&(
+                theme.format_for_key("j"),
+                theme.format_for_key("s"),
+                theme.format_for_key("k"),
+            ))
+            .is_equal_to((None, Some(OutputFormat::Csv), Some(OutputFormat::Kv)));
+
+        let content = ".tabsel {\n  --format-keys: ;\n}\n";
+        let theme = parse_layer_str(Theme::base(), content).unwrap();
+        asserting!("An empty list unbinds every key")
+            .that(&theme.format_keys.is_empty())
+            .is_true();
+
+        let content = ".tabsel {\n  --format-keys: \"D\" csv, \"x\" kv;\n}\n";
+        let theme = parse_layer_str(Theme::base(), content).unwrap();
+        asserting!("Keys with a built-in binding are skipped")
+            .that(&theme.format_keys)
+            .is_equal_to(vec![("x".to_string(), OutputFormat::Kv)]);
+    }
+
+    #[test]
+    fn boxed_grid_outlines_cells_with_half_lines() {
+        asserting!("The grid is off by default")
+            .that(&Theme::base().grid_style)
+            .is_equal_to(GridStyle::Borderless);
+
+        let content = ".tabsel {\n  --grid: boxed;\n  --grid-width: 2px;\n  --grid-color: #ff0000;\n}\n";
+        let theme = parse_layer_str(Theme::base(), content).unwrap();
+        asserting!("The grid is turned on")
+            .that(&theme.grid_style)
+            .is_equal_to(GridStyle::Boxed);
+
+        let base = GenericContainerStyle::default();
+        let cell = theme.boxed_cell(&base, &OnagrePadding::from(3), false);
+        asserting!("Cells draw half of each line, padded by their row")
+            .that(&(cell.border_width, cell.border_color, cell.padding))
+            .is_equal_to((1.0, OnagreColor::from("#ff0000").unwrap(), OnagrePadding::from(3)));
+        let outlined = theme.boxed_cell(&base, &OnagrePadding::ZERO, true);
+        asserting!("The selected cell keeps its outline")
+            .that(&outlined.border_width)
+            .is_equal_to(base.border_width);
+
+        asserting!("Scaling covers the line width")
+            .that(&theme.scale(2.0).grid_width)
+            .is_equal_to(4.0);
+    }
+
+    #[test]
+    fn cell_rules_are_read_in_order() {
+        let content = ".tabsel {
+  .cell-rule {
+    --column: \"status\";
+    --regex: \"^E\\\\d+$\";
+    color: #ff0000;
+  }
+  .cell-rule {
+    --match: \"FAIL\";
+    background: #00ff00;
+  }
+  .cell-rule {
+    --regex: \"(\";
+  }
+  .cell-rule {
+    color: #0000ff;
+  }
+}
+";
+        let theme = parse_layer_str(Theme::base(), content).unwrap();
+        asserting!("Rules without a valid match are dropped")
+            .that(&theme.cell_rules.len())
+            .is_equal_to(2);
+
+        let table = Table::new(Some(vec!["status".to_string()]), vec![]);
+        let base = GenericContainerStyle::default();
+        let style = |value| cell_rule::style_cell(&theme.cell_rules, &base, &table, 0, value);
+        asserting!("The regex rule matches first")
+            .that(&style("E42").map(|style| style.color))
+            .is_equal_to(Some(OnagreColor::from("#ff0000").unwrap()));
+        asserting!("The substring rule colors the background")
+            .that(&style("FAIL").map(|style| (style.color, style.background)))
+            .is_equal_to(Some((base.color, OnagreColor::from("#00ff00").unwrap())));
+        asserting!("Other values keep the row's style")
+            .that(&style("PASS"))
+            .is_equal_to(None);
+    }
+
+    #[test]
+    fn later_layers_override_only_what_they_set() {
+        let base = ".tabsel {\n  font-size: 20px;\n  background: #000000;\n  --rtl: true;\n}\n";
+        let local = ".tabsel {\n  background: #ffffff;\n  --show-footer: true;\n}\n";
+
+        let theme = parse_layer_str(Theme::base(), base).unwrap();
+        let theme = parse_layer_str(theme, local).unwrap();
+
+        asserting!("Base layer values are kept")
+            .that(&(theme.font_size, theme.rtl))
+            .is_equal_to((20, true));
+        asserting!("Override layer values win")
+            .that(&(theme.background, theme.show_footer))
+            .is_equal_to((OnagreColor::WHITE, true));
+    }
+}
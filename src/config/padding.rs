@@ -1,6 +1,6 @@
 use crate::app::style::Scale;
 use iced::Padding;
-use std::ops::Mul;
+use std::ops::{Add, Mul};
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct OnagrePadding {
@@ -23,6 +23,19 @@ impl Mul<f32> for OnagrePadding {
     }
 }
 
+impl Add for OnagrePadding {
+    type Output = OnagrePadding;
+
+    fn add(self, rhs: OnagrePadding) -> Self::Output {
+        OnagrePadding {
+            top: self.top + rhs.top,
+            right: self.right + rhs.right,
+            bottom: self.bottom + rhs.bottom,
+            left: self.left + rhs.left,
+        }
+    }
+}
+
 impl Scale for OnagrePadding {
     fn scale(self, scale: f32) -> Self {
         self * scale
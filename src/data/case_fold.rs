@@ -0,0 +1,305 @@
+//! Full case folding, for the characters whose folding differs from
+//! `char::to_lowercase`. Generated from the status C and F entries of the
+//! Unicode 14.0 `CaseFolding.txt`; every other character folds to its
+//! lowercase.
+
+/// `(character, folding)` pairs, sorted by character.
+pub(crate) const FOLDINGS: &[(char, &str)] = &[
+    ('\u{b5}', "\u{3bc}"), // MICRO SIGN
+    ('\u{df}', "ss"), // LATIN SMALL LETTER SHARP S
+    ('\u{149}', "\u{2bc}n"), // LATIN SMALL LETTER N PRECEDED BY APOSTROPHE
+    ('\u{17f}', "s"), // LATIN SMALL LETTER LONG S
+    ('\u{1f0}', "j\u{30c}"), // LATIN SMALL LETTER J WITH CARON
+    ('\u{345}', "\u{3b9}"), // COMBINING GREEK YPOGEGRAMMENI
+    ('\u{390}', "\u{3b9}\u{308}\u{301}"), // GREEK SMALL LETTER IOTA WITH DIALYTIKA AND TONOS
+    ('\u{3b0}', "\u{3c5}\u{308}\u{301}"), // GREEK SMALL LETTER UPSILON WITH DIALYTIKA AND TONOS
+    ('\u{3c2}', "\u{3c3}"), // GREEK SMALL LETTER FINAL SIGMA
+    ('\u{3d0}', "\u{3b2}"), // GREEK BETA SYMBOL
+    ('\u{3d1}', "\u{3b8}"), // GREEK THETA SYMBOL
+    ('\u{3d5}', "\u{3c6}"), // GREEK PHI SYMBOL
+    ('\u{3d6}', "\u{3c0}"), // GREEK PI SYMBOL
+    ('\u{3f0}', "\u{3ba}"), // GREEK KAPPA SYMBOL
+    ('\u{3f1}', "\u{3c1}"), // GREEK RHO SYMBOL
+    ('\u{3f5}', "\u{3b5}"), // GREEK LUNATE EPSILON SYMBOL
+    ('\u{587}', "\u{565}\u{582}"), // ARMENIAN SMALL LIGATURE ECH YIWN
+    ('\u{13a0}', "\u{13a0}"), // CHEROKEE LETTER A
+    ('\u{13a1}', "\u{13a1}"), // CHEROKEE LETTER E
+    ('\u{13a2}', "\u{13a2}"), // CHEROKEE LETTER I
+    ('\u{13a3}', "\u{13a3}"), // CHEROKEE LETTER O
+    ('\u{13a4}', "\u{13a4}"), // CHEROKEE LETTER U
+    ('\u{13a5}', "\u{13a5}"), // CHEROKEE LETTER V
+    ('\u{13a6}', "\u{13a6}"), // CHEROKEE LETTER GA
+    ('\u{13a7}', "\u{13a7}"), // CHEROKEE LETTER KA
+    ('\u{13a8}', "\u{13a8}"), // CHEROKEE LETTER GE
+    ('\u{13a9}', "\u{13a9}"), // CHEROKEE LETTER GI
+    ('\u{13aa}', "\u{13aa}"), // CHEROKEE LETTER GO
+    ('\u{13ab}', "\u{13ab}"), // CHEROKEE LETTER GU
+    ('\u{13ac}', "\u{13ac}"), // CHEROKEE LETTER GV
+    ('\u{13ad}', "\u{13ad}"), // CHEROKEE LETTER HA
+    ('\u{13ae}', "\u{13ae}"), // CHEROKEE LETTER HE
+    ('\u{13af}', "\u{13af}"), // CHEROKEE LETTER HI
+    ('\u{13b0}', "\u{13b0}"), // CHEROKEE LETTER HO
+    ('\u{13b1}', "\u{13b1}"), // CHEROKEE LETTER HU
+    ('\u{13b2}', "\u{13b2}"), // CHEROKEE LETTER HV
+    ('\u{13b3}', "\u{13b3}"), // CHEROKEE LETTER LA
+    ('\u{13b4}', "\u{13b4}"), // CHEROKEE LETTER LE
+    ('\u{13b5}', "\u{13b5}"), // CHEROKEE LETTER LI
+    ('\u{13b6}', "\u{13b6}"), // CHEROKEE LETTER LO
+    ('\u{13b7}', "\u{13b7}"), // CHEROKEE LETTER LU
+    ('\u{13b8}', "\u{13b8}"), // CHEROKEE LETTER LV
+    ('\u{13b9}', "\u{13b9}"), // CHEROKEE LETTER MA
+    ('\u{13ba}', "\u{13ba}"), // CHEROKEE LETTER ME
+    ('\u{13bb}', "\u{13bb}"), // CHEROKEE LETTER MI
+    ('\u{13bc}', "\u{13bc}"), // CHEROKEE LETTER MO
+    ('\u{13bd}', "\u{13bd}"), // CHEROKEE LETTER MU
+    ('\u{13be}', "\u{13be}"), // CHEROKEE LETTER NA
+    ('\u{13bf}', "\u{13bf}"), // CHEROKEE LETTER HNA
+    ('\u{13c0}', "\u{13c0}"), // CHEROKEE LETTER NAH
+    ('\u{13c1}', "\u{13c1}"), // CHEROKEE LETTER NE
+    ('\u{13c2}', "\u{13c2}"), // CHEROKEE LETTER NI
+    ('\u{13c3}', "\u{13c3}"), // CHEROKEE LETTER NO
+    ('\u{13c4}', "\u{13c4}"), // CHEROKEE LETTER NU
+    ('\u{13c5}', "\u{13c5}"), // CHEROKEE LETTER NV
+    ('\u{13c6}', "\u{13c6}"), // CHEROKEE LETTER QUA
+    ('\u{13c7}', "\u{13c7}"), // CHEROKEE LETTER QUE
+    ('\u{13c8}', "\u{13c8}"), // CHEROKEE LETTER QUI
+    ('\u{13c9}', "\u{13c9}"), // CHEROKEE LETTER QUO
+    ('\u{13ca}', "\u{13ca}"), // CHEROKEE LETTER QUU
+    ('\u{13cb}', "\u{13cb}"), // CHEROKEE LETTER QUV
+    ('\u{13cc}', "\u{13cc}"), // CHEROKEE LETTER SA
+    ('\u{13cd}', "\u{13cd}"), // CHEROKEE LETTER S
+    ('\u{13ce}', "\u{13ce}"), // CHEROKEE LETTER SE
+    ('\u{13cf}', "\u{13cf}"), // CHEROKEE LETTER SI
+    ('\u{13d0}', "\u{13d0}"), // CHEROKEE LETTER SO
+    ('\u{13d1}', "\u{13d1}"), // CHEROKEE LETTER SU
+    ('\u{13d2}', "\u{13d2}"), // CHEROKEE LETTER SV
+    ('\u{13d3}', "\u{13d3}"), // CHEROKEE LETTER DA
+    ('\u{13d4}', "\u{13d4}"), // CHEROKEE LETTER TA
+    ('\u{13d5}', "\u{13d5}"), // CHEROKEE LETTER DE
+    ('\u{13d6}', "\u{13d6}"), // CHEROKEE LETTER TE
+    ('\u{13d7}', "\u{13d7}"), // CHEROKEE LETTER DI
+    ('\u{13d8}', "\u{13d8}"), // CHEROKEE LETTER TI
+    ('\u{13d9}', "\u{13d9}"), // CHEROKEE LETTER DO
+    ('\u{13da}', "\u{13da}"), // CHEROKEE LETTER DU
+    ('\u{13db}', "\u{13db}"), // CHEROKEE LETTER DV
+    ('\u{13dc}', "\u{13dc}"), // CHEROKEE LETTER DLA
+    ('\u{13dd}', "\u{13dd}"), // CHEROKEE LETTER TLA
+    ('\u{13de}', "\u{13de}"), // CHEROKEE LETTER TLE
+    ('\u{13df}', "\u{13df}"), // CHEROKEE LETTER TLI
+    ('\u{13e0}', "\u{13e0}"), // CHEROKEE LETTER TLO
+    ('\u{13e1}', "\u{13e1}"), // CHEROKEE LETTER TLU
+    ('\u{13e2}', "\u{13e2}"), // CHEROKEE LETTER TLV
+    ('\u{13e3}', "\u{13e3}"), // CHEROKEE LETTER TSA
+    ('\u{13e4}', "\u{13e4}"), // CHEROKEE LETTER TSE
+    ('\u{13e5}', "\u{13e5}"), // CHEROKEE LETTER TSI
+    ('\u{13e6}', "\u{13e6}"), // CHEROKEE LETTER TSO
+    ('\u{13e7}', "\u{13e7}"), // CHEROKEE LETTER TSU
+    ('\u{13e8}', "\u{13e8}"), // CHEROKEE LETTER TSV
+    ('\u{13e9}', "\u{13e9}"), // CHEROKEE LETTER WA
+    ('\u{13ea}', "\u{13ea}"), // CHEROKEE LETTER WE
+    ('\u{13eb}', "\u{13eb}"), // CHEROKEE LETTER WI
+    ('\u{13ec}', "\u{13ec}"), // CHEROKEE LETTER WO
+    ('\u{13ed}', "\u{13ed}"), // CHEROKEE LETTER WU
+    ('\u{13ee}', "\u{13ee}"), // CHEROKEE LETTER WV
+    ('\u{13ef}', "\u{13ef}"), // CHEROKEE LETTER YA
+    ('\u{13f0}', "\u{13f0}"), // CHEROKEE LETTER YE
+    ('\u{13f1}', "\u{13f1}"), // CHEROKEE LETTER YI
+    ('\u{13f2}', "\u{13f2}"), // CHEROKEE LETTER YO
+    ('\u{13f3}', "\u{13f3}"), // CHEROKEE LETTER YU
+    ('\u{13f4}', "\u{13f4}"), // CHEROKEE LETTER YV
+    ('\u{13f5}', "\u{13f5}"), // CHEROKEE LETTER MV
+    ('\u{13f8}', "\u{13f0}"), // CHEROKEE SMALL LETTER YE
+    ('\u{13f9}', "\u{13f1}"), // CHEROKEE SMALL LETTER YI
+    ('\u{13fa}', "\u{13f2}"), // CHEROKEE SMALL LETTER YO
+    ('\u{13fb}', "\u{13f3}"), // CHEROKEE SMALL LETTER YU
+    ('\u{13fc}', "\u{13f4}"), // CHEROKEE SMALL LETTER YV
+    ('\u{13fd}', "\u{13f5}"), // CHEROKEE SMALL LETTER MV
+    ('\u{1c80}', "\u{432}"), // CYRILLIC SMALL LETTER ROUNDED VE
+    ('\u{1c81}', "\u{434}"), // CYRILLIC SMALL LETTER LONG-LEGGED DE
+    ('\u{1c82}', "\u{43e}"), // CYRILLIC SMALL LETTER NARROW O
+    ('\u{1c83}', "\u{441}"), // CYRILLIC SMALL LETTER WIDE ES
+    ('\u{1c84}', "\u{442}"), // CYRILLIC SMALL LETTER TALL TE
+    ('\u{1c85}', "\u{442}"), // CYRILLIC SMALL LETTER THREE-LEGGED TE
+    ('\u{1c86}', "\u{44a}"), // CYRILLIC SMALL LETTER TALL HARD SIGN
+    ('\u{1c87}', "\u{463}"), // CYRILLIC SMALL LETTER TALL YAT
+    ('\u{1c88}', "\u{a64b}"), // CYRILLIC SMALL LETTER UNBLENDED UK
+    ('\u{1e96}', "h\u{331}"), // LATIN SMALL LETTER H WITH LINE BELOW
+    ('\u{1e97}', "t\u{308}"), // LATIN SMALL LETTER T WITH DIAERESIS
+    ('\u{1e98}', "w\u{30a}"), // LATIN SMALL LETTER W WITH RING ABOVE
+    ('\u{1e99}', "y\u{30a}"), // LATIN SMALL LETTER Y WITH RING ABOVE
+    ('\u{1e9a}', "a\u{2be}"), // LATIN SMALL LETTER A WITH RIGHT HALF RING
+    ('\u{1e9b}', "\u{1e61}"), // LATIN SMALL LETTER LONG S WITH DOT ABOVE
+    ('\u{1e9e}', "ss"), // LATIN CAPITAL LETTER SHARP S
+    ('\u{1f50}', "\u{3c5}\u{313}"), // GREEK SMALL LETTER UPSILON WITH PSILI
+    ('\u{1f52}', "\u{3c5}\u{313}\u{300}"), // GREEK SMALL LETTER UPSILON WITH PSILI AND VARIA
+    ('\u{1f54}', "\u{3c5}\u{313}\u{301}"), // GREEK SMALL LETTER UPSILON WITH PSILI AND OXIA
+    ('\u{1f56}', "\u{3c5}\u{313}\u{342}"), // GREEK SMALL LETTER UPSILON WITH PSILI AND PERISPOMENI
+    ('\u{1f80}', "\u{1f00}\u{3b9}"), // GREEK SMALL LETTER ALPHA WITH PSILI AND YPOGEGRAMMENI
+    ('\u{1f81}', "\u{1f01}\u{3b9}"), // GREEK SMALL LETTER ALPHA WITH DASIA AND YPOGEGRAMMENI
+    ('\u{1f82}', "\u{1f02}\u{3b9}"), // GREEK SMALL LETTER ALPHA WITH PSILI AND VARIA AND YPOGEGRAMMENI
+    ('\u{1f83}', "\u{1f03}\u{3b9}"), // GREEK SMALL LETTER ALPHA WITH DASIA AND VARIA AND YPOGEGRAMMENI
+    ('\u{1f84}', "\u{1f04}\u{3b9}"), // GREEK SMALL LETTER ALPHA WITH PSILI AND OXIA AND YPOGEGRAMMENI
+    ('\u{1f85}', "\u{1f05}\u{3b9}"), // GREEK SMALL LETTER ALPHA WITH DASIA AND OXIA AND YPOGEGRAMMENI
+    ('\u{1f86}', "\u{1f06}\u{3b9}"), // GREEK SMALL LETTER ALPHA WITH PSILI AND PERISPOMENI AND YPOGEGRAMMENI
+    ('\u{1f87}', "\u{1f07}\u{3b9}"), // GREEK SMALL LETTER ALPHA WITH DASIA AND PERISPOMENI AND YPOGEGRAMMENI
+    ('\u{1f88}', "\u{1f00}\u{3b9}"), // GREEK CAPITAL LETTER ALPHA WITH PSILI AND PROSGEGRAMMENI
+    ('\u{1f89}', "\u{1f01}\u{3b9}"), // GREEK CAPITAL LETTER ALPHA WITH DASIA AND PROSGEGRAMMENI
+    ('\u{1f8a}', "\u{1f02}\u{3b9}"), // GREEK CAPITAL LETTER ALPHA WITH PSILI AND VARIA AND PROSGEGRAMMENI
+    ('\u{1f8b}', "\u{1f03}\u{3b9}"), // GREEK CAPITAL LETTER ALPHA WITH DASIA AND VARIA AND PROSGEGRAMMENI
+    ('\u{1f8c}', "\u{1f04}\u{3b9}"), // GREEK CAPITAL LETTER ALPHA WITH PSILI AND OXIA AND PROSGEGRAMMENI
+    ('\u{1f8d}', "\u{1f05}\u{3b9}"), // GREEK CAPITAL LETTER ALPHA WITH DASIA AND OXIA AND PROSGEGRAMMENI
+    ('\u{1f8e}', "\u{1f06}\u{3b9}"), // GREEK CAPITAL LETTER ALPHA WITH PSILI AND PERISPOMENI AND PROSGEGRAMMENI
+    ('\u{1f8f}', "\u{1f07}\u{3b9}"), // GREEK CAPITAL LETTER ALPHA WITH DASIA AND PERISPOMENI AND PROSGEGRAMMENI
+    ('\u{1f90}', "\u{1f20}\u{3b9}"), // GREEK SMALL LETTER ETA WITH PSILI AND YPOGEGRAMMENI
+    ('\u{1f91}', "\u{1f21}\u{3b9}"), // GREEK SMALL LETTER ETA WITH DASIA AND YPOGEGRAMMENI
+    ('\u{1f92}', "\u{1f22}\u{3b9}"), // GREEK SMALL LETTER ETA WITH PSILI AND VARIA AND YPOGEGRAMMENI
+    ('\u{1f93}', "\u{1f23}\u{3b9}"), // GREEK SMALL LETTER ETA WITH DASIA AND VARIA AND YPOGEGRAMMENI
+    ('\u{1f94}', "\u{1f24}\u{3b9}"), // GREEK SMALL LETTER ETA WITH PSILI AND OXIA AND YPOGEGRAMMENI
+    ('\u{1f95}', "\u{1f25}\u{3b9}"), // GREEK SMALL LETTER ETA WITH DASIA AND OXIA AND YPOGEGRAMMENI
+    ('\u{1f96}', "\u{1f26}\u{3b9}"), // GREEK SMALL LETTER ETA WITH PSILI AND PERISPOMENI AND YPOGEGRAMMENI
+    ('\u{1f97}', "\u{1f27}\u{3b9}"), // GREEK SMALL LETTER ETA WITH DASIA AND PERISPOMENI AND YPOGEGRAMMENI
+    ('\u{1f98}', "\u{1f20}\u{3b9}"), // GREEK CAPITAL LETTER ETA WITH PSILI AND PROSGEGRAMMENI
+    ('\u{1f99}', "\u{1f21}\u{3b9}"), // GREEK CAPITAL LETTER ETA WITH DASIA AND PROSGEGRAMMENI
+    ('\u{1f9a}', "\u{1f22}\u{3b9}"), // GREEK CAPITAL LETTER ETA WITH PSILI AND VARIA AND PROSGEGRAMMENI
+    ('\u{1f9b}', "\u{1f23}\u{3b9}"), // GREEK CAPITAL LETTER ETA WITH DASIA AND VARIA AND PROSGEGRAMMENI
+    ('\u{1f9c}', "\u{1f24}\u{3b9}"), // GREEK CAPITAL LETTER ETA WITH PSILI AND OXIA AND PROSGEGRAMMENI
+    ('\u{1f9d}', "\u{1f25}\u{3b9}"), // GREEK CAPITAL LETTER ETA WITH DASIA AND OXIA AND PROSGEGRAMMENI
+    ('\u{1f9e}', "\u{1f26}\u{3b9}"), // GREEK CAPITAL LETTER ETA WITH PSILI AND PERISPOMENI AND PROSGEGRAMMENI
+    ('\u{1f9f}', "\u{1f27}\u{3b9}"), // GREEK CAPITAL LETTER ETA WITH DASIA AND PERISPOMENI AND PROSGEGRAMMENI
+    ('\u{1fa0}', "\u{1f60}\u{3b9}"), // GREEK SMALL LETTER OMEGA WITH PSILI AND YPOGEGRAMMENI
+    ('\u{1fa1}', "\u{1f61}\u{3b9}"), // GREEK SMALL LETTER OMEGA WITH DASIA AND YPOGEGRAMMENI
+    ('\u{1fa2}', "\u{1f62}\u{3b9}"), // GREEK SMALL LETTER OMEGA WITH PSILI AND VARIA AND YPOGEGRAMMENI
+    ('\u{1fa3}', "\u{1f63}\u{3b9}"), // GREEK SMALL LETTER OMEGA WITH DASIA AND VARIA AND YPOGEGRAMMENI
+    ('\u{1fa4}', "\u{1f64}\u{3b9}"), // GREEK SMALL LETTER OMEGA WITH PSILI AND OXIA AND YPOGEGRAMMENI
+    ('\u{1fa5}', "\u{1f65}\u{3b9}"), // GREEK SMALL LETTER OMEGA WITH DASIA AND OXIA AND YPOGEGRAMMENI
+    ('\u{1fa6}', "\u{1f66}\u{3b9}"), // GREEK SMALL LETTER OMEGA WITH PSILI AND PERISPOMENI AND YPOGEGRAMMENI
+    ('\u{1fa7}', "\u{1f67}\u{3b9}"), // GREEK SMALL LETTER OMEGA WITH DASIA AND PERISPOMENI AND YPOGEGRAMMENI
+    ('\u{1fa8}', "\u{1f60}\u{3b9}"), // GREEK CAPITAL LETTER OMEGA WITH PSILI AND PROSGEGRAMMENI
+    ('\u{1fa9}', "\u{1f61}\u{3b9}"), // GREEK CAPITAL LETTER OMEGA WITH DASIA AND PROSGEGRAMMENI
+    ('\u{1faa}', "\u{1f62}\u{3b9}"), // GREEK CAPITAL LETTER OMEGA WITH PSILI AND VARIA AND PROSGEGRAMMENI
+    ('\u{1fab}', "\u{1f63}\u{3b9}"), // GREEK CAPITAL LETTER OMEGA WITH DASIA AND VARIA AND PROSGEGRAMMENI
+    ('\u{1fac}', "\u{1f64}\u{3b9}"), // GREEK CAPITAL LETTER OMEGA WITH PSILI AND OXIA AND PROSGEGRAMMENI
+    ('\u{1fad}', "\u{1f65}\u{3b9}"), // GREEK CAPITAL LETTER OMEGA WITH DASIA AND OXIA AND PROSGEGRAMMENI
+    ('\u{1fae}', "\u{1f66}\u{3b9}"), // GREEK CAPITAL LETTER OMEGA WITH PSILI AND PERISPOMENI AND PROSGEGRAMMENI
+    ('\u{1faf}', "\u{1f67}\u{3b9}"), // GREEK CAPITAL LETTER OMEGA WITH DASIA AND PERISPOMENI AND PROSGEGRAMMENI
+    ('\u{1fb2}', "\u{1f70}\u{3b9}"), // GREEK SMALL LETTER ALPHA WITH VARIA AND YPOGEGRAMMENI
+    ('\u{1fb3}', "\u{3b1}\u{3b9}"), // GREEK SMALL LETTER ALPHA WITH YPOGEGRAMMENI
+    ('\u{1fb4}', "\u{3ac}\u{3b9}"), // GREEK SMALL LETTER ALPHA WITH OXIA AND YPOGEGRAMMENI
+    ('\u{1fb6}', "\u{3b1}\u{342}"), // GREEK SMALL LETTER ALPHA WITH PERISPOMENI
+    ('\u{1fb7}', "\u{3b1}\u{342}\u{3b9}"), // GREEK SMALL LETTER ALPHA WITH PERISPOMENI AND YPOGEGRAMMENI
+    ('\u{1fbc}', "\u{3b1}\u{3b9}"), // GREEK CAPITAL LETTER ALPHA WITH PROSGEGRAMMENI
+    ('\u{1fbe}', "\u{3b9}"), // GREEK PROSGEGRAMMENI
+    ('\u{1fc2}', "\u{1f74}\u{3b9}"), // GREEK SMALL LETTER ETA WITH VARIA AND YPOGEGRAMMENI
+    ('\u{1fc3}', "\u{3b7}\u{3b9}"), // GREEK SMALL LETTER ETA WITH YPOGEGRAMMENI
+    ('\u{1fc4}', "\u{3ae}\u{3b9}"), // GREEK SMALL LETTER ETA WITH OXIA AND YPOGEGRAMMENI
+    ('\u{1fc6}', "\u{3b7}\u{342}"), // GREEK SMALL LETTER ETA WITH PERISPOMENI
+    ('\u{1fc7}', "\u{3b7}\u{342}\u{3b9}"), // GREEK SMALL LETTER ETA WITH PERISPOMENI AND YPOGEGRAMMENI
+    ('\u{1fcc}', "\u{3b7}\u{3b9}"), // GREEK CAPITAL LETTER ETA WITH PROSGEGRAMMENI
+    ('\u{1fd2}', "\u{3b9}\u{308}\u{300}"), // GREEK SMALL LETTER IOTA WITH DIALYTIKA AND VARIA
+    ('\u{1fd3}', "\u{3b9}\u{308}\u{301}"), // GREEK SMALL LETTER IOTA WITH DIALYTIKA AND OXIA
+    ('\u{1fd6}', "\u{3b9}\u{342}"), // GREEK SMALL LETTER IOTA WITH PERISPOMENI
+    ('\u{1fd7}', "\u{3b9}\u{308}\u{342}"), // GREEK SMALL LETTER IOTA WITH DIALYTIKA AND PERISPOMENI
+    ('\u{1fe2}', "\u{3c5}\u{308}\u{300}"), // GREEK SMALL LETTER UPSILON WITH DIALYTIKA AND VARIA
+    ('\u{1fe3}', "\u{3c5}\u{308}\u{301}"), // GREEK SMALL LETTER UPSILON WITH DIALYTIKA AND OXIA
+    ('\u{1fe4}', "\u{3c1}\u{313}"), // GREEK SMALL LETTER RHO WITH PSILI
+    ('\u{1fe6}', "\u{3c5}\u{342}"), // GREEK SMALL LETTER UPSILON WITH PERISPOMENI
+    ('\u{1fe7}', "\u{3c5}\u{308}\u{342}"), // GREEK SMALL LETTER UPSILON WITH DIALYTIKA AND PERISPOMENI
+    ('\u{1ff2}', "\u{1f7c}\u{3b9}"), // GREEK SMALL LETTER OMEGA WITH VARIA AND YPOGEGRAMMENI
+    ('\u{1ff3}', "\u{3c9}\u{3b9}"), // GREEK SMALL LETTER OMEGA WITH YPOGEGRAMMENI
+    ('\u{1ff4}', "\u{3ce}\u{3b9}"), // GREEK SMALL LETTER OMEGA WITH OXIA AND YPOGEGRAMMENI
+    ('\u{1ff6}', "\u{3c9}\u{342}"), // GREEK SMALL LETTER OMEGA WITH PERISPOMENI
+    ('\u{1ff7}', "\u{3c9}\u{342}\u{3b9}"), // GREEK SMALL LETTER OMEGA WITH PERISPOMENI AND YPOGEGRAMMENI
+    ('\u{1ffc}', "\u{3c9}\u{3b9}"), // GREEK CAPITAL LETTER OMEGA WITH PROSGEGRAMMENI
+    ('\u{ab70}', "\u{13a0}"), // CHEROKEE SMALL LETTER A
+    ('\u{ab71}', "\u{13a1}"), // CHEROKEE SMALL LETTER E
+    ('\u{ab72}', "\u{13a2}"), // CHEROKEE SMALL LETTER I
+    ('\u{ab73}', "\u{13a3}"), // CHEROKEE SMALL LETTER O
+    ('\u{ab74}', "\u{13a4}"), // CHEROKEE SMALL LETTER U
+    ('\u{ab75}', "\u{13a5}"), // CHEROKEE SMALL LETTER V
+    ('\u{ab76}', "\u{13a6}"), // CHEROKEE SMALL LETTER GA
+    ('\u{ab77}', "\u{13a7}"), // CHEROKEE SMALL LETTER KA
+    ('\u{ab78}', "\u{13a8}"), // CHEROKEE SMALL LETTER GE
+    ('\u{ab79}', "\u{13a9}"), // CHEROKEE SMALL LETTER GI
+    ('\u{ab7a}', "\u{13aa}"), // CHEROKEE SMALL LETTER GO
+    ('\u{ab7b}', "\u{13ab}"), // CHEROKEE SMALL LETTER GU
+    ('\u{ab7c}', "\u{13ac}"), // CHEROKEE SMALL LETTER GV
+    ('\u{ab7d}', "\u{13ad}"), // CHEROKEE SMALL LETTER HA
+    ('\u{ab7e}', "\u{13ae}"), // CHEROKEE SMALL LETTER HE
+    ('\u{ab7f}', "\u{13af}"), // CHEROKEE SMALL LETTER HI
+    ('\u{ab80}', "\u{13b0}"), // CHEROKEE SMALL LETTER HO
+    ('\u{ab81}', "\u{13b1}"), // CHEROKEE SMALL LETTER HU
+    ('\u{ab82}', "\u{13b2}"), // CHEROKEE SMALL LETTER HV
+    ('\u{ab83}', "\u{13b3}"), // CHEROKEE SMALL LETTER LA
+    ('\u{ab84}', "\u{13b4}"), // CHEROKEE SMALL LETTER LE
+    ('\u{ab85}', "\u{13b5}"), // CHEROKEE SMALL LETTER LI
+    ('\u{ab86}', "\u{13b6}"), // CHEROKEE SMALL LETTER LO
+    ('\u{ab87}', "\u{13b7}"), // CHEROKEE SMALL LETTER LU
+    ('\u{ab88}', "\u{13b8}"), // CHEROKEE SMALL LETTER LV
+    ('\u{ab89}', "\u{13b9}"), // CHEROKEE SMALL LETTER MA
+    ('\u{ab8a}', "\u{13ba}"), // CHEROKEE SMALL LETTER ME
+    ('\u{ab8b}', "\u{13bb}"), // CHEROKEE SMALL LETTER MI
+    ('\u{ab8c}', "\u{13bc}"), // CHEROKEE SMALL LETTER MO
+    ('\u{ab8d}', "\u{13bd}"), // CHEROKEE SMALL LETTER MU
+    ('\u{ab8e}', "\u{13be}"), // CHEROKEE SMALL LETTER NA
+    ('\u{ab8f}', "\u{13bf}"), // CHEROKEE SMALL LETTER HNA
+    ('\u{ab90}', "\u{13c0}"), // CHEROKEE SMALL LETTER NAH
+    ('\u{ab91}', "\u{13c1}"), // CHEROKEE SMALL LETTER NE
+    ('\u{ab92}', "\u{13c2}"), // CHEROKEE SMALL LETTER NI
+    ('\u{ab93}', "\u{13c3}"), // CHEROKEE SMALL LETTER NO
+    ('\u{ab94}', "\u{13c4}"), // CHEROKEE SMALL LETTER NU
+    ('\u{ab95}', "\u{13c5}"), // CHEROKEE SMALL LETTER NV
+    ('\u{ab96}', "\u{13c6}"), // CHEROKEE SMALL LETTER QUA
+    ('\u{ab97}', "\u{13c7}"), // CHEROKEE SMALL LETTER QUE
+    ('\u{ab98}', "\u{13c8}"), // CHEROKEE SMALL LETTER QUI
+    ('\u{ab99}', "\u{13c9}"), // CHEROKEE SMALL LETTER QUO
+    ('\u{ab9a}', "\u{13ca}"), // CHEROKEE SMALL LETTER QUU
+    ('\u{ab9b}', "\u{13cb}"), // CHEROKEE SMALL LETTER QUV
+    ('\u{ab9c}', "\u{13cc}"), // CHEROKEE SMALL LETTER SA
+    ('\u{ab9d}', "\u{13cd}"), // CHEROKEE SMALL LETTER S
+    ('\u{ab9e}', "\u{13ce}"), // CHEROKEE SMALL LETTER SE
+    ('\u{ab9f}', "\u{13cf}"), // CHEROKEE SMALL LETTER SI
+    ('\u{aba0}', "\u{13d0}"), // CHEROKEE SMALL LETTER SO
+    ('\u{aba1}', "\u{13d1}"), // CHEROKEE SMALL LETTER SU
+    ('\u{aba2}', "\u{13d2}"), // CHEROKEE SMALL LETTER SV
+    ('\u{aba3}', "\u{13d3}"), // CHEROKEE SMALL LETTER DA
+    ('\u{aba4}', "\u{13d4}"), // CHEROKEE SMALL LETTER TA
+    ('\u{aba5}', "\u{13d5}"), // CHEROKEE SMALL LETTER DE
+    ('\u{aba6}', "\u{13d6}"), // CHEROKEE SMALL LETTER TE
+    ('\u{aba7}', "\u{13d7}"), // CHEROKEE SMALL LETTER DI
+    ('\u{aba8}', "\u{13d8}"), // CHEROKEE SMALL LETTER TI
+    ('\u{aba9}', "\u{13d9}"), // CHEROKEE SMALL LETTER DO
+    ('\u{abaa}', "\u{13da}"), // CHEROKEE SMALL LETTER DU
+    ('\u{abab}', "\u{13db}"), // CHEROKEE SMALL LETTER DV
+    ('\u{abac}', "\u{13dc}"), // CHEROKEE SMALL LETTER DLA
+    ('\u{abad}', "\u{13dd}"), // CHEROKEE SMALL LETTER TLA
+    ('\u{abae}', "\u{13de}"), // CHEROKEE SMALL LETTER TLE
+    ('\u{abaf}', "\u{13df}"), // CHEROKEE SMALL LETTER TLI
+    ('\u{abb0}', "\u{13e0}"), // CHEROKEE SMALL LETTER TLO
+    ('\u{abb1}', "\u{13e1}"), // CHEROKEE SMALL LETTER TLU
+    ('\u{abb2}', "\u{13e2}"), // CHEROKEE SMALL LETTER TLV
+    ('\u{abb3}', "\u{13e3}"), // CHEROKEE SMALL LETTER TSA
+    ('\u{abb4}', "\u{13e4}"), // CHEROKEE SMALL LETTER TSE
+    ('\u{abb5}', "\u{13e5}"), // CHEROKEE SMALL LETTER TSI
+    ('\u{abb6}', "\u{13e6}"), // CHEROKEE SMALL LETTER TSO
+    ('\u{abb7}', "\u{13e7}"), // CHEROKEE SMALL LETTER TSU
+    ('\u{abb8}', "\u{13e8}"), // CHEROKEE SMALL LETTER TSV
+    ('\u{abb9}', "\u{13e9}"), // CHEROKEE SMALL LETTER WA
+    ('\u{abba}', "\u{13ea}"), // CHEROKEE SMALL LETTER WE
+    ('\u{abbb}', "\u{13eb}"), // CHEROKEE SMALL LETTER WI
+    ('\u{abbc}', "\u{13ec}"), // CHEROKEE SMALL LETTER WO
+    ('\u{abbd}', "\u{13ed}"), // CHEROKEE SMALL LETTER WU
+    ('\u{abbe}', "\u{13ee}"), // CHEROKEE SMALL LETTER WV
+    ('\u{abbf}', "\u{13ef}"), // CHEROKEE SMALL LETTER YA
+    ('\u{fb00}', "ff"), // LATIN SMALL LIGATURE FF
+    ('\u{fb01}', "fi"), // LATIN SMALL LIGATURE FI
+    ('\u{fb02}', "fl"), // LATIN SMALL LIGATURE FL
+    ('\u{fb03}', "ffi"), // LATIN SMALL LIGATURE FFI
+    ('\u{fb04}', "ffl"), // LATIN SMALL LIGATURE FFL
+    ('\u{fb05}', "st"), // LATIN SMALL LIGATURE LONG S T
+    ('\u{fb06}', "st"), // LATIN SMALL LIGATURE ST
+    ('\u{fb13}', "\u{574}\u{576}"), // ARMENIAN SMALL LIGATURE MEN NOW
+    ('\u{fb14}', "\u{574}\u{565}"), // ARMENIAN SMALL LIGATURE MEN ECH
+    ('\u{fb15}', "\u{574}\u{56b}"), // ARMENIAN SMALL LIGATURE MEN INI
+    ('\u{fb16}', "\u{57e}\u{576}"), // ARMENIAN SMALL LIGATURE VEW NOW
+    ('\u{fb17}', "\u{574}\u{56d}"), // ARMENIAN SMALL LIGATURE MEN XEH
+];
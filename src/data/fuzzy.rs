@@ -0,0 +1,100 @@
+use super::CaseFold;
+
+/// Score `candidate` against a lowercased `query` by matching the query's
+/// characters in order. Returns `None` unless every query character is found.
+///
+/// Each matched character scores one point, with bonuses for continuing a run
+/// of consecutive matches and for landing on the start of a word, so "nc"
+/// ranks "New City" above "bounce".
+pub fn score(candidate: &str, query: &str) -> Option<u32> {
+    let mut query_chars = query.chars().peekable();
+    let mut score = 0;
+    let mut prev: Option<char> = None;
+    let mut prev_matched = false;
+
+    for c in candidate.chars().flat_map(char::to_lowercase) {
+        let Some(&wanted) = query_chars.peek() else {
+            break;
+        };
+
+        if c == wanted {
+            query_chars.next();
+            score += 1;
+            if prev_matched {
+                score += 4;
+            }
+            if prev.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 3;
+            }
+            prev_matched = true;
+        } else {
+            prev_matched = false;
+        }
+        prev = Some(c);
+    }
+
+    query_chars.peek().is_none().then_some(score)
+}
+
+/// The char positions of `candidate` that [`score`] matches against a query
+/// already folded with `case_fold`, for highlighting. `None` unless every
+/// query character is found.
+pub fn match_positions(candidate: &str, query: &str, case_fold: CaseFold) -> Option<Vec<usize>> {
+    let query: Vec<char> = query.chars().collect();
+    let mut next = 0;
+    let mut positions = Vec::new();
+
+    for (pos, c) in candidate.chars().enumerate() {
+        if next == query.len() {
+            break;
+        }
+        // A character can fold to several ("ß" to "ss") and match a run
+        let mut matched = false;
+        for folded in case_fold.fold(c.encode_utf8(&mut [0; 4])).chars() {
+            if query.get(next) == Some(&folded) {
+                next += 1;
+                matched = true;
+            }
+        }
+        if matched {
+            positions.push(pos);
+        }
+    }
+
+    (next == query.len()).then_some(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requires_all_query_chars_in_order() {
+        assert!(score("Alice", "ace").is_some());
+        assert!(score("Alice", "eca").is_none());
+        assert!(score("Bob", "bobby").is_none());
+        assert_eq!(score("anything", ""), Some(0));
+    }
+
+    #[test]
+    fn prefers_word_starts_and_runs() {
+        let word_starts = score("New City", "nc").unwrap();
+        let scattered = score("bounce", "nc").unwrap();
+        assert!(word_starts > scattered);
+
+        let run = score("carol", "car").unwrap();
+        let gaps = score("cxaxr", "car").unwrap();
+        assert!(run > gaps);
+    }
+
+    #[test]
+    fn match_positions_follow_score() {
+        assert_eq!(match_positions("New City", "nc", CaseFold::Simple), Some(vec![0, 4]));
+        assert_eq!(match_positions("Alice", "eca", CaseFold::Simple), None);
+        assert_eq!(match_positions("Alice", "", CaseFold::Simple), Some(vec![]));
+        assert_eq!(
+            match_positions("Straße", "strasse", CaseFold::Full),
+            Some(vec![0, 1, 2, 3, 4, 5])
+        );
+    }
+}
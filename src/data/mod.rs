@@ -1,5 +1,18 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use base64::Engine;
+use encoding_rs::Encoding;
+
+mod case_fold;
+pub mod fuzzy;
 pub mod output;
 pub mod parse;
+pub mod select;
+pub mod template;
 
 #[derive(Debug, Clone)]
 pub struct Table {
@@ -7,22 +20,914 @@ pub struct Table {
     pub rows: Vec<Vec<String>>,
 }
 
+impl Table {
+    pub fn new(headers: Option<Vec<String>>, rows: Vec<Vec<String>>) -> Self {
+        Table { headers, rows }
+    }
+
+    /// Build a table from any nested iterable of string-like cells.
+    pub fn from_records<I, R, S>(headers: Option<Vec<String>>, records: I) -> Self
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let rows = records
+            .into_iter()
+            .map(|record| record.into_iter().map(Into::into).collect())
+            .collect();
+        Table::new(headers, rows)
+    }
+
+    /// The number of columns: the longest of the header and data rows, so
+    /// ragged rows are never cut off.
+    pub fn width(&self) -> usize {
+        self.rows
+            .iter()
+            .map(Vec::len)
+            .chain(self.headers.as_ref().map(Vec::len))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The data row at `idx`, if there is one.
+    pub fn row(&self, idx: usize) -> Option<&Vec<String>> {
+        self.rows.get(idx)
+    }
+
+    /// The cell at (`row`, `col`), or `None` if either is out of range
+    /// (including past the end of a short ragged row).
+    pub fn cell(&self, row: usize, col: usize) -> Option<&str> {
+        self.row(row)?.get(col).map(String::as_str)
+    }
+
+    /// Whether the table has no data rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Remove one layer of matching quotes around every header and cell
+    /// (`--strip-quotes`), see [`strip_quotes`].
+    pub fn strip_quotes(&mut self) {
+        let cells = self.headers.iter_mut().flatten();
+        for cell in cells.chain(self.rows.iter_mut().flatten()) {
+            if strip_quotes(cell).len() < cell.len() {
+                cell.pop();
+                cell.remove(0);
+            }
+        }
+    }
+
+    /// Structural problems worth reporting before display. Rows are
+    /// compared against the header width, or the first row's without one.
+    pub fn validate(&self) -> Vec<TableWarning> {
+        let mut warnings = Vec::new();
+
+        if let Some(headers) = &self.headers {
+            for (col, name) in headers.iter().enumerate() {
+                if name.trim().is_empty() {
+                    warnings.push(TableWarning::EmptyHeader { col });
+                } else if let Some(first) = headers[..col].iter().position(|h| h == name) {
+                    warnings.push(TableWarning::DuplicateHeader {
+                        name: name.clone(),
+                        first,
+                        col,
+                    });
+                }
+            }
+        }
+
+        let expected = match &self.headers {
+            Some(headers) => headers.len(),
+            None => self.rows.first().map_or(0, Vec::len),
+        };
+        for (row, cells) in self.rows.iter().enumerate() {
+            if cells.len() != expected {
+                warnings.push(TableWarning::RaggedRow {
+                    row,
+                    len: cells.len(),
+                    expected,
+                });
+            }
+            for (col, cell) in cells.iter().enumerate() {
+                if cell.contains('\0') {
+                    warnings.push(TableWarning::NulByte { row, col });
+                }
+            }
+        }
+
+        warnings
+    }
+
+    /// Whether every non-empty cell in `col` is a number. Columns without
+    /// any non-empty cell are treated as text.
+    pub fn column_is_numeric(&self, col: usize) -> bool {
+        let mut values = self
+            .rows
+            .iter()
+            .filter_map(|row| row.get(col))
+            .map(|cell| cell.trim())
+            .filter(|cell| !cell.is_empty())
+            .peekable();
+
+        values.peek().is_some() && values.all(is_numeric)
+    }
+
+    /// Collapse the table to one row per distinct value of column `key`, in
+    /// order of first appearance: the value, how many rows have it, then
+    /// each of `aggregates` over the group's numeric cells (blank when there
+    /// are none). Headers are named after the grouped columns when the
+    /// table has headers.
+    pub fn group_by(&self, key: usize, aggregates: &[GroupAggregate]) -> Table {
+        let mut positions: HashMap<&str, usize> = HashMap::new();
+        let mut groups: Vec<(&str, Vec<&Vec<String>>)> = Vec::new();
+        for row in &self.rows {
+            let value = row.get(key).map_or("", String::as_str);
+            let pos = *positions.entry(value).or_insert_with(|| {
+                groups.push((value, Vec::new()));
+                groups.len() - 1
+            });
+            groups[pos].1.push(row);
+        }
+
+        let rows = groups
+            .into_iter()
+            .map(|(value, rows)| {
+                let mut cells = vec![value.to_string(), rows.len().to_string()];
+                cells.extend(aggregates.iter().map(|agg| {
+                    let values: Vec<f64> = rows
+                        .iter()
+                        .filter_map(|row| row.get(agg.col))
+                        .filter_map(|cell| cell.trim().parse::<f64>().ok())
+                        .filter(|value| value.is_finite())
+                        .collect();
+                    agg.aggregate
+                        .apply(&values)
+                        .map_or_else(String::new, |value| value.to_string())
+                }));
+                cells
+            })
+            .collect();
+
+        let headers = self.headers.as_ref().map(|headers| {
+            let name = |col: usize| headers.get(col).cloned().unwrap_or_default();
+            let mut names = vec![name(key), "count".to_string()];
+            names.extend(
+                aggregates
+                    .iter()
+                    .map(|agg| format!("{}({})", agg.aggregate.name(), name(agg.col))),
+            );
+            names
+        });
+        Table::new(headers, rows)
+    }
+}
+
+/// A structural issue found by [`Table::validate`]. Rows and columns are
+/// 0-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableWarning {
+    RaggedRow { row: usize, len: usize, expected: usize },
+    DuplicateHeader { name: String, first: usize, col: usize },
+    EmptyHeader { col: usize },
+    NulByte { row: usize, col: usize },
+}
+
+impl fmt::Display for TableWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableWarning::RaggedRow { row, len, expected } => {
+                write!(f, "row {row} has {len} fields, expected {expected}")
+            }
+            TableWarning::DuplicateHeader { name, first, col } => {
+                write!(f, "header \"{name}\" of column {col} repeats column {first}")
+            }
+            TableWarning::EmptyHeader { col } => write!(f, "column {col} has an empty header"),
+            TableWarning::NulByte { row, col } => {
+                write!(f, "cell at row {row}, column {col} contains a NUL byte")
+            }
+        }
+    }
+}
+
+/// Keep only the first or last rows of a table (`--head`/`--tail`).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowSlice {
+    Head(usize),
+    Tail(usize),
+}
+
+impl RowSlice {
+    pub fn apply(self, table: &mut Table) {
+        match self {
+            RowSlice::Head(n) => table.rows.truncate(n),
+            RowSlice::Tail(n) => {
+                let skip = table.rows.len().saturating_sub(n);
+                table.rows.drain(..skip);
+            }
+        }
+    }
+}
+
+/// `value` without one layer of matching surrounding quotes, `"..."` or
+/// `'...'`. A lone quote or mismatched quotes are left as they are.
+pub fn strip_quotes(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value.strip_prefix(quote).and_then(|v| v.strip_suffix(quote)) {
+            return inner;
+        }
+    }
+    value
+}
+
+/// Whether a cell value reads as a finite number (so "inf" and "NaN" don't count).
+pub fn is_numeric(value: &str) -> bool {
+    value.parse::<f64>().is_ok_and(f64::is_finite)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum InputFormat {
+    #[default]
     Csv,
+    /// Tab-separated values, quoted like CSV.
+    Tsv,
     Json,
+    /// One JSON object per line (NDJSON or JSON Lines).
+    Ndjson,
+    /// Lines split on the literal `ParseOptions::separator` string.
+    Delimited,
+    /// Lines split on any one of the `ParseOptions::delimiters` characters.
+    AnyDelimiter,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl InputFormat {
+    /// The formats Ctrl+Shift+F cycles through. `Delimited` needs a `--sep`
+    /// and is left out; `AnyDelimiter` splits on whitespace unless
+    /// `--any-delimiter` gave other delimiters.
+    pub const CYCLE: [InputFormat; 5] = [
+        InputFormat::Csv,
+        InputFormat::Tsv,
+        InputFormat::AnyDelimiter,
+        InputFormat::Json,
+        InputFormat::Ndjson,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            InputFormat::Csv => "csv",
+            InputFormat::Tsv => "tsv",
+            InputFormat::Json => "json",
+            InputFormat::Ndjson => "ndjson",
+            InputFormat::Delimited => "delimited",
+            InputFormat::AnyDelimiter => "any-delimiter",
+        }
+    }
+
+    /// The format after this one in [`InputFormat::CYCLE`], wrapping around.
+    pub fn next(self) -> InputFormat {
+        match InputFormat::CYCLE.iter().position(|f| *f == self) {
+            Some(idx) => InputFormat::CYCLE[(idx + 1) % InputFormat::CYCLE.len()],
+            None => InputFormat::CYCLE[0],
+        }
+    }
+}
+
+/// Dialect knobs for parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub quote: u8,
+    /// When false, quote characters are kept as literal cell content.
+    pub quoting: bool,
+    /// Field separator for `InputFormat::Delimited`, matched exactly.
+    pub separator: String,
+    /// Field delimiters for `InputFormat::AnyDelimiter`.
+    pub delimiters: Vec<char>,
+    /// Treat a run of delimiters as one, ignoring leading and trailing ones.
+    pub collapse_delimiters: bool,
+    /// The text encoding of the input, transcoded to UTF-8 before parsing.
+    pub encoding: &'static Encoding,
+    /// Dotted path to the array of rows inside a JSON document, e.g.
+    /// `data.0.items`; `None` reads the top-level array.
+    pub json_path: Option<String>,
+    /// Blank input is an empty table in any format rather than an error
+    /// (`--allow-empty`).
+    pub allow_empty: bool,
+    /// Nested JSON objects become columns named by dotted paths, such as
+    /// `addr.city`, rather than one cell of raw JSON (`--flatten-json`).
+    pub flatten_json: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            quote: b'"',
+            quoting: true,
+            separator: ",".to_string(),
+            delimiters: Vec::new(),
+            collapse_delimiters: false,
+            encoding: encoding_rs::UTF_8,
+            json_path: None,
+            allow_empty: false,
+            flatten_json: false,
+        }
+    }
+}
+
+/// Where the table is read from.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum InputSource {
+    #[default]
+    Stdin,
+    /// Files whose rows are concatenated, or merged by header union.
+    Files { paths: Vec<PathBuf>, union: bool },
+    /// The standard output of a shell command.
+    Command(String),
+    /// Nothing: start with an empty table (`--null-input`).
+    Null,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum SelectionMode {
+    #[default]
     Row,
     Column,
     Cell,
 }
 
+/// What Enter does in `--multi` mode (`--multi-enter`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MultiEnter {
+    /// Output the checked rows, or the cursor row when none is checked.
+    #[default]
+    ConfirmAll,
+    /// Check or uncheck the cursor row like Tab; Ctrl+Enter confirms.
+    ToggleRow,
+}
+
+/// How the filter text is matched against cells. All modes are case-insensitive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilterMode {
+    /// The query appears anywhere in the cell.
+    #[default]
+    Substring,
+    /// The cell starts with the query.
+    Prefix,
+    /// The query matches whole words of the cell, splitting on non-alphanumerics.
+    Word,
+    /// The query's characters appear in order; rows are ranked by match score.
+    Fuzzy,
+}
+
+impl FilterMode {
+    pub const ALL: [FilterMode; 4] = [
+        FilterMode::Substring,
+        FilterMode::Prefix,
+        FilterMode::Word,
+        FilterMode::Fuzzy,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FilterMode::Substring => "substring",
+            FilterMode::Prefix => "prefix",
+            FilterMode::Word => "word",
+            FilterMode::Fuzzy => "fuzzy",
+        }
+    }
+
+    /// The mode after this one, wrapping around.
+    pub fn next(self) -> FilterMode {
+        let idx = FilterMode::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        FilterMode::ALL[(idx + 1) % FilterMode::ALL.len()]
+    }
+
+    /// Whether `cell` matches `query`, which must already be folded with
+    /// `fold`.
+    pub fn matches(self, cell: &str, query: &str, fold: CaseFold) -> bool {
+        let cell = fold.fold(cell);
+        match self {
+            FilterMode::Substring => cell.contains(query),
+            FilterMode::Prefix => cell.starts_with(query),
+            FilterMode::Word => {
+                let query_words = words(query);
+                if query_words.is_empty() {
+                    return true;
+                }
+                words(&cell)
+                    .windows(query_words.len())
+                    .any(|window| window == query_words.as_slice())
+            }
+            FilterMode::Fuzzy => fuzzy::score(&cell, query).is_some(),
+        }
+    }
+}
+
+/// How filter text and cells are made case-insensitive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaseFold {
+    /// `str::to_lowercase`, which keeps e.g. "ß" and a word-final "ς".
+    #[default]
+    Simple,
+    /// Full case folding (`CaseFolding.txt` statuses C and F), where e.g.
+    /// "ß" folds to "ss", "ς" to "σ" and "ﬁ" to "fi".
+    Full,
+}
+
+impl CaseFold {
+    pub fn fold(self, value: &str) -> String {
+        match self {
+            CaseFold::Simple => value.to_lowercase(),
+            CaseFold::Full => {
+                let mut folded = String::with_capacity(value.len());
+                for c in value.chars() {
+                    match case_fold::FOLDINGS.binary_search_by_key(&c, |&(from, _)| from) {
+                        Ok(idx) => folded.push_str(case_fold::FOLDINGS[idx].1),
+                        Err(_) => folded.extend(c.to_lowercase()),
+                    }
+                }
+                folded
+            }
+        }
+    }
+}
+
+fn words(value: &str) -> Vec<&str> {
+    value
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// How the footer row sums up a numeric column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Aggregate {
+    #[default]
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl Aggregate {
+    /// The aggregate named on the command line.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sum" => Some(Aggregate::Sum),
+            "avg" => Some(Aggregate::Avg),
+            "min" => Some(Aggregate::Min),
+            "max" => Some(Aggregate::Max),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Aggregate::Sum => "sum",
+            Aggregate::Avg => "avg",
+            Aggregate::Min => "min",
+            Aggregate::Max => "max",
+        }
+    }
+
+    /// Combine the values, or `None` if there are none.
+    pub fn apply(self, values: &[f64]) -> Option<f64> {
+        if values.is_empty() {
+            return None;
+        }
+        let sum = || values.iter().sum::<f64>();
+        Some(match self {
+            Aggregate::Sum => sum(),
+            Aggregate::Avg => sum() / values.len() as f64,
+            Aggregate::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            Aggregate::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        })
+    }
+}
+
+/// One `--group-agg`: an aggregate of an actual column within each group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GroupAggregate {
+    pub aggregate: Aggregate,
+    pub col: usize,
+}
+
+/// How `--decode` turns a selected cell into raw bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decode {
+    Base64,
+    Hex,
+}
+
+impl Decode {
+    /// Decode a cell's text, ignoring surrounding whitespace.
+    pub fn apply(self, value: &str) -> Result<Vec<u8>> {
+        let value = value.trim();
+        Ok(match self {
+            Decode::Base64 => base64::engine::general_purpose::STANDARD.decode(value)?,
+            Decode::Hex => hex::decode(value)?,
+        })
+    }
+}
+
+/// One `--sort` key: an actual column and which way it sorts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    pub col: usize,
+    pub descending: bool,
+}
+
+/// Order two cells of a column ascending. Numeric columns compare as numbers
+/// with empty cells last; other columns compare case-insensitively.
+pub fn compare_cells(a: &str, b: &str, numeric: bool) -> Ordering {
+    if numeric {
+        let number = |cell: &str| cell.trim().parse::<f64>().ok();
+        match (number(a), number(b)) {
+            (Some(x), Some(y)) => return x.total_cmp(&y),
+            (Some(_), None) => return Ordering::Less,
+            (None, Some(_)) => return Ordering::Greater,
+            (None, None) => {}
+        }
+    }
+    a.to_lowercase().cmp(&b.to_lowercase())
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OutputFormat {
     Plain,
     Json,
     Csv,
+    /// `header=value` lines, e.g. for sourcing into a shell.
+    Kv,
+    /// Shell-quoted fields joined by spaces, for `$(tabsel ...)` arguments.
+    Shell,
+    /// `NAME='value'` lines with shell-safe names, for `eval` or `source`.
+    Env,
+    /// One human-readable line listing the selected values, e.g.
+    /// "2 rows selected: Alice, Bob".
+    Summary,
+}
+
+impl OutputFormat {
+    /// The format named on the command line.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "plain" => Some(OutputFormat::Plain),
+            "json" => Some(OutputFormat::Json),
+            "csv" => Some(OutputFormat::Csv),
+            "kv" => Some(OutputFormat::Kv),
+            "shell" => Some(OutputFormat::Shell),
+            "env" => Some(OutputFormat::Env),
+            "summary" => Some(OutputFormat::Summary),
+            _ => None,
+        }
+    }
+}
+
+/// The output format used in each selection mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModeFormats {
+    pub row: OutputFormat,
+    pub column: OutputFormat,
+    pub cell: OutputFormat,
+}
+
+impl ModeFormats {
+    /// The same format in every mode.
+    pub fn all(format: OutputFormat) -> Self {
+        ModeFormats {
+            row: format,
+            column: format,
+            cell: format,
+        }
+    }
+
+    pub fn get(&self, mode: SelectionMode) -> OutputFormat {
+        match mode {
+            SelectionMode::Row => self.row,
+            SelectionMode::Column => self.column,
+            SelectionMode::Cell => self.cell,
+        }
+    }
+
+    /// Parse a single format for all modes, or a `mode=format` list such as
+    /// `row=csv,cell=plain`. Modes missing from a list use plain.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        if !spec.contains('=') {
+            return OutputFormat::from_name(spec)
+                .map(ModeFormats::all)
+                .ok_or_else(|| format!("unknown output format: {spec}"));
+        }
+
+        let mut formats = ModeFormats::all(OutputFormat::Plain);
+        for entry in spec.split(',').filter(|entry| !entry.trim().is_empty()) {
+            let (mode, name) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected mode=format, got: {entry}"))?;
+            let format = OutputFormat::from_name(name.trim())
+                .ok_or_else(|| format!("unknown output format: {}", name.trim()))?;
+            match mode.trim() {
+                "row" => formats.row = format,
+                "column" => formats.column = format,
+                "cell" => formats.cell = format,
+                other => return Err(format!("unknown mode: {other}")),
+            }
+        }
+        Ok(formats)
+    }
+}
+
+impl Default for ModeFormats {
+    fn default() -> Self {
+        ModeFormats::all(OutputFormat::Plain)
+    }
+}
+
+/// Formatting knobs shared by all selection outputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputOptions {
+    /// Quote character used by CSV output.
+    pub quote: u8,
+    /// CSV output quotes every field, not just those that need it.
+    pub quote_all: bool,
+    /// Printed output starts with a UTF-8 byte order mark (`--output-bom`).
+    pub bom: bool,
+    /// Cell output is the whole row as a JSON object naming the chosen
+    /// column in `_selected`, whatever the output format.
+    pub cell_context: bool,
+    /// CSV output of several checked rows starts with the header record.
+    pub multi_header: bool,
+    /// Replaces the text before the colon of `summary` output; `{n}` is
+    /// the number of selected values.
+    pub summary_prefix: Option<String>,
+    /// Joins the fields of a row, or the header names, in plain output.
+    /// Any string, including an empty one.
+    pub plain_separator: String,
+    /// Row and column indices in the output count from 1 (`--one-based`).
+    pub one_based: bool,
+}
+
+impl OutputOptions {
+    /// A 0-based row or column index as it is written out.
+    pub fn index(&self, idx: usize) -> usize {
+        idx + usize::from(self.one_based)
+    }
+}
+
+impl Default for OutputOptions {
+    fn default() -> Self {
+        OutputOptions {
+            quote: b'"',
+            quote_all: false,
+            bom: false,
+            cell_context: false,
+            multi_header: false,
+            summary_prefix: None,
+            plain_separator: ",".to_string(),
+            one_based: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(rows: &[&[&str]]) -> Table {
+        Table::from_records(None, rows.iter().map(|row| row.iter().copied()))
+    }
+
+    #[test]
+    fn group_by_counts_rows_per_value() {
+        let mut sales = table(&[&["east", "3"], &["west", "2"], &["east", "x"], &["", "1"]]);
+        sales.headers = Some(vec!["region".to_string(), "units".to_string()]);
+
+        let grouped = sales.group_by(0, &[]);
+        assert_eq!(grouped.headers, Some(vec!["region".to_string(), "count".to_string()]));
+        assert_eq!(
+            grouped.rows,
+            vec![vec!["east", "2"], vec!["west", "1"], vec!["", "1"]]
+        );
+    }
+
+    #[test]
+    fn group_by_aggregates_numeric_cells() {
+        let sales = table(&[&["east", "3"], &["west", "2.5"], &["east", "4"], &["north", "n/a"]]);
+        let aggregates = [
+            GroupAggregate {
+                aggregate: Aggregate::Sum,
+                col: 1,
+            },
+            GroupAggregate {
+                aggregate: Aggregate::Max,
+                col: 1,
+            },
+        ];
+
+        let grouped = sales.group_by(0, &aggregates);
+        assert_eq!(grouped.headers, None);
+        assert_eq!(
+            grouped.rows,
+            vec![
+                vec!["east", "2", "7", "4"],
+                vec!["west", "1", "2.5", "2.5"],
+                vec!["north", "1", "", ""],
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_base64_and_hex() {
+        assert_eq!(Decode::Base64.apply("aGk=\n").unwrap(), b"hi");
+        assert_eq!(Decode::Base64.apply("").unwrap(), b"");
+        assert_eq!(Decode::Hex.apply("00ff10").unwrap(), vec![0x00, 0xff, 0x10]);
+        assert_eq!(Decode::Hex.apply("DEADbeef").unwrap(), vec![0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    #[test]
+    fn decode_rejects_malformed_input() {
+        assert!(Decode::Base64.apply("not base64!").is_err());
+        assert!(Decode::Base64.apply("aGk").is_err());
+        assert!(Decode::Hex.apply("abc").is_err());
+        assert!(Decode::Hex.apply("zz").is_err());
+    }
+
+    #[test]
+    fn numeric_column_detection() {
+        let t = table(&[&["Alice", "30", "1.5"], &["Bob", "", "-2e3"], &["Carol", "25"]]);
+
+        assert!(!t.column_is_numeric(0));
+        // Empty cells and short rows are ignored
+        assert!(t.column_is_numeric(1));
+        assert!(t.column_is_numeric(2));
+    }
+
+    #[test]
+    fn empty_or_non_finite_columns_are_text() {
+        let t = table(&[&["", "inf"], &["", "NaN"]]);
+
+        assert!(!t.column_is_numeric(0));
+        assert!(!t.column_is_numeric(1));
+        assert!(!t.column_is_numeric(5));
+    }
+
+    #[test]
+    fn strip_quotes_removes_one_matching_layer() {
+        assert_eq!(strip_quotes("\"Alice\""), "Alice");
+        assert_eq!(strip_quotes("'Bob'"), "Bob");
+        assert_eq!(strip_quotes("\"'Carol'\""), "'Carol'");
+        assert_eq!(strip_quotes("\"\""), "");
+        assert_eq!(strip_quotes("Dave"), "Dave");
+        assert_eq!(strip_quotes("it's"), "it's");
+    }
+
+    #[test]
+    fn strip_quotes_leaves_lone_and_mismatched_quotes() {
+        assert_eq!(strip_quotes("\""), "\"");
+        assert_eq!(strip_quotes("'"), "'");
+        assert_eq!(strip_quotes("\"Alice'"), "\"Alice'");
+        assert_eq!(strip_quotes("\"Alice"), "\"Alice");
+        assert_eq!(strip_quotes(""), "");
+    }
+
+    #[test]
+    fn strip_quotes_applies_to_headers_and_cells() {
+        let mut t = Table::from_records(
+            Some(vec!["\"name\"".into(), "age".into()]),
+            vec![vec!["\"Alice\"", "'30'"], vec!["\"", "25"]],
+        );
+        t.strip_quotes();
+
+        assert_eq!(t.headers, Some(vec!["name".to_string(), "age".to_string()]));
+        assert_eq!(t.rows, vec![vec!["Alice", "30"], vec!["\"", "25"]]);
+    }
+
+    #[test]
+    fn width_of_ragged_tables() {
+        assert_eq!(table(&[&["a", "b"], &["c"], &["d", "e", "f"]]).width(), 3);
+
+        let mut t = table(&[&["a"], &["b", "c"]]);
+        t.headers = Some(vec!["x".to_string(), "y".to_string(), "z".to_string()]);
+        assert_eq!(t.width(), 3);
+
+        assert_eq!(table(&[]).width(), 0);
+        assert!(table(&[]).is_empty());
+    }
+
+    #[test]
+    fn mode_formats_single_and_mapping() {
+        assert_eq!(ModeFormats::parse("csv"), Ok(ModeFormats::all(OutputFormat::Csv)));
+
+        let formats = ModeFormats::parse("row=csv, cell=plain,column=json").unwrap();
+        assert_eq!(formats.get(SelectionMode::Row), OutputFormat::Csv);
+        assert_eq!(formats.get(SelectionMode::Column), OutputFormat::Json);
+        assert_eq!(formats.get(SelectionMode::Cell), OutputFormat::Plain);
+
+        let partial = ModeFormats::parse("cell=kv").unwrap();
+        assert_eq!(partial.row, OutputFormat::Plain);
+        assert_eq!(partial.cell, OutputFormat::Kv);
+    }
+
+    #[test]
+    fn mode_formats_errors() {
+        assert!(ModeFormats::parse("xml").is_err());
+        assert!(ModeFormats::parse("row=xml").is_err());
+        assert!(ModeFormats::parse("table=csv").is_err());
+        assert!(ModeFormats::parse("row=csv,cell").is_err());
+    }
+
+    #[test]
+    fn validate_reports_ragged_rows() {
+        let t = table(&[&["a", "b"], &["c"], &["d", "e", "f"], &["g", "h"]]);
+
+        assert_eq!(
+            t.validate(),
+            vec![
+                TableWarning::RaggedRow { row: 1, len: 1, expected: 2 },
+                TableWarning::RaggedRow { row: 2, len: 3, expected: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_reports_header_problems() {
+        let t = Table::from_records(
+            Some(vec!["id".into(), "".into(), "id".into()]),
+            vec![vec!["1", "x\0", "2"]],
+        );
+
+        assert_eq!(
+            t.validate(),
+            vec![
+                TableWarning::EmptyHeader { col: 1 },
+                TableWarning::DuplicateHeader {
+                    name: "id".to_string(),
+                    first: 0,
+                    col: 2,
+                },
+                TableWarning::NulByte { row: 0, col: 1 },
+            ]
+        );
+        assert!(table(&[&["a"], &["b"]]).validate().is_empty());
+    }
+
+    #[test]
+    fn full_case_folding() {
+        assert_eq!(CaseFold::Simple.fold("Straße"), "straße");
+        assert_eq!(CaseFold::Full.fold("Straße"), "strasse");
+        assert_eq!(CaseFold::Full.fold("STRASSE"), "strasse");
+        // Lowercasing picks the final form of sigma at the end of a word
+        assert_eq!(CaseFold::Simple.fold("ΟΔΟΣ"), "οδος");
+        assert_eq!(CaseFold::Full.fold("ΟΔΟΣ"), "οδοσ");
+        assert_eq!(CaseFold::Full.fold("ﬁle"), "file");
+        // Dotted capital I lowercases to "i" plus a combining dot either way
+        assert_eq!(CaseFold::Full.fold("İ"), "i\u{307}");
+        // Multi-character foldings beyond the Latin ones
+        assert_eq!(CaseFold::Full.fold("ẞ"), "ss");
+        assert_eq!(CaseFold::Full.fold("ǰ"), "j\u{30c}");
+        assert_eq!(CaseFold::Full.fold("ΐ"), "ι\u{308}\u{301}");
+        assert_eq!(CaseFold::Full.fold("ﬓ"), "մն");
+        assert_eq!(CaseFold::Full.fold("ᾈ"), "ἀι");
+    }
+
+    #[test]
+    fn case_foldings_are_sorted_for_lookup() {
+        assert!(case_fold::FOLDINGS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn filter_matches_with_full_folding() {
+        let query = CaseFold::Full.fold("strasse");
+        assert!(FilterMode::Substring.matches("Hauptstraße", &query, CaseFold::Full));
+        assert!(!FilterMode::Substring.matches("Hauptstraße", "strasse", CaseFold::Simple));
+        let query = CaseFold::Full.fold("οδοσ");
+        assert!(FilterMode::Word.matches("ΟΔΟΣ ΑΘΗΝΑΣ", &query, CaseFold::Full));
+    }
+
+    #[test]
+    fn head_and_tail_slices() {
+        let rows: &[&[&str]] = &[&["a"], &["b"], &["c"]];
+
+        let mut head = table(rows);
+        RowSlice::Head(2).apply(&mut head);
+        assert_eq!(head.rows, vec![vec!["a"], vec!["b"]]);
+
+        let mut tail = table(rows);
+        RowSlice::Tail(2).apply(&mut tail);
+        assert_eq!(tail.rows, vec![vec!["b"], vec!["c"]]);
+
+        let mut short = table(rows);
+        RowSlice::Tail(10).apply(&mut short);
+        assert_eq!(short.rows.len(), 3);
+    }
+
+    #[test]
+    fn out_of_range_access_is_none() {
+        let t = table(&[&["a", "b"], &["c"]]);
+
+        assert_eq!(t.row(1), Some(&vec!["c".to_string()]));
+        assert_eq!(t.row(2), None);
+        assert_eq!(t.cell(0, 1), Some("b"));
+        assert_eq!(t.cell(1, 1), None);
+        assert_eq!(t.cell(5, 0), None);
+    }
 }
@@ -1,66 +1,293 @@
-use super::{OutputFormat, Table};
+use super::{OutputFormat, OutputOptions, Table};
 
-pub fn format_row(table: &Table, format: OutputFormat, row_idx: usize) -> String {
-    let row = &table.rows[row_idx];
+/// Format a data row. An out-of-range index formats as an empty row.
+pub fn format_row(
+    table: &Table,
+    format: OutputFormat,
+    opts: &OutputOptions,
+    row_idx: usize,
+) -> String {
+    let row: &[String] = table.row(row_idx).map_or(&[], Vec::as_slice);
+    match format {
+        OutputFormat::Plain | OutputFormat::Summary => row.join(&opts.plain_separator),
+        OutputFormat::Csv => csv_encode_row(row, opts),
+        OutputFormat::Shell => shell_join(row),
+        OutputFormat::Json => serde_json::to_string(&row_json(table, row)).unwrap(),
+        OutputFormat::Kv | OutputFormat::Env => {
+            let width = row.len().max(table.headers.as_ref().map_or(0, Vec::len));
+            (0..width)
+                .map(|i| {
+                    let value = row.get(i).map_or("", String::as_str);
+                    match format {
+                        OutputFormat::Env => env_assignment(table, i, value, opts),
+                        _ => format!("{}={value}", column_key(table, i, opts)),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    }
+}
+
+/// A row as a JSON object keyed by header, or an array for headerless tables.
+fn row_json(table: &Table, row: &[String]) -> serde_json::Value {
+    if let Some(headers) = &table.headers {
+        let obj: serde_json::Map<String, serde_json::Value> = headers
+            .iter()
+            .enumerate()
+            .map(|(i, h)| {
+                let val = row.get(i).cloned().unwrap_or_default();
+                (h.clone(), serde_json::Value::String(val))
+            })
+            .collect();
+        serde_json::Value::Object(obj)
+    } else {
+        row.iter()
+            .map(|v| serde_json::Value::String(v.clone()))
+            .collect()
+    }
+}
+
+/// Format a whole table, e.g. the checked columns of Column mode: CSV
+/// records led by the header record, a JSON array of row objects (row arrays
+/// for headerless tables), or one line per row with tab-separated fields in
+/// plain output.
+pub fn format_table(table: &Table, format: OutputFormat, opts: &OutputOptions) -> String {
+    match format {
+        OutputFormat::Json => format_json_rows(table),
+        OutputFormat::Csv => table
+            .headers
+            .iter()
+            .chain(&table.rows)
+            .map(|row| csv_encode_row(row, opts))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        OutputFormat::Plain | OutputFormat::Summary => table
+            .rows
+            .iter()
+            .map(|row| row.join("\t"))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        OutputFormat::Shell | OutputFormat::Kv | OutputFormat::Env => (0..table.rows.len())
+            .map(|idx| format_row(table, format, opts, idx))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// Format every row of the table as a single-line JSON array of row objects
+/// (row arrays for headerless tables), for `--output json-rows`.
+pub fn format_json_rows(table: &Table) -> String {
+    let rows: Vec<serde_json::Value> = table.rows.iter().map(|row| row_json(table, row)).collect();
+    serde_json::to_string(&rows).unwrap()
+}
+
+/// The columns two rows differ in, as `(column, value in i, value in j)`
+/// with the column named by its header, or its index for headerless tables.
+pub fn diff_rows(
+    table: &Table,
+    opts: &OutputOptions,
+    i: usize,
+    j: usize,
+) -> Vec<(String, String, String)> {
+    (0..table.width())
+        .filter_map(|col| {
+            let a = table.cell(i, col).unwrap_or_default();
+            let b = table.cell(j, col).unwrap_or_default();
+            (a != b).then(|| (column_key(table, col, opts), a.to_string(), b.to_string()))
+        })
+        .collect()
+}
+
+/// Format a row diff: a JSON array of `{"column", "from", "to"}` objects,
+/// CSV records of the same three fields, or `column: a -> b` lines.
+pub fn format_diff(
+    changes: &[(String, String, String)],
+    format: OutputFormat,
+    opts: &OutputOptions,
+) -> String {
     match format {
-        OutputFormat::Plain => row.join(","),
-        OutputFormat::Csv => csv_encode_row(row),
         OutputFormat::Json => {
-            if let Some(headers) = &table.headers {
-                let obj: serde_json::Map<String, serde_json::Value> = headers
-                    .iter()
-                    .enumerate()
-                    .map(|(i, h)| {
-                        let val = row.get(i).cloned().unwrap_or_default();
-                        (h.clone(), serde_json::Value::String(val))
-                    })
-                    .collect();
-                serde_json::to_string(&obj).unwrap()
-            } else {
-                let arr: Vec<serde_json::Value> = row
-                    .iter()
-                    .map(|v| serde_json::Value::String(v.clone()))
-                    .collect();
-                serde_json::to_string(&arr).unwrap()
-            }
+            let arr: Vec<serde_json::Value> = changes
+                .iter()
+                .map(|(column, from, to)| {
+                    serde_json::json!({ "column": column, "from": from, "to": to })
+                })
+                .collect();
+            serde_json::to_string(&arr).unwrap()
         }
+        OutputFormat::Csv => changes
+            .iter()
+            .map(|(column, from, to)| {
+                csv_encode_row(&[column.clone(), from.clone(), to.clone()], opts)
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        OutputFormat::Plain
+        | OutputFormat::Kv
+        | OutputFormat::Shell
+        | OutputFormat::Env
+        | OutputFormat::Summary => changes
+            .iter()
+            .map(|(column, from, to)| format!("{column}: {from} -> {to}"))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// A human-readable line naming the selected values, for `--output summary`:
+/// "Selected: Alice" for one value and "3 rows selected: Alice, Bob, Carol"
+/// for several. `opts.summary_prefix` replaces the text before the colon,
+/// with `{n}` standing for the number of values.
+pub fn format_summary(values: &[String], opts: &OutputOptions) -> String {
+    if values.is_empty() {
+        return "Nothing selected".to_string();
+    }
+
+    let count = values.len();
+    let prefix = match &opts.summary_prefix {
+        Some(prefix) => prefix.replace("{n}", &count.to_string()),
+        None if count == 1 => "Selected".to_string(),
+        None => format!("{count} rows selected"),
+    };
+    format!("{prefix}: {}", values.join(", "))
+}
+
+/// The text as printed: with `opts.bom`, prefixed by a UTF-8 byte order
+/// mark for tools such as Excel that need one to detect the encoding.
+pub fn with_bom(text: String, opts: &OutputOptions) -> String {
+    if opts.bom {
+        format!("\u{FEFF}{text}")
+    } else {
+        text
     }
 }
 
-pub fn format_column(table: &Table, format: OutputFormat, col_idx: usize) -> String {
-    let col_name = table
+/// The header name of a column, or its index for headerless tables.
+fn column_key(table: &Table, col_idx: usize, opts: &OutputOptions) -> String {
+    table
         .headers
         .as_ref()
-        .and_then(|h| h.get(col_idx).cloned());
+        .and_then(|h| h.get(col_idx).cloned())
+        .unwrap_or_else(|| opts.index(col_idx).to_string())
+}
 
+/// Format the header row itself. Headerless tables produce an empty row.
+pub fn format_header(table: &Table, format: OutputFormat, opts: &OutputOptions) -> String {
+    let headers: &[String] = table.headers.as_deref().unwrap_or_default();
     match format {
-        OutputFormat::Plain => col_name.unwrap_or_else(|| col_idx.to_string()),
-        OutputFormat::Csv => col_name.unwrap_or_else(|| col_idx.to_string()),
+        OutputFormat::Plain | OutputFormat::Kv | OutputFormat::Env | OutputFormat::Summary => {
+            headers.join(&opts.plain_separator)
+        }
+        OutputFormat::Csv => csv_encode_row(headers, opts),
+        OutputFormat::Shell => shell_join(headers),
+        OutputFormat::Json => {
+            let arr: Vec<serde_json::Value> = headers
+                .iter()
+                .map(|h| serde_json::Value::String(h.clone()))
+                .collect();
+            serde_json::to_string(&arr).unwrap()
+        }
+    }
+}
+
+/// List the column names for `--list-columns`: one per line, or a JSON
+/// array for JSON output. Headerless tables list their column indices,
+/// always from 0 as column specs take them.
+pub fn format_column_list(table: &Table, format: OutputFormat) -> String {
+    let opts = OutputOptions::default();
+    let names: Vec<String> = (0..table.width())
+        .map(|col| column_key(table, col, &opts))
+        .collect();
+    match format {
+        OutputFormat::Json => {
+            let arr: Vec<serde_json::Value> =
+                names.into_iter().map(serde_json::Value::String).collect();
+            serde_json::to_string(&arr).unwrap()
+        }
+        OutputFormat::Shell => shell_join(&names),
+        OutputFormat::Plain
+        | OutputFormat::Csv
+        | OutputFormat::Kv
+        | OutputFormat::Env
+        | OutputFormat::Summary => names.join("\n"),
+    }
+}
+
+pub fn format_column(
+    table: &Table,
+    format: OutputFormat,
+    opts: &OutputOptions,
+    col_idx: usize,
+) -> String {
+    let col_name = column_key(table, col_idx, opts);
+
+    match format {
+        OutputFormat::Plain
+        | OutputFormat::Csv
+        | OutputFormat::Kv
+        | OutputFormat::Env
+        | OutputFormat::Summary => col_name,
+        OutputFormat::Shell => shell_quote(&col_name),
         OutputFormat::Json => {
             let mut obj = serde_json::Map::new();
-            obj.insert(
-                "column".to_string(),
-                serde_json::Value::String(col_name.unwrap_or_else(|| col_idx.to_string())),
-            );
+            obj.insert("column".to_string(), serde_json::Value::String(col_name));
             serde_json::to_string(&obj).unwrap()
         }
     }
 }
 
+/// Format the values of a column for the given rows, one per line (a JSON
+/// array for JSON output).
+pub fn format_column_values(
+    table: &Table,
+    format: OutputFormat,
+    opts: &OutputOptions,
+    col_idx: usize,
+    row_indices: &[usize],
+) -> String {
+    let values: Vec<String> = row_indices
+        .iter()
+        .map(|&row_idx| table.cell(row_idx, col_idx).unwrap_or_default().to_string())
+        .collect();
+
+    match format {
+        OutputFormat::Plain | OutputFormat::Kv | OutputFormat::Env | OutputFormat::Summary => {
+            values.join("\n")
+        }
+        OutputFormat::Shell => shell_join(&values),
+        OutputFormat::Csv => values
+            .into_iter()
+            .map(|value| csv_encode_row(&[value], opts))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        OutputFormat::Json => {
+            let arr: Vec<serde_json::Value> =
+                values.into_iter().map(serde_json::Value::String).collect();
+            serde_json::to_string(&arr).unwrap()
+        }
+    }
+}
+
 pub fn format_cell(
     table: &Table,
     format: OutputFormat,
+    opts: &OutputOptions,
     row_idx: usize,
     col_idx: usize,
 ) -> String {
-    let value = table.rows[row_idx]
-        .get(col_idx)
-        .cloned()
-        .unwrap_or_default();
+    if opts.cell_context {
+        return format_cell_context(table, opts, row_idx, col_idx);
+    }
+
+    let value = table.cell(row_idx, col_idx).unwrap_or_default().to_string();
 
     match format {
-        OutputFormat::Plain => value,
-        OutputFormat::Csv => csv_encode_row(&[value]),
+        OutputFormat::Plain | OutputFormat::Summary => value,
+        OutputFormat::Csv => csv_encode_row(&[value], opts),
+        OutputFormat::Shell => shell_quote(&value),
+        OutputFormat::Kv => format!("{}={value}", column_key(table, col_idx, opts)),
+        OutputFormat::Env => env_assignment(table, col_idx, &value, opts),
         OutputFormat::Json => {
             let mut obj = serde_json::Map::new();
             obj.insert(
@@ -69,25 +296,95 @@ pub fn format_cell(
             );
             obj.insert(
                 "row".to_string(),
-                serde_json::Value::Number(serde_json::Number::from(row_idx)),
+                serde_json::Value::Number(serde_json::Number::from(opts.index(row_idx))),
             );
-            let col_name = table
-                .headers
-                .as_ref()
-                .and_then(|h| h.get(col_idx).cloned())
-                .unwrap_or_else(|| col_idx.to_string());
             obj.insert(
                 "column".to_string(),
-                serde_json::Value::String(col_name),
+                serde_json::Value::String(column_key(table, col_idx, opts)),
             );
             serde_json::to_string(&obj).unwrap()
         }
     }
 }
 
-fn csv_encode_row(fields: &[String]) -> String {
+/// The row holding a cell as a JSON object keyed by header (or column index),
+/// with `_selected` naming the cell's column.
+fn format_cell_context(
+    table: &Table,
+    opts: &OutputOptions,
+    row_idx: usize,
+    col_idx: usize,
+) -> String {
+    let row: &[String] = table.row(row_idx).map_or(&[], Vec::as_slice);
+    let width = row.len().max(table.headers.as_ref().map_or(0, Vec::len));
+    let mut obj: serde_json::Map<String, serde_json::Value> = (0..width)
+        .map(|i| {
+            let value = row.get(i).cloned().unwrap_or_default();
+            (column_key(table, i, opts), serde_json::Value::String(value))
+        })
+        .collect();
+    obj.insert(
+        "_selected".to_string(),
+        serde_json::Value::String(column_key(table, col_idx, opts)),
+    );
+    serde_json::to_string(&obj).unwrap()
+}
+
+/// Quote a string for a POSIX shell: wrap it in single quotes and write
+/// embedded single quotes as `'\''`.
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Turn a header into a shell variable name: uppercased, with anything but
+/// ASCII letters, digits and `_` replaced by `_`, and a leading `_` if it
+/// would start with a digit (or be empty).
+pub fn env_name(header: &str) -> String {
+    let name: String = header
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.chars().next().is_none_or(|c| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+/// A `NAME='value'` line for `--output env`. Columns without a header are
+/// named `COL0`, `COL1`...
+fn env_assignment(table: &Table, col_idx: usize, value: &str, opts: &OutputOptions) -> String {
+    let name = table
+        .headers
+        .as_ref()
+        .and_then(|h| h.get(col_idx))
+        .map_or_else(|| format!("COL{}", opts.index(col_idx)), |header| env_name(header));
+    format!("{name}={}", shell_quote(value))
+}
+
+fn shell_join(fields: &[String]) -> String {
+    fields
+        .iter()
+        .map(|field| shell_quote(field))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn csv_encode_row(fields: &[String], opts: &OutputOptions) -> String {
     let mut wtr = csv::WriterBuilder::new()
         .has_headers(false)
+        .quote(opts.quote)
+        .quote_style(if opts.quote_all {
+            csv::QuoteStyle::Always
+        } else {
+            csv::QuoteStyle::Necessary
+        })
         .from_writer(Vec::new());
     wtr.write_record(fields).unwrap();
     wtr.flush().unwrap();
@@ -125,20 +422,45 @@ mod tests {
     #[test]
     fn row_plain_with_headers() {
         let t = table_with_headers();
-        assert_eq!(format_row(&t, OutputFormat::Plain, 0), "Alice,30");
+        assert_eq!(format_row(&t, OutputFormat::Plain, &OutputOptions::default(), 0), "Alice,30");
     }
 
     #[test]
     fn row_plain_without_headers() {
         let t = table_without_headers();
-        assert_eq!(format_row(&t, OutputFormat::Plain, 1), "Bob,25");
+        assert_eq!(format_row(&t, OutputFormat::Plain, &OutputOptions::default(), 1), "Bob,25");
+    }
+
+    fn plain_sep(sep: &str) -> OutputOptions {
+        OutputOptions {
+            plain_separator: sep.to_string(),
+            ..OutputOptions::default()
+        }
+    }
+
+    #[test]
+    fn row_plain_empty_separator_concatenates() {
+        let t = table_with_headers();
+        let opts = plain_sep("");
+        assert_eq!(format_row(&t, OutputFormat::Plain, &opts, 0), "Alice30");
+        assert_eq!(format_header(&t, OutputFormat::Plain, &opts), "nameage");
+    }
+
+    #[test]
+    fn row_plain_multi_char_separator() {
+        let t = table_with_headers();
+        let opts = plain_sep(" :: ");
+        assert_eq!(format_row(&t, OutputFormat::Plain, &opts, 1), "Bob :: 25");
+        assert_eq!(format_header(&t, OutputFormat::Plain, &opts), "name :: age");
+        // Other formats keep their own separators
+        assert_eq!(format_row(&t, OutputFormat::Csv, &opts, 1), "Bob,25");
     }
 
     #[test]
     fn row_json_with_headers() {
         let t = table_with_headers();
         assert_eq!(
-            format_row(&t, OutputFormat::Json, 0),
+            format_row(&t, OutputFormat::Json, &OutputOptions::default(), 0),
             r#"{"name":"Alice","age":"30"}"#
         );
     }
@@ -147,7 +469,7 @@ mod tests {
     fn row_json_without_headers() {
         let t = table_without_headers();
         assert_eq!(
-            format_row(&t, OutputFormat::Json, 0),
+            format_row(&t, OutputFormat::Json, &OutputOptions::default(), 0),
             r#"["Alice","30"]"#
         );
     }
@@ -155,7 +477,7 @@ mod tests {
     #[test]
     fn row_csv_with_headers() {
         let t = table_with_headers();
-        assert_eq!(format_row(&t, OutputFormat::Csv, 0), "Alice,30");
+        assert_eq!(format_row(&t, OutputFormat::Csv, &OutputOptions::default(), 0), "Alice,30");
     }
 
     #[test]
@@ -165,32 +487,264 @@ mod tests {
             rows: vec![vec!["Alice".to_string(), "likes cats, dogs".to_string()]],
         };
         assert_eq!(
-            format_row(&t, OutputFormat::Csv, 0),
+            format_row(&t, OutputFormat::Csv, &OutputOptions::default(), 0),
             r#"Alice,"likes cats, dogs""#
         );
     }
 
+    #[test]
+    fn row_csv_with_custom_quote() {
+        let t = Table {
+            headers: None,
+            rows: vec![vec!["Alice".to_string(), "likes cats, dogs".to_string()]],
+        };
+        let opts = OutputOptions {
+            quote: b'\'',
+            ..Default::default()
+        };
+        assert_eq!(format_row(&t, OutputFormat::Csv, &opts, 0), "Alice,'likes cats, dogs'");
+    }
+
+    #[test]
+    fn csv_quote_all_quotes_simple_fields() {
+        let t = table_with_headers();
+        let opts = OutputOptions {
+            quote_all: true,
+            ..Default::default()
+        };
+        assert_eq!(format_row(&t, OutputFormat::Csv, &opts, 0), r#""Alice","30""#);
+        assert_eq!(format_header(&t, OutputFormat::Csv, &opts), r#""name","age""#);
+        assert_eq!(format_cell(&t, OutputFormat::Csv, &opts, 1, 0), r#""Bob""#);
+        // Plain output is untouched
+        assert_eq!(format_row(&t, OutputFormat::Plain, &opts, 0), "Alice,30");
+    }
+
+    #[test]
+    fn row_kv_with_headers() {
+        let t = table_with_headers();
+        assert_eq!(
+            format_row(&t, OutputFormat::Kv, &OutputOptions::default(), 0),
+            "name=Alice\nage=30"
+        );
+    }
+
+    #[test]
+    fn row_kv_without_headers() {
+        let t = table_without_headers();
+        assert_eq!(format_row(&t, OutputFormat::Kv, &OutputOptions::default(), 1), "0=Bob\n1=25");
+    }
+
+    #[test]
+    fn cell_kv() {
+        let t = table_with_headers();
+        assert_eq!(format_cell(&t, OutputFormat::Kv, &OutputOptions::default(), 1, 0), "name=Bob");
+    }
+
+    #[test]
+    fn env_names_are_shell_identifiers() {
+        assert_eq!(env_name("name"), "NAME");
+        assert_eq!(env_name("first name"), "FIRST_NAME");
+        assert_eq!(env_name("e-mail.addr"), "E_MAIL_ADDR");
+        assert_eq!(env_name("2fa"), "_2FA");
+        assert_eq!(env_name("café"), "CAF_");
+        assert_eq!(env_name(""), "_");
+    }
+
+    #[test]
+    fn env_row_output_quotes_values() {
+        let t = Table::from_records(
+            Some(vec!["first name".into(), "motto".into()]),
+            vec![vec!["Alice", "it's $HOME"]],
+        );
+        assert_eq!(
+            format_row(&t, OutputFormat::Env, &OutputOptions::default(), 0),
+            "FIRST_NAME='Alice'\nMOTTO='it'\\''s $HOME'"
+        );
+        assert_eq!(
+            format_cell(&t, OutputFormat::Env, &OutputOptions::default(), 0, 0),
+            "FIRST_NAME='Alice'"
+        );
+    }
+
+    #[test]
+    fn env_without_headers_uses_column_numbers() {
+        let t = table_without_headers();
+        assert_eq!(
+            format_row(&t, OutputFormat::Env, &OutputOptions::default(), 1),
+            "COL0='Bob'\nCOL1='25'"
+        );
+    }
+
+    #[test]
+    fn shell_quote_spaces_quotes_and_empty() {
+        assert_eq!(shell_quote("two words"), "'two words'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn row_shell() {
+        let t = Table {
+            headers: None,
+            rows: vec![vec!["Bob Smith".to_string(), "O'Neil".to_string(), String::new()]],
+        };
+        assert_eq!(
+            format_row(&t, OutputFormat::Shell, &OutputOptions::default(), 0),
+            r"'Bob Smith' 'O'\''Neil' ''"
+        );
+    }
+
+    #[test]
+    fn json_rows_with_headers() {
+        let t = table_with_headers();
+        assert_eq!(
+            format_json_rows(&t),
+            r#"[{"name":"Alice","age":"30"},{"name":"Bob","age":"25"}]"#
+        );
+    }
+
+    #[test]
+    fn json_rows_without_headers() {
+        let t = table_without_headers();
+        assert_eq!(format_json_rows(&t), r#"[["Alice","30"],["Bob","25"]]"#);
+    }
+
+    // --- Row diff ---
+
+    #[test]
+    fn diff_of_identical_rows_is_empty() {
+        let t = Table::from_records(
+            Some(vec!["name".into(), "age".into()]),
+            vec![vec!["Alice", "30"], vec!["Alice", "30"]],
+        );
+        assert_eq!(diff_rows(&t, &OutputOptions::default(), 0, 1), vec![]);
+    }
+
+    #[test]
+    fn diff_lists_differing_columns() {
+        let t = table_with_headers();
+        let changes = diff_rows(&t, &OutputOptions::default(), 0, 1);
+        assert_eq!(
+            changes,
+            vec![
+                ("name".to_string(), "Alice".to_string(), "Bob".to_string()),
+                ("age".to_string(), "30".to_string(), "25".to_string()),
+            ]
+        );
+
+        let opts = OutputOptions::default();
+        assert_eq!(
+            format_diff(&changes, OutputFormat::Plain, &opts),
+            "name: Alice -> Bob\nage: 30 -> 25"
+        );
+        assert_eq!(
+            format_diff(&changes[1..], OutputFormat::Json, &opts),
+            r#"[{"column":"age","from":"30","to":"25"}]"#
+        );
+    }
+
+    #[test]
+    fn diff_without_headers_uses_indices() {
+        let t = table_without_headers();
+        assert_eq!(diff_rows(&t, &OutputOptions::default(), 0, 1)[1].0, "1");
+    }
+
+    // --- Whole tables ---
+
+    #[test]
+    fn table_csv_starts_with_the_header_record() {
+        let t = table_with_headers();
+        assert_eq!(
+            format_table(&t, OutputFormat::Csv, &OutputOptions::default()),
+            "name,age\nAlice,30\nBob,25"
+        );
+        let t = table_without_headers();
+        assert_eq!(
+            format_table(&t, OutputFormat::Csv, &OutputOptions::default()),
+            "Alice,30\nBob,25"
+        );
+    }
+
+    #[test]
+    fn table_plain_is_tab_separated() {
+        let t = table_with_headers();
+        assert_eq!(
+            format_table(&t, OutputFormat::Plain, &OutputOptions::default()),
+            "Alice\t30\nBob\t25"
+        );
+    }
+
+    // --- Header output ---
+
+    #[test]
+    fn header_plain() {
+        let t = table_with_headers();
+        assert_eq!(format_header(&t, OutputFormat::Plain, &OutputOptions::default()), "name,age");
+    }
+
+    #[test]
+    fn header_csv_quotes_when_needed() {
+        let t = Table {
+            headers: Some(vec!["name".to_string(), "city, state".to_string()]),
+            rows: vec![],
+        };
+        assert_eq!(
+            format_header(&t, OutputFormat::Csv, &OutputOptions::default()),
+            r#"name,"city, state""#
+        );
+    }
+
+    #[test]
+    fn header_json() {
+        let t = table_with_headers();
+        assert_eq!(
+            format_header(&t, OutputFormat::Json, &OutputOptions::default()),
+            r#"["name","age"]"#
+        );
+    }
+
+    #[test]
+    fn header_without_headers_is_empty() {
+        let t = table_without_headers();
+        assert_eq!(format_header(&t, OutputFormat::Plain, &OutputOptions::default()), "");
+        assert_eq!(format_header(&t, OutputFormat::Json, &OutputOptions::default()), "[]");
+    }
+
+    #[test]
+    fn column_list_with_headers() {
+        let t = table_with_headers();
+        assert_eq!(format_column_list(&t, OutputFormat::Plain), "name\nage");
+        assert_eq!(format_column_list(&t, OutputFormat::Json), r#"["name","age"]"#);
+    }
+
+    #[test]
+    fn column_list_without_headers_uses_indices() {
+        let t = table_without_headers();
+        assert_eq!(format_column_list(&t, OutputFormat::Plain), "0\n1");
+        assert_eq!(format_column_list(&t, OutputFormat::Json), r#"["0","1"]"#);
+    }
+
     // --- Column output ---
 
     #[test]
     fn column_plain_with_headers() {
         let t = table_with_headers();
-        assert_eq!(format_column(&t, OutputFormat::Plain, 0), "name");
-        assert_eq!(format_column(&t, OutputFormat::Plain, 1), "age");
+        assert_eq!(format_column(&t, OutputFormat::Plain, &OutputOptions::default(), 0), "name");
+        assert_eq!(format_column(&t, OutputFormat::Plain, &OutputOptions::default(), 1), "age");
     }
 
     #[test]
     fn column_plain_without_headers() {
         let t = table_without_headers();
-        assert_eq!(format_column(&t, OutputFormat::Plain, 0), "0");
-        assert_eq!(format_column(&t, OutputFormat::Plain, 1), "1");
+        assert_eq!(format_column(&t, OutputFormat::Plain, &OutputOptions::default(), 0), "0");
+        assert_eq!(format_column(&t, OutputFormat::Plain, &OutputOptions::default(), 1), "1");
     }
 
     #[test]
     fn column_json_with_headers() {
         let t = table_with_headers();
         assert_eq!(
-            format_column(&t, OutputFormat::Json, 0),
+            format_column(&t, OutputFormat::Json, &OutputOptions::default(), 0),
             r#"{"column":"name"}"#
         );
     }
@@ -199,25 +753,50 @@ mod tests {
     fn column_json_without_headers() {
         let t = table_without_headers();
         assert_eq!(
-            format_column(&t, OutputFormat::Json, 1),
+            format_column(&t, OutputFormat::Json, &OutputOptions::default(), 1),
             r#"{"column":"1"}"#
         );
     }
 
+    #[test]
+    fn column_values_for_selected_rows() {
+        let t = table_with_headers();
+        assert_eq!(
+            format_column_values(&t, OutputFormat::Plain, &OutputOptions::default(), 0, &[1, 0]),
+            "Bob\nAlice"
+        );
+        assert_eq!(
+            format_column_values(&t, OutputFormat::Json, &OutputOptions::default(), 1, &[0]),
+            r#"["30"]"#
+        );
+    }
+
+    #[test]
+    fn out_of_range_rows_and_cells_are_empty() {
+        let t = table_with_headers();
+        assert_eq!(format_row(&t, OutputFormat::Plain, &OutputOptions::default(), 9), "");
+        assert_eq!(
+            format_row(&t, OutputFormat::Json, &OutputOptions::default(), 9),
+            r#"{"name":"","age":""}"#
+        );
+        assert_eq!(format_cell(&t, OutputFormat::Plain, &OutputOptions::default(), 0, 9), "");
+        assert_eq!(format_cell(&t, OutputFormat::Plain, &OutputOptions::default(), 9, 0), "");
+    }
+
     // --- Cell output ---
 
     #[test]
     fn cell_plain() {
         let t = table_with_headers();
-        assert_eq!(format_cell(&t, OutputFormat::Plain, 0, 0), "Alice");
-        assert_eq!(format_cell(&t, OutputFormat::Plain, 1, 1), "25");
+        assert_eq!(format_cell(&t, OutputFormat::Plain, &OutputOptions::default(), 0, 0), "Alice");
+        assert_eq!(format_cell(&t, OutputFormat::Plain, &OutputOptions::default(), 1, 1), "25");
     }
 
     #[test]
     fn cell_json_with_headers() {
         let t = table_with_headers();
         assert_eq!(
-            format_cell(&t, OutputFormat::Json, 0, 0),
+            format_cell(&t, OutputFormat::Json, &OutputOptions::default(), 0, 0),
             r#"{"value":"Alice","row":0,"column":"name"}"#
         );
     }
@@ -226,15 +805,56 @@ mod tests {
     fn cell_json_without_headers() {
         let t = table_without_headers();
         assert_eq!(
-            format_cell(&t, OutputFormat::Json, 0, 1),
+            format_cell(&t, OutputFormat::Json, &OutputOptions::default(), 0, 1),
             r#"{"value":"30","row":0,"column":"1"}"#
         );
     }
 
+    #[test]
+    fn one_based_cell_and_column_indices_start_at_1() {
+        let t = table_without_headers();
+        let opts = OutputOptions {
+            one_based: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_cell(&t, OutputFormat::Json, &opts, 0, 0),
+            r#"{"value":"Alice","row":1,"column":"1"}"#
+        );
+        assert_eq!(format_column(&t, OutputFormat::Plain, &opts, 0), "1");
+        assert_eq!(format_cell(&t, OutputFormat::Env, &opts, 0, 1), "COL2='30'");
+    }
+
+    #[test]
+    fn cell_context_with_headers() {
+        let t = table_with_headers();
+        let opts = OutputOptions {
+            cell_context: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_cell(&t, OutputFormat::Plain, &opts, 1, 1),
+            r#"{"name":"Bob","age":"25","_selected":"age"}"#
+        );
+    }
+
+    #[test]
+    fn cell_context_without_headers() {
+        let t = table_without_headers();
+        let opts = OutputOptions {
+            cell_context: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_cell(&t, OutputFormat::Json, &opts, 0, 0),
+            r#"{"0":"Alice","1":"30","_selected":"0"}"#
+        );
+    }
+
     #[test]
     fn cell_csv() {
         let t = table_with_headers();
-        assert_eq!(format_cell(&t, OutputFormat::Csv, 0, 0), "Alice");
+        assert_eq!(format_cell(&t, OutputFormat::Csv, &OutputOptions::default(), 0, 0), "Alice");
     }
 
     // --- Edge cases ---
@@ -245,9 +865,9 @@ mod tests {
             headers: Some(vec!["item".to_string()]),
             rows: vec![vec!["apple".to_string()]],
         };
-        assert_eq!(format_row(&t, OutputFormat::Plain, 0), "apple");
+        assert_eq!(format_row(&t, OutputFormat::Plain, &OutputOptions::default(), 0), "apple");
         assert_eq!(
-            format_row(&t, OutputFormat::Json, 0),
+            format_row(&t, OutputFormat::Json, &OutputOptions::default(), 0),
             r#"{"item":"apple"}"#
         );
     }
@@ -258,6 +878,47 @@ mod tests {
             headers: Some(vec!["x".to_string()]),
             rows: vec![vec!["val".to_string()]],
         };
-        assert_eq!(format_column(&t, OutputFormat::Plain, 0), "x");
+        assert_eq!(format_column(&t, OutputFormat::Plain, &OutputOptions::default(), 0), "x");
+    }
+
+    #[test]
+    fn summary_of_one_and_several_values() {
+        let opts = OutputOptions::default();
+        let values = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(format_summary(&values(&["Alice"]), &opts), "Selected: Alice");
+        assert_eq!(
+            format_summary(&values(&["Alice", "Bob", "Carol"]), &opts),
+            "3 rows selected: Alice, Bob, Carol"
+        );
+        assert_eq!(format_summary(&[], &opts), "Nothing selected");
+    }
+
+    #[test]
+    fn summary_prefix_counts_values() {
+        let opts = OutputOptions {
+            summary_prefix: Some("Deleting {n} files".to_string()),
+            ..Default::default()
+        };
+        let values = vec!["a.txt".to_string(), "b.txt".to_string()];
+        assert_eq!(format_summary(&values, &opts), "Deleting 2 files: a.txt, b.txt");
+    }
+
+    #[test]
+    fn bom_prefixes_output_only_when_set() {
+        let t = table_with_headers();
+        let row = || format_row(&t, OutputFormat::Csv, &OutputOptions::default(), 0);
+
+        let plain = with_bom(row(), &OutputOptions::default());
+        assert_eq!(plain, "Alice,30");
+
+        let opts = OutputOptions {
+            bom: true,
+            ..Default::default()
+        };
+        let marked = with_bom(row(), &opts);
+        assert!(marked.starts_with('\u{FEFF}'));
+        assert_eq!(marked.as_bytes()[..3], [0xEF, 0xBB, 0xBF]);
+        assert_eq!(&marked[3..], "Alice,30");
     }
 }
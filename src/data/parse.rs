@@ -1,31 +1,322 @@
+use std::collections::HashMap;
+use std::fs;
 use std::io::{self, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 use anyhow::{anyhow, Result};
+use encoding_rs::Encoding;
+use tracing::warn;
 
-use super::{InputFormat, Table};
+use super::{InputFormat, InputSource, ParseOptions, Table};
+
+/// Read and parse the table from wherever `source` points.
+pub fn parse_source(
+    source: &InputSource,
+    format: InputFormat,
+    has_header: bool,
+    opts: &ParseOptions,
+) -> Result<Table> {
+    match source {
+        InputSource::Stdin => parse_stdin(format, has_header, opts),
+        InputSource::Files { paths, union } => parse_files(paths, format, has_header, *union, opts),
+        InputSource::Command(command) => parse_command(command, format, has_header, opts),
+        InputSource::Null => Ok(Table::new(None, Vec::new())),
+    }
+}
+
+/// Read and parse the table like [`parse_source`], also returning the
+/// decoded input when it is a single stream (stdin, a command or one file)
+/// so it can be parsed again in another format.
+pub fn parse_source_keeping_input(
+    source: &InputSource,
+    format: InputFormat,
+    has_header: bool,
+    opts: &ParseOptions,
+) -> Result<(Table, Option<String>)> {
+    let parse = |input: String| {
+        let table = parse_string(&input, format, has_header, opts)?;
+        Ok((table, Some(input)))
+    };
+    match source {
+        InputSource::Stdin => parse(read_stdin(opts)?),
+        InputSource::Command(command) => parse(run_command(command, opts)?),
+        InputSource::Files { paths, .. } if paths.len() == 1 => {
+            let path = &paths[0];
+            read_file(path, opts)
+                .and_then(parse)
+                .map_err(|err| anyhow!("{}: {err}", path.display()))
+        }
+        _ => Ok((parse_source(source, format, has_header, opts)?, None)),
+    }
+}
 
 /// Read from stdin and parse into a Table.
-pub fn parse_stdin(format: InputFormat, has_header: bool) -> Result<Table> {
+pub fn parse_stdin(format: InputFormat, has_header: bool, opts: &ParseOptions) -> Result<Table> {
+    parse_string(&read_stdin(opts)?, format, has_header, opts)
+}
+
+/// All of stdin, decoded. A terminal on stdin is an error.
+fn read_stdin(opts: &ParseOptions) -> Result<String> {
     if io::stdin().is_terminal() {
         return Err(anyhow!("no input provided; pipe data into tabsel or redirect from a file"));
     }
-    let mut input = String::new();
-    io::stdin().read_to_string(&mut input)?;
-    parse_string(&input, format, has_header)
+    let mut input = Vec::new();
+    io::stdin().read_to_end(&mut input)?;
+    decode(input, opts.encoding).map_err(|err| anyhow!("stdin: {err}"))
+}
+
+/// Run `command` through `sh -c` and parse its standard output. A failing
+/// command is an error carrying its stderr.
+pub fn parse_command(
+    command: &str,
+    format: InputFormat,
+    has_header: bool,
+    opts: &ParseOptions,
+) -> Result<Table> {
+    parse_string(&run_command(command, opts)?, format, has_header, opts)
+}
+
+/// The decoded standard output of `command`.
+fn run_command(command: &str, opts: &ParseOptions) -> Result<String> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .output()
+        .map_err(|err| anyhow!("failed to run `{command}`: {err}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!(
+            "`{command}` exited with {}: {}",
+            output.status,
+            stderr.trim()
+        ));
+    }
+
+    decode(output.stdout, opts.encoding).map_err(|err| anyhow!("`{command}` output: {err}"))
+}
+
+/// A file's contents, decoded.
+fn read_file(path: &Path, opts: &ParseOptions) -> Result<String> {
+    let bytes = fs::read(path)?;
+    decode(bytes, opts.encoding)
+}
+
+/// Read and parse each file, then combine them into one Table. Files must
+/// share the same headers unless `union` is set, in which case they are
+/// merged with [`union_tables`].
+pub fn parse_files(
+    paths: &[PathBuf],
+    format: InputFormat,
+    has_header: bool,
+    union: bool,
+    opts: &ParseOptions,
+) -> Result<Table> {
+    let tables = paths
+        .iter()
+        .map(|path| {
+            let input =
+                read_file(path, opts).map_err(|err| anyhow!("{}: {err}", path.display()))?;
+            parse_string(&input, format, has_header, opts)
+                .map_err(|err| anyhow!("{}: {err}", path.display()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if union {
+        Ok(union_tables(tables))
+    } else {
+        concat_tables(tables)
+    }
+}
+
+/// Transcode raw input in `encoding` to UTF-8. UTF-8 input must be valid;
+/// in other encodings, bytes that can't be decoded are replaced with U+FFFD
+/// and a warning is logged.
+pub fn decode(bytes: Vec<u8>, encoding: &'static Encoding) -> Result<String> {
+    if encoding == encoding_rs::UTF_8 {
+        return String::from_utf8(bytes).map_err(|_| {
+            anyhow!("input is not valid UTF-8; pass --encoding to read another encoding")
+        });
+    }
+
+    let (text, had_errors) = encoding.decode_without_bom_handling(&bytes);
+    if had_errors {
+        warn!("Input has bytes that are not valid {}; replaced them", encoding.name());
+    }
+    Ok(text.into_owned())
+}
+
+/// Look up an encoding by a label such as `latin1`, `windows-1252` or
+/// `shift_jis`.
+pub fn parse_encoding(label: &str) -> Result<&'static Encoding> {
+    Encoding::for_label(label.trim().as_bytes())
+        .ok_or_else(|| anyhow!("unknown encoding `{label}`"))
+}
+
+/// Append the rows of tables that all have the same headers.
+fn concat_tables(tables: Vec<Table>) -> Result<Table> {
+    let mut tables = tables.into_iter();
+    let mut merged = tables.next().unwrap_or_else(|| Table::new(None, Vec::new()));
+
+    for table in tables {
+        if table.headers != merged.headers {
+            return Err(anyhow!(
+                "input files have different headers; use --union to merge them"
+            ));
+        }
+        merged.rows.extend(table.rows);
+    }
+
+    Ok(merged)
+}
+
+/// Merge tables by the union of their headers, in order of first appearance.
+/// Cells are moved under their header and missing ones are left empty. Rows
+/// of headerless tables are placed positionally; if no table has headers the
+/// rows are simply concatenated.
+pub fn union_tables(tables: Vec<Table>) -> Table {
+    let headers = union_headers(tables.iter().flat_map(|t| t.headers.iter().flatten()));
+    if headers.is_empty() {
+        return Table::new(None, tables.into_iter().flat_map(|t| t.rows).collect());
+    }
+
+    let mut rows = Vec::new();
+    for table in tables {
+        let Some(table_headers) = table.headers else {
+            rows.extend(table.rows);
+            continue;
+        };
+        let positions: Vec<Option<usize>> = headers
+            .iter()
+            .map(|h| table_headers.iter().position(|th| th == h))
+            .collect();
+        for row in table.rows {
+            rows.push(
+                positions
+                    .iter()
+                    .map(|pos| pos.and_then(|p| row.get(p).cloned()).unwrap_or_default())
+                    .collect(),
+            );
+        }
+    }
+
+    Table::new(Some(headers), rows)
+}
+
+/// Collect unique header names in order of first appearance.
+fn union_headers<'a>(names: impl IntoIterator<Item = &'a String>) -> Vec<String> {
+    let mut headers: Vec<String> = Vec::new();
+    for name in names {
+        if !headers.contains(name) {
+            headers.push(name.clone());
+        }
+    }
+    headers
 }
 
 /// Parse a string into a Table (testable core).
-pub fn parse_string(input: &str, format: InputFormat, has_header: bool) -> Result<Table> {
+pub fn parse_string(
+    input: &str,
+    format: InputFormat,
+    has_header: bool,
+    opts: &ParseOptions,
+) -> Result<Table> {
+    if opts.allow_empty && input.trim().is_empty() {
+        return Ok(Table::new(None, Vec::new()));
+    }
+
     match format {
-        InputFormat::Csv => parse_csv(input, has_header),
-        InputFormat::Json => parse_json(input),
+        InputFormat::Csv => parse_csv(input, has_header, opts),
+        InputFormat::Tsv => parse_tsv(input, has_header, opts),
+        InputFormat::Json => parse_json(input, opts.json_path.as_deref(), opts.flatten_json),
+        InputFormat::Ndjson => parse_ndjson(input, opts.flatten_json),
+        InputFormat::Delimited => Ok(parse_delimited(input, has_header, &opts.separator)),
+        InputFormat::AnyDelimiter => Ok(parse_any_delimited(input, has_header, opts)),
     }
 }
 
-fn parse_csv(input: &str, has_header: bool) -> Result<Table> {
-    let mut reader = csv::ReaderBuilder::new()
+/// Split each non-empty line on the exact separator string. Consecutive
+/// separators give empty fields rather than being collapsed.
+fn parse_delimited(input: &str, has_header: bool, separator: &str) -> Table {
+    split_lines(input, has_header, |line| line.split(separator).collect())
+}
+
+/// Split each non-empty line on any of the delimiter characters. Runs of
+/// delimiters give empty fields unless `collapse_delimiters` is set.
+fn parse_any_delimited(input: &str, has_header: bool, opts: &ParseOptions) -> Table {
+    let delimiters = opts.delimiters.as_slice();
+    split_lines(input, has_header, |line| {
+        line.split(delimiters)
+            .filter(|field| !opts.collapse_delimiters || !field.is_empty())
+            .collect()
+    })
+}
+
+/// Build a table from the non-empty lines of `input`, each split into
+/// fields by `split`; with `has_header` the first line is the header row.
+fn split_lines<'a>(
+    input: &'a str,
+    has_header: bool,
+    split: impl Fn(&'a str) -> Vec<&'a str>,
+) -> Table {
+    let mut records = input.lines().filter(|line| !line.is_empty()).map(split);
+
+    let headers = if has_header {
+        records
+            .next()
+            .map(|fields| fields.into_iter().map(str::to_string).collect())
+    } else {
+        None
+    };
+
+    Table::from_records(headers, records)
+}
+
+/// Read an `--any-delimiter` set: each character is a delimiter, with `\t`
+/// for a tab and `\\` for a backslash.
+pub fn parse_delimiter_set(spec: &str) -> Result<Vec<char>> {
+    let mut delimiters = Vec::new();
+    let mut chars = spec.chars();
+    while let Some(c) = chars.next() {
+        delimiters.push(match c {
+            '\\' => match chars.next() {
+                Some('t') => '\t',
+                Some('\\') => '\\',
+                Some(other) => return Err(anyhow!("unknown escape \\{other}")),
+                None => return Err(anyhow!("trailing backslash")),
+            },
+            c => c,
+        });
+    }
+    if delimiters.is_empty() {
+        return Err(anyhow!("no delimiters given"));
+    }
+    Ok(delimiters)
+}
+
+fn parse_csv(input: &str, has_header: bool, opts: &ParseOptions) -> Result<Table> {
+    read_records(csv::ReaderBuilder::new().delimiter(b','), input, has_header, opts)
+}
+
+/// Tab-separated input, read like CSV: quoted fields may hold tabs and
+/// newlines, and ragged rows are kept.
+fn parse_tsv(input: &str, has_header: bool, opts: &ParseOptions) -> Result<Table> {
+    read_records(csv::ReaderBuilder::new().delimiter(b'\t'), input, has_header, opts)
+}
+
+/// Read a table with the csv crate, `builder` setting the delimiter.
+fn read_records(
+    builder: &mut csv::ReaderBuilder,
+    input: &str,
+    has_header: bool,
+    opts: &ParseOptions,
+) -> Result<Table> {
+    let mut reader = builder
         .has_headers(has_header)
         .flexible(true)
+        .quote(opts.quote)
+        .quoting(opts.quoting)
         .from_reader(input.as_bytes());
 
     let headers = if has_header {
@@ -39,73 +330,188 @@ fn parse_csv(input: &str, has_header: bool) -> Result<Table> {
         None
     };
 
-    let mut rows = Vec::new();
-    for result in reader.records() {
-        let record = result?;
-        rows.push(record.iter().map(|field| field.to_string()).collect());
-    }
+    let records = reader.records().collect::<Result<Vec<_>, _>>()?;
 
-    Ok(Table { headers, rows })
+    Ok(Table::from_records(headers, records.iter()))
 }
 
-fn parse_json(input: &str) -> Result<Table> {
-    let value: serde_json::Value = serde_json::from_str(input)?;
+fn parse_json(input: &str, path: Option<&str>, flatten: bool) -> Result<Table> {
+    let mut value: serde_json::Value = serde_json::from_str(input)?;
+    if let Some(path) = path {
+        value = select_json_path(value, path)?;
+    }
 
     match value {
         serde_json::Value::Array(arr) => {
             if arr.is_empty() {
-                return Ok(Table {
-                    headers: None,
-                    rows: Vec::new(),
-                });
+                return Ok(Table::new(None, Vec::new()));
             }
 
             // Check if first element is an object (array of objects) or array (array of arrays)
             match &arr[0] {
-                serde_json::Value::Object(_) => parse_json_objects(&arr),
+                serde_json::Value::Object(_) => parse_json_objects(&arr, flatten),
                 serde_json::Value::Array(_) => parse_json_arrays(&arr),
                 _ => Err(anyhow!(
                     "JSON input must be an array of objects or an array of arrays"
                 )),
             }
         }
-        _ => Err(anyhow!("JSON input must be a top-level array")),
+        serde_json::Value::Object(map)
+            if !map.is_empty() && map.values().all(serde_json::Value::is_array) =>
+        {
+            parse_json_columns(map)
+        }
+        _ => Err(anyhow!(
+            "JSON input must be a top-level array, or an object of column arrays"
+        )),
     }
 }
 
-fn parse_json_objects(arr: &[serde_json::Value]) -> Result<Table> {
-    // Collect all unique keys in order of first appearance
-    let mut headers: Vec<String> = Vec::new();
-    for item in arr {
-        if let serde_json::Value::Object(map) = item {
-            for key in map.keys() {
-                if !headers.contains(key) {
-                    headers.push(key.clone());
+/// A columnar JSON object, `{"name":["Alice","Bob"],"age":[30,25]}`: the
+/// keys are the headers and each row takes the next value of every array.
+fn parse_json_columns(map: serde_json::Map<String, serde_json::Value>) -> Result<Table> {
+    let (headers, columns): (Vec<String>, Vec<Vec<serde_json::Value>>) = map
+        .into_iter()
+        .filter_map(|(key, value)| match value {
+            serde_json::Value::Array(values) => Some((key, values)),
+            _ => None,
+        })
+        .unzip();
+
+    let len = columns[0].len();
+    if let Some(idx) = columns.iter().position(|values| values.len() != len) {
+        return Err(anyhow!(
+            "JSON column arrays differ in length: '{}' has {len} values, '{}' has {}",
+            headers[0],
+            headers[idx],
+            columns[idx].len()
+        ));
+    }
+
+    let rows = (0..len)
+        .map(|row| columns.iter().map(|values| stringify_json_value(&values[row])).collect())
+        .collect();
+    Ok(Table::new(Some(headers), rows))
+}
+
+/// Walk a dotted `--json-path` into `value`. Each step is an object key, or
+/// an index when the value at that point is an array. The path must end on
+/// an array.
+fn select_json_path(mut value: serde_json::Value, path: &str) -> Result<serde_json::Value> {
+    let mut walked = Vec::new();
+    for key in path.split('.') {
+        let at = if walked.is_empty() {
+            "the top level".to_string()
+        } else {
+            format!("'{}'", walked.join("."))
+        };
+        value = match value {
+            serde_json::Value::Object(mut map) => map
+                .remove(key)
+                .ok_or_else(|| anyhow!("--json-path: no key '{key}' at {at}"))?,
+            serde_json::Value::Array(mut arr) => {
+                let idx: usize = key
+                    .parse()
+                    .map_err(|_| anyhow!("--json-path: {at} is an array, '{key}' is no index"))?;
+                if idx >= arr.len() {
+                    return Err(anyhow!(
+                        "--json-path: index {idx} is out of range at {at} ({} items)",
+                        arr.len()
+                    ));
                 }
+                arr.swap_remove(idx)
             }
-        } else {
-            return Err(anyhow!("Expected all elements to be objects"));
-        }
+            _ => return Err(anyhow!("--json-path: {at} has no key '{key}'")),
+        };
+        walked.push(key);
+    }
+    if !value.is_array() {
+        return Err(anyhow!("--json-path: '{path}' is not an array"));
     }
+    Ok(value)
+}
 
-    let mut rows = Vec::new();
-    for item in arr {
-        if let serde_json::Value::Object(map) = item {
-            let row: Vec<String> = headers
+/// One JSON object per non-empty line, headed by the union of their keys
+/// like an array of objects. Errors name the 1-based line at fault.
+fn parse_ndjson(input: &str, flatten: bool) -> Result<Table> {
+    let objects = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            let value: serde_json::Value = serde_json::from_str(line)
+                .map_err(|err| anyhow!("line {}: {err}", idx + 1))?;
+            if !value.is_object() {
+                return Err(anyhow!("line {}: expected a JSON object", idx + 1));
+            }
+            Ok(value)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if objects.is_empty() {
+        return Ok(Table::new(None, Vec::new()));
+    }
+    parse_json_objects(&objects, flatten)
+}
+
+/// An array of JSON objects, headed by the union of their keys. With
+/// `flatten`, nested objects are expanded into one column per leaf.
+fn parse_json_objects(arr: &[serde_json::Value], flatten: bool) -> Result<Table> {
+    if !arr.iter().all(serde_json::Value::is_object) {
+        return Err(anyhow!("Expected all elements to be objects"));
+    }
+
+    let objects: Vec<Vec<(String, &serde_json::Value)>> = arr
+        .iter()
+        .filter_map(serde_json::Value::as_object)
+        .map(|map| {
+            let mut fields = Vec::new();
+            object_fields(map, "", flatten, &mut fields);
+            fields
+        })
+        .collect();
+
+    // Collect all unique keys in order of first appearance
+    let headers = union_headers(objects.iter().flatten().map(|(name, _)| name));
+
+    let rows = objects
+        .iter()
+        .map(|fields| {
+            let values: HashMap<&str, &serde_json::Value> =
+                fields.iter().map(|(name, value)| (name.as_str(), *value)).collect();
+            headers
                 .iter()
-                .map(|key| match map.get(key) {
-                    Some(v) => stringify_json_value(v),
-                    None => String::new(),
+                .map(|key| {
+                    values
+                        .get(key.as_str())
+                        .map_or_else(String::new, |v| stringify_json_value(v))
                 })
-                .collect();
-            rows.push(row);
+                .collect()
+        })
+        .collect();
+
+    Ok(Table::new(Some(headers), rows))
+}
+
+/// The fields of a JSON object as (column, value) pairs, named `prefix` and
+/// the key. With `flatten`, nested objects are walked instead, so their
+/// leaves get dotted names like `addr.city`; arrays and empty objects stay
+/// whole.
+fn object_fields<'v>(
+    map: &'v serde_json::Map<String, serde_json::Value>,
+    prefix: &str,
+    flatten: bool,
+    fields: &mut Vec<(String, &'v serde_json::Value)>,
+) {
+    for (key, value) in map {
+        let name = format!("{prefix}{key}");
+        match value {
+            serde_json::Value::Object(inner) if flatten && !inner.is_empty() => {
+                object_fields(inner, &format!("{name}."), flatten, fields)
+            }
+            _ => fields.push((name, value)),
         }
     }
-
-    Ok(Table {
-        headers: Some(headers),
-        rows,
-    })
 }
 
 fn parse_json_arrays(arr: &[serde_json::Value]) -> Result<Table> {
@@ -119,10 +525,34 @@ fn parse_json_arrays(arr: &[serde_json::Value]) -> Result<Table> {
         }
     }
 
-    Ok(Table {
-        headers: None,
-        rows,
-    })
+    Ok(Table::new(None, rows))
+}
+
+/// Open a cell holding a nested JSON object or array as a table of its own:
+/// an object becomes `key`/`value` rows, an array of objects or arrays is
+/// read like top-level JSON input and any other array is one value per row.
+/// `None` if the cell is not a JSON object or array.
+pub fn parse_nested(cell: &str) -> Option<Table> {
+    match serde_json::from_str(cell.trim()).ok()? {
+        serde_json::Value::Object(map) => Some(Table::new(
+            Some(vec!["key".to_string(), "value".to_string()]),
+            map.iter()
+                .map(|(key, value)| vec![key.clone(), stringify_json_value(value)])
+                .collect(),
+        )),
+        serde_json::Value::Array(arr) => {
+            let table = match arr.first() {
+                Some(serde_json::Value::Object(_)) => parse_json_objects(&arr, false),
+                Some(serde_json::Value::Array(_)) => parse_json_arrays(&arr),
+                _ => Ok(Table::new(
+                    None,
+                    arr.iter().map(|value| vec![stringify_json_value(value)]).collect(),
+                )),
+            };
+            table.ok()
+        }
+        _ => None,
+    }
 }
 
 fn stringify_json_value(v: &serde_json::Value) -> String {
@@ -139,12 +569,28 @@ mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
 
+    // --- Encoding tests ---
+
+    #[test]
+    fn windows_1252_is_transcoded() {
+        let encoding = parse_encoding("windows-1252").unwrap();
+        let bytes = b"name,price\ncaf\xe9,\x805\n".to_vec();
+        assert_eq!(decode(bytes, encoding).unwrap(), "name,price\ncafé,€5\n");
+    }
+
+    #[test]
+    fn invalid_utf8_is_an_error_by_default() {
+        assert!(decode(b"caf\xe9".to_vec(), encoding_rs::UTF_8).is_err());
+        assert_eq!(decode("café".as_bytes().to_vec(), encoding_rs::UTF_8).unwrap(), "café");
+        assert!(parse_encoding("latin-9000").is_err());
+    }
+
     // --- CSV tests ---
 
     #[test]
     fn csv_with_header() {
         let input = "name,age\nAlice,30\nBob,25";
-        let table = parse_string(input, InputFormat::Csv, true).unwrap();
+        let table = parse_string(input, InputFormat::Csv, true, &ParseOptions::default()).unwrap();
 
         assert_eq!(
             table.headers,
@@ -158,7 +604,7 @@ mod tests {
     #[test]
     fn csv_without_header() {
         let input = "Alice,30\nBob,25";
-        let table = parse_string(input, InputFormat::Csv, false).unwrap();
+        let table = parse_string(input, InputFormat::Csv, false, &ParseOptions::default()).unwrap();
 
         assert_eq!(table.headers, None);
         assert_eq!(table.rows.len(), 2);
@@ -169,16 +615,26 @@ mod tests {
     #[test]
     fn csv_empty() {
         let input = "";
-        let table = parse_string(input, InputFormat::Csv, true).unwrap();
+        let table = parse_string(input, InputFormat::Csv, true, &ParseOptions::default()).unwrap();
 
         assert_eq!(table.headers, None);
         assert_eq!(table.rows.len(), 0);
     }
 
+    #[test]
+    fn csv_header_only() {
+        let input = "name,age\n";
+        let table = parse_string(input, InputFormat::Csv, true, &ParseOptions::default()).unwrap();
+
+        assert_eq!(table.headers, Some(vec!["name".to_string(), "age".to_string()]));
+        assert!(table.is_empty());
+        assert_eq!(table.width(), 2);
+    }
+
     #[test]
     fn csv_single_column() {
         let input = "item\napple\nbanana\ncherry";
-        let table = parse_string(input, InputFormat::Csv, true).unwrap();
+        let table = parse_string(input, InputFormat::Csv, true, &ParseOptions::default()).unwrap();
 
         assert_eq!(table.headers, Some(vec!["item".to_string()]));
         assert_eq!(table.rows.len(), 3);
@@ -190,7 +646,7 @@ mod tests {
     #[test]
     fn csv_quoted_fields_with_commas_and_newlines() {
         let input = "name,bio\nAlice,\"likes cats, dogs\"\nBob,\"line1\nline2\"";
-        let table = parse_string(input, InputFormat::Csv, true).unwrap();
+        let table = parse_string(input, InputFormat::Csv, true, &ParseOptions::default()).unwrap();
 
         assert_eq!(
             table.headers,
@@ -201,11 +657,36 @@ mod tests {
         assert_eq!(table.rows[1], vec!["Bob", "line1\nline2"]);
     }
 
+    #[test]
+    fn csv_custom_quote_character() {
+        let input = "name,bio\n'Alice','likes cats, dogs'\nBob,'say \"hi\"'";
+        let opts = ParseOptions {
+            quote: b'\'',
+            ..Default::default()
+        };
+        let table = parse_string(input, InputFormat::Csv, true, &opts).unwrap();
+
+        assert_eq!(table.rows[0], vec!["Alice", "likes cats, dogs"]);
+        assert_eq!(table.rows[1], vec!["Bob", "say \"hi\""]);
+    }
+
+    #[test]
+    fn csv_without_quoting_keeps_quotes_literal() {
+        let input = "name,height\n\"Alice\",5'6\"";
+        let opts = ParseOptions {
+            quoting: false,
+            ..Default::default()
+        };
+        let table = parse_string(input, InputFormat::Csv, true, &opts).unwrap();
+
+        assert_eq!(table.rows[0], vec!["\"Alice\"", "5'6\""]);
+    }
+
     #[test]
     fn csv_ragged_rows() {
         // csv crate pads short rows and allows long rows by default
         let input = "a,b,c\n1,2\n3,4,5,6";
-        let table = parse_string(input, InputFormat::Csv, true).unwrap();
+        let table = parse_string(input, InputFormat::Csv, true, &ParseOptions::default()).unwrap();
 
         assert_eq!(
             table.headers,
@@ -217,12 +698,132 @@ mod tests {
         assert_eq!(table.rows[1].len(), 4);
     }
 
+    #[test]
+    fn blank_input_is_an_empty_table_with_allow_empty() {
+        let opts = ParseOptions {
+            allow_empty: true,
+            ..Default::default()
+        };
+        for format in [InputFormat::Csv, InputFormat::Json] {
+            let table = parse_string(" \n", format, true, &opts).unwrap();
+            assert_eq!(table.headers, None);
+            assert!(table.is_empty());
+        }
+
+        assert!(parse_string("", InputFormat::Json, true, &ParseOptions::default()).is_err());
+    }
+
+    // --- TSV tests ---
+
+    #[test]
+    fn tsv_with_header() {
+        let input = "name\tbio\nAlice\t\"likes \"\"tabs\"\"\tand more\"\nBob\t25, or so";
+        let table = parse_string(input, InputFormat::Tsv, true, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            table.headers,
+            Some(vec!["name".to_string(), "bio".to_string()])
+        );
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0], vec!["Alice", "likes \"tabs\"\tand more"]);
+        // Commas are plain text in TSV
+        assert_eq!(table.rows[1], vec!["Bob", "25, or so"]);
+    }
+
+    #[test]
+    fn tsv_ragged_rows() {
+        let input = "a\tb\tc\n1\t2\n3\t4\t5\t6";
+        let table = parse_string(input, InputFormat::Tsv, false, &ParseOptions::default()).unwrap();
+
+        assert_eq!(table.headers, None);
+        assert_eq!(table.rows[0].len(), 3);
+        assert_eq!(table.rows[1], vec!["1", "2"]);
+        assert_eq!(table.rows[2].len(), 4);
+    }
+
+    // --- Delimited tests ---
+
+    fn delimited(separator: &str) -> ParseOptions {
+        ParseOptions {
+            separator: separator.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn delimited_multi_char_separator() {
+        let input = "x::y::z\na::b::c\n";
+        let table = parse_string(input, InputFormat::Delimited, true, &delimited("::")).unwrap();
+
+        assert_eq!(
+            table.headers,
+            Some(vec!["x".to_string(), "y".to_string(), "z".to_string()])
+        );
+        assert_eq!(table.rows, vec![vec!["a", "b", "c"]]);
+    }
+
+    #[test]
+    fn delimited_consecutive_separators_give_empty_fields() {
+        let input = "a::::c\n::b::";
+        let table = parse_string(input, InputFormat::Delimited, false, &delimited("::")).unwrap();
+
+        assert_eq!(table.headers, None);
+        assert_eq!(table.rows[0], vec!["a", "", "c"]);
+        assert_eq!(table.rows[1], vec!["", "b", ""]);
+    }
+
+    fn any_delimiter(delimiters: &str, collapse: bool) -> ParseOptions {
+        ParseOptions {
+            delimiters: parse_delimiter_set(delimiters).unwrap(),
+            collapse_delimiters: collapse,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn any_delimiter_splits_mixed_lines() {
+        let input = "name;age\tcity\nAlice,30;Paris\nBob\t25\tRome\n";
+        let opts = any_delimiter(",;\\t", false);
+        let table = parse_string(input, InputFormat::AnyDelimiter, true, &opts).unwrap();
+
+        assert_eq!(
+            table.headers,
+            Some(vec!["name".to_string(), "age".to_string(), "city".to_string()])
+        );
+        assert_eq!(table.rows, vec![vec!["Alice", "30", "Paris"], vec!["Bob", "25", "Rome"]]);
+    }
+
+    #[test]
+    fn any_delimiter_can_collapse_runs() {
+        let input = ",a;;b , c\n";
+        let kept =
+            parse_string(input, InputFormat::AnyDelimiter, false, &any_delimiter(",; ", false));
+        assert_eq!(kept.unwrap().rows[0], vec!["", "a", "", "b", "", "", "c"]);
+
+        let collapsed =
+            parse_string(input, InputFormat::AnyDelimiter, false, &any_delimiter(",; ", true));
+        assert_eq!(collapsed.unwrap().rows[0], vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn delimiter_set_escapes() {
+        assert_eq!(parse_delimiter_set("|\\t\\\\").unwrap(), vec!['|', '\t', '\\']);
+        assert!(parse_delimiter_set("").is_err());
+        assert!(parse_delimiter_set("\\n").is_err());
+    }
+
     // --- JSON tests ---
 
     #[test]
     fn json_array_of_objects() {
         let input = r#"[{"name":"Alice","age":30},{"name":"Bob","age":25}]"#;
-        let table = parse_string(input, InputFormat::Json, false).unwrap();
+        let table = parse_string(
+            input,
+            InputFormat::Json,
+            false,
+            &ParseOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(
             table.headers,
@@ -236,7 +837,13 @@ mod tests {
     #[test]
     fn json_array_of_arrays() {
         let input = r#"[["Alice",30],["Bob",25]]"#;
-        let table = parse_string(input, InputFormat::Json, false).unwrap();
+        let table = parse_string(
+            input,
+            InputFormat::Json,
+            false,
+            &ParseOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(table.headers, None);
         assert_eq!(table.rows.len(), 2);
@@ -247,7 +854,13 @@ mod tests {
     #[test]
     fn json_empty_array() {
         let input = "[]";
-        let table = parse_string(input, InputFormat::Json, false).unwrap();
+        let table = parse_string(
+            input,
+            InputFormat::Json,
+            false,
+            &ParseOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(table.headers, None);
         assert_eq!(table.rows.len(), 0);
@@ -256,7 +869,13 @@ mod tests {
     #[test]
     fn json_nested_values_stringified() {
         let input = r#"[{"name":"Alice","meta":{"x":1}},{"name":"Bob","meta":[1,2]}]"#;
-        let table = parse_string(input, InputFormat::Json, false).unwrap();
+        let table = parse_string(
+            input,
+            InputFormat::Json,
+            false,
+            &ParseOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(
             table.headers,
@@ -266,24 +885,155 @@ mod tests {
         assert_eq!(table.rows[1], vec!["Bob", "[1,2]"]);
     }
 
+    #[test]
+    fn flatten_json_names_nested_leaves_by_path() {
+        let input = r#"[
+            {"name":"Alice","addr":{"city":"NYC","geo":{"lat":40}}},
+            {"name":"Bob","addr":{"zip":"10001"},"tags":["a","b"],"meta":{}}
+        ]"#;
+        let opts = ParseOptions {
+            flatten_json: true,
+            ..Default::default()
+        };
+        let table = parse_string(input, InputFormat::Json, true, &opts).unwrap();
+
+        let headers = ["name", "addr.city", "addr.geo.lat", "addr.zip", "tags", "meta"];
+        assert_eq!(table.headers, Some(headers.iter().map(|h| h.to_string()).collect()));
+        assert_eq!(table.rows[0], vec!["Alice", "NYC", "40", "", "", ""]);
+        // Arrays and empty objects keep their JSON text
+        assert_eq!(table.rows[1], vec!["Bob", "", "", "10001", r#"["a","b"]"#, "{}"]);
+    }
+
+    #[test]
+    fn flatten_json_applies_to_ndjson_lines() {
+        let input = "{\"id\":1,\"user\":{\"name\":\"Alice\"}}\n{\"id\":2}\n";
+        let opts = ParseOptions {
+            flatten_json: true,
+            ..Default::default()
+        };
+        let table = parse_string(input, InputFormat::Ndjson, true, &opts).unwrap();
+
+        assert_eq!(table.headers, Some(vec!["id".to_string(), "user.name".to_string()]));
+        assert_eq!(table.rows, vec![vec!["1", "Alice"], vec!["2", ""]]);
+    }
+
     #[test]
     fn json_invalid_input() {
         let input = "not valid json";
-        let result = parse_string(input, InputFormat::Json, false);
+        let result = parse_string(input, InputFormat::Json, false, &ParseOptions::default());
         assert!(result.is_err());
     }
 
     #[test]
     fn json_not_array() {
         let input = r#"{"key":"value"}"#;
-        let result = parse_string(input, InputFormat::Json, false);
+        let result = parse_string(input, InputFormat::Json, false, &ParseOptions::default());
         assert!(result.is_err());
     }
 
+    #[test]
+    fn ndjson_lines_share_a_header_union() {
+        let input = "{\"name\":\"Alice\",\"age\":30}\n{\"name\":\"Bob\"}\n{\"city\":\"Rome\",\"name\":\"Carol\"}";
+        let table = parse_string(input, InputFormat::Ndjson, true, &ParseOptions::default()).unwrap();
+
+        assert_eq!(
+            table.headers,
+            Some(vec!["name".to_string(), "age".to_string(), "city".to_string()])
+        );
+        assert_eq!(table.rows[0], vec!["Alice", "30", ""]);
+        assert_eq!(table.rows[1], vec!["Bob", "", ""]);
+        assert_eq!(table.rows[2], vec!["Carol", "", "Rome"]);
+    }
+
+    #[test]
+    fn ndjson_skips_blank_lines_and_names_the_bad_one() {
+        let input = "{\"name\":\"Alice\"}\n\n{\"name\":\"Bob\"}\n";
+        let table = parse_string(input, InputFormat::Ndjson, true, &ParseOptions::default()).unwrap();
+        assert_eq!(table.rows, vec![vec!["Alice"], vec!["Bob"]]);
+
+        let input = "{\"name\":\"Alice\"}\n{\"name\":\n";
+        let err = parse_string(input, InputFormat::Ndjson, true, &ParseOptions::default())
+            .unwrap_err();
+        assert!(err.to_string().starts_with("line 2:"), "{}", err);
+
+        let err = parse_string("[1]", InputFormat::Ndjson, true, &ParseOptions::default())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "line 1: expected a JSON object");
+    }
+
+    #[test]
+    fn json_columns_read_like_an_array_of_objects() {
+        let columnar = r#"{"name":["Alice","Bob"],"age":[30,null]}"#;
+        let objects = r#"[{"name":"Alice","age":30},{"name":"Bob","age":null}]"#;
+        let opts = ParseOptions::default();
+        let by_column = parse_string(columnar, InputFormat::Json, false, &opts).unwrap();
+        let by_row = parse_string(objects, InputFormat::Json, false, &opts).unwrap();
+
+        assert_eq!(by_column.headers, by_row.headers);
+        assert_eq!(by_column.rows, by_row.rows);
+        assert_eq!(by_column.rows[1], vec!["Bob", ""]);
+    }
+
+    #[test]
+    fn json_columns_must_have_equal_lengths() {
+        let input = r#"{"name":["Alice","Bob"],"age":[30]}"#;
+        let err = parse_string(input, InputFormat::Json, false, &ParseOptions::default())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "JSON column arrays differ in length: 'name' has 2 values, 'age' has 1"
+        );
+    }
+
+    fn json_path_options(path: &str) -> ParseOptions {
+        ParseOptions {
+            json_path: Some(path.to_string()),
+            ..ParseOptions::default()
+        }
+    }
+
+    #[test]
+    fn json_path_one_level() {
+        let input = r#"{"count":2,"results":[{"name":"Alice"},{"name":"Bob"}]}"#;
+        let table =
+            parse_string(input, InputFormat::Json, false, &json_path_options("results")).unwrap();
+
+        assert_eq!(table.headers, Some(vec!["name".to_string()]));
+        assert_eq!(table.rows, vec![vec!["Alice"], vec!["Bob"]]);
+    }
+
+    #[test]
+    fn json_path_through_objects_and_indices() {
+        let input = r#"{"data":[{"items":[["a",1]]},{"items":[["b",2],["c",3]]}]}"#;
+        let opts = json_path_options("data.1.items");
+        let table = parse_string(input, InputFormat::Json, false, &opts).unwrap();
+
+        assert_eq!(table.headers, None);
+        assert_eq!(table.rows, vec![vec!["b", "2"], vec!["c", "3"]]);
+    }
+
+    #[test]
+    fn json_path_must_resolve_to_an_array() {
+        let input = r#"{"data":{"items":[],"total":0}}"#;
+        for path in ["data.total", "data", "data.missing", "data.items.0", "data.total.x"] {
+            let result = parse_string(input, InputFormat::Json, false, &json_path_options(path));
+            assert!(result.is_err(), "{} should not resolve", path);
+        }
+        let err = parse_string(input, InputFormat::Json, false, &json_path_options("data.total"))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "--json-path: 'data.total' is not an array");
+    }
+
     #[test]
     fn json_null_values() {
         let input = r#"[{"name":"Alice","age":null},{"name":"Bob","age":25}]"#;
-        let table = parse_string(input, InputFormat::Json, false).unwrap();
+        let table = parse_string(
+            input,
+            InputFormat::Json,
+            false,
+            &ParseOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(table.rows[0], vec!["Alice", ""]);
         assert_eq!(table.rows[1], vec!["Bob", "25"]);
@@ -292,7 +1042,13 @@ mod tests {
     #[test]
     fn json_objects_with_different_keys() {
         let input = r#"[{"a":1,"b":2},{"b":3,"c":4}]"#;
-        let table = parse_string(input, InputFormat::Json, false).unwrap();
+        let table = parse_string(
+            input,
+            InputFormat::Json,
+            false,
+            &ParseOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(
             table.headers,
@@ -301,4 +1057,128 @@ mod tests {
         assert_eq!(table.rows[0], vec!["1", "2", ""]);
         assert_eq!(table.rows[1], vec!["", "3", "4"]);
     }
+
+    #[test]
+    fn nested_object_becomes_key_value_rows() {
+        let table = parse_nested(r#"{"city":"Paris","zip":75001,"tags":["a"]}"#).unwrap();
+
+        assert_eq!(
+            table.headers,
+            Some(vec!["key".to_string(), "value".to_string()])
+        );
+        assert_eq!(table.rows[1], vec!["zip".to_string(), "75001".to_string()]);
+        assert_eq!(table.rows[2], vec!["tags".to_string(), r#"["a"]"#.to_string()]);
+    }
+
+    #[test]
+    fn nested_arrays_and_scalars() {
+        let objects = parse_nested(r#"[{"a":1},{"b":2}]"#).unwrap();
+        assert_eq!(objects.headers, Some(vec!["a".to_string(), "b".to_string()]));
+
+        let values = parse_nested("[1, \"two\", null]").unwrap();
+        assert_eq!(values.headers, None);
+        assert_eq!(values.rows, vec![vec!["1"], vec!["two"], vec![""]]);
+
+        assert!(parse_nested("plain text").is_none());
+        assert!(parse_nested("42").is_none());
+    }
+
+    // --- Command tests ---
+
+    #[test]
+    fn command_output_is_parsed() {
+        let table = parse_command(
+            "printf 'name,age\\nAlice,30\\n'",
+            InputFormat::Csv,
+            true,
+            &ParseOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            table.headers,
+            Some(vec!["name".to_string(), "age".to_string()])
+        );
+        assert_eq!(table.rows, vec![vec!["Alice", "30"]]);
+
+        let table = parse_command(
+            "echo '[[1,2]]'",
+            InputFormat::Json,
+            false,
+            &ParseOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(table.rows, vec![vec!["1", "2"]]);
+    }
+
+    #[test]
+    fn failing_command_reports_stderr() {
+        let err = parse_command(
+            "echo oops >&2; exit 3",
+            InputFormat::Csv,
+            true,
+            &ParseOptions::default(),
+        )
+        .unwrap_err();
+        let message = err.to_string();
+
+        assert!(message.contains("oops"), "unexpected error: {}", message);
+    }
+
+    // --- Multi-file tests ---
+
+    fn table(headers: &[&str], rows: &[&[&str]]) -> Table {
+        Table {
+            headers: Some(headers.iter().map(|h| h.to_string()).collect()),
+            rows: rows
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn union_merges_differing_headers() {
+        let people = table(&["name", "age"], &[&["Alice", "30"]]);
+        let places = table(&["name", "city"], &[&["Bob", "Paris"], &["Carol", "Oslo"]]);
+
+        let merged = union_tables(vec![people, places]);
+
+        assert_eq!(
+            merged.headers,
+            Some(vec!["name".to_string(), "age".to_string(), "city".to_string()])
+        );
+        assert_eq!(merged.rows[0], vec!["Alice", "30", ""]);
+        assert_eq!(merged.rows[1], vec!["Bob", "", "Paris"]);
+        assert_eq!(merged.rows[2], vec!["Carol", "", "Oslo"]);
+    }
+
+    #[test]
+    fn union_of_headerless_tables_concatenates() {
+        let a = Table {
+            headers: None,
+            rows: vec![vec!["1".to_string()]],
+        };
+        let b = Table {
+            headers: None,
+            rows: vec![vec!["2".to_string(), "3".to_string()]],
+        };
+
+        let merged = union_tables(vec![a, b]);
+
+        assert_eq!(merged.headers, None);
+        assert_eq!(merged.rows, vec![vec!["1"], vec!["2", "3"]]);
+    }
+
+    #[test]
+    fn concat_requires_matching_headers() {
+        let a = table(&["name", "age"], &[&["Alice", "30"]]);
+        let b = table(&["name", "age"], &[&["Bob", "25"]]);
+        let merged = concat_tables(vec![a, b]).unwrap();
+        assert_eq!(merged.rows.len(), 2);
+
+        let a = table(&["name", "age"], &[&["Alice", "30"]]);
+        let c = table(&["name", "city"], &[&["Bob", "Paris"]]);
+        assert!(concat_tables(vec![a, c]).is_err());
+    }
 }
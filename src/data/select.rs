@@ -0,0 +1,262 @@
+//! The filter, sort and select pipeline, shared by the window and the
+//! headless paths. Nothing here depends on iced, so a selection can be
+//! resolved against a table without any UI state.
+
+use std::cmp::Ordering;
+
+use super::{compare_cells, fuzzy, CaseFold, FilterMode, SortKey, Table};
+
+/// What to narrow a table down to and which of the remaining rows to pick.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SelectionConfig<'a> {
+    /// The filter text. Empty keeps every row.
+    pub query: &'a str,
+    /// Actual indices of the only rows to filter, in table order (`--scope`).
+    /// Every row when unset.
+    pub scope: Option<&'a [usize]>,
+    pub filter_mode: FilterMode,
+    pub case_fold: CaseFold,
+    /// Fuzzy scoring weight per actual column. Missing entries weigh 1 and
+    /// a weight of 0 or less leaves the column out of fuzzy matching.
+    pub column_weights: &'a [f64],
+    /// Whether each actual column sorts numerically. Missing entries are
+    /// worked out from the table.
+    pub numeric_columns: &'a [bool],
+    /// `--sort` keys, the first deciding and each later one breaking ties.
+    pub sort_keys: &'a [SortKey],
+    /// Position among the matching rows to select, clamped to the last.
+    pub select: usize,
+}
+
+/// The rows a [`SelectionConfig`] leaves, in display order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SelectionResult {
+    /// Actual indices of the matching rows.
+    pub rows: Vec<usize>,
+    /// The fuzzy score of each of `rows`; empty unless fuzzy filtering.
+    pub scores: Vec<f64>,
+    /// Position in `rows` of the selected row. `None` when nothing matched.
+    pub selected: Option<usize>,
+}
+
+impl SelectionResult {
+    /// Actual index of the selected row.
+    pub fn selected_row(&self) -> Option<usize> {
+        self.rows.get(self.selected?).copied()
+    }
+}
+
+/// Filter and sort `table`, then select a row of what is left.
+pub fn resolve(table: &Table, config: &SelectionConfig) -> SelectionResult {
+    let (rows, scores) = filter_rows(table, config);
+    let selected = rows
+        .len()
+        .checked_sub(1)
+        .map(|last| config.select.min(last));
+    SelectionResult {
+        rows,
+        scores,
+        selected,
+    }
+}
+
+/// The actual indices of the rows matching the query, sorted by the sort
+/// keys, with their fuzzy scores when fuzzy filtering. Fuzzy matches come
+/// best first before sorting; rows the sort keys consider equal keep that
+/// order.
+pub fn filter_rows(table: &Table, config: &SelectionConfig) -> (Vec<usize>, Vec<f64>) {
+    let scored = !config.query.is_empty() && config.filter_mode == FilterMode::Fuzzy;
+    let mut entries = if config.query.is_empty() {
+        candidates(table, config).map(|(idx, _)| (idx, 0.0)).collect()
+    } else if scored {
+        fuzzy_rows(table, config)
+    } else {
+        let fold = config.case_fold;
+        let query = fold.fold(config.query);
+        candidates(table, config)
+            .filter(|(_, row)| {
+                row.iter()
+                    .any(|cell| config.filter_mode.matches(cell, &query, fold))
+            })
+            .map(|(idx, _)| (idx, 0.0))
+            .collect()
+    };
+    sort_rows(table, config, &mut entries);
+
+    let rows = entries.iter().map(|&(idx, _)| idx).collect();
+    let scores = if scored {
+        entries.iter().map(|&(_, score)| score).collect()
+    } else {
+        Vec::new()
+    };
+    (rows, scores)
+}
+
+/// The rows the query is matched against, with their actual indices: those
+/// in the scope, or all of them.
+fn candidates<'t>(
+    table: &'t Table,
+    config: &SelectionConfig<'t>,
+) -> Box<dyn Iterator<Item = (usize, &'t Vec<String>)> + 't> {
+    match config.scope {
+        Some(scope) => Box::new(
+            scope
+                .iter()
+                .filter_map(move |&idx| table.rows.get(idx).map(|row| (idx, row))),
+        ),
+        None => Box::new(table.rows.iter().enumerate()),
+    }
+}
+
+/// Rows with a fuzzy match in any weighted column, scored by their best
+/// weighted cell, best first and in table order among equal scores.
+fn fuzzy_rows(table: &Table, config: &SelectionConfig) -> Vec<(usize, f64)> {
+    let fold = config.case_fold;
+    let query = fold.fold(config.query);
+    let mut scored: Vec<(usize, f64)> = candidates(table, config)
+        .filter_map(|(idx, row)| {
+            row.iter()
+                .enumerate()
+                .filter_map(|(col, cell)| {
+                    let weight = config.column_weights.get(col).copied().unwrap_or(1.0);
+                    if weight <= 0.0 {
+                        return None;
+                    }
+                    fuzzy::score(&fold.fold(cell), &query).map(|score| score as f64 * weight)
+                })
+                .max_by(f64::total_cmp)
+                .map(|score| (idx, score))
+        })
+        .collect();
+
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    scored
+}
+
+/// Order rows by the sort keys, each later key breaking ties in the ones
+/// before it. The sort is stable, so equal rows keep their order.
+fn sort_rows(table: &Table, config: &SelectionConfig, entries: &mut [(usize, f64)]) {
+    if config.sort_keys.is_empty() {
+        return;
+    }
+
+    let numeric: Vec<bool> = config
+        .sort_keys
+        .iter()
+        .map(|key| {
+            config
+                .numeric_columns
+                .get(key.col)
+                .copied()
+                .unwrap_or_else(|| table.column_is_numeric(key.col))
+        })
+        .collect();
+    let compare = |a: usize, b: usize| {
+        config
+            .sort_keys
+            .iter()
+            .zip(&numeric)
+            .fold(Ordering::Equal, |order, (key, &numeric)| {
+                order.then_with(|| {
+                    let cell = |row| table.cell(row, key.col).unwrap_or_default();
+                    let order = compare_cells(cell(a), cell(b), numeric);
+                    if key.descending {
+                        order.reverse()
+                    } else {
+                        order
+                    }
+                })
+            })
+    };
+    entries.sort_by(|a, b| compare(a.0, b.0));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn table() -> Table {
+        let rows = [
+            ["carol", "London", "41"],
+            ["alice", "Paris", "30"],
+            ["bob", "London", "25"],
+            ["dave", "Lyon", "30"],
+        ];
+        Table {
+            headers: Some(vec!["name".into(), "city".into(), "age".into()]),
+            rows: rows
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn resolve_filters_sorts_and_selects_the_first() {
+        let sort_keys = [SortKey {
+            col: 2,
+            descending: false,
+        }];
+        let config = SelectionConfig {
+            query: "lon",
+            sort_keys: &sort_keys,
+            ..SelectionConfig::default()
+        };
+        let result = resolve(&table(), &config);
+        assert_eq!(result.rows, vec![2, 0]);
+        assert_eq!(result.scores, Vec::<f64>::new());
+        assert_eq!(result.selected_row(), Some(2));
+    }
+
+    #[test]
+    fn resolve_clamps_the_selection_and_reports_no_match() {
+        let config = SelectionConfig {
+            select: 9,
+            ..SelectionConfig::default()
+        };
+        assert_eq!(resolve(&table(), &config).selected, Some(3));
+
+        let config = SelectionConfig {
+            query: "nowhere",
+            ..SelectionConfig::default()
+        };
+        let result = resolve(&table(), &config);
+        assert_eq!(result.rows, Vec::<usize>::new());
+        assert_eq!(result.selected_row(), None);
+    }
+
+    #[test]
+    fn resolve_ranks_fuzzy_matches_best_first() {
+        let config = SelectionConfig {
+            query: "ly",
+            filter_mode: FilterMode::Fuzzy,
+            ..SelectionConfig::default()
+        };
+        let result = resolve(&table(), &config);
+        assert_eq!(result.selected_row(), Some(3));
+        assert_eq!(result.rows.len(), result.scores.len());
+    }
+
+    #[test]
+    fn queries_only_match_within_the_scope() {
+        let config = SelectionConfig {
+            query: "a",
+            scope: Some(&[0, 2, 3]),
+            ..SelectionConfig::default()
+        };
+        // alice (row 1) matches but is out of scope
+        assert_eq!(resolve(&table(), &config).rows, vec![0, 3]);
+    }
+
+    #[test]
+    fn zero_weight_columns_are_left_out_of_fuzzy_matching() {
+        let config = SelectionConfig {
+            query: "ly",
+            filter_mode: FilterMode::Fuzzy,
+            column_weights: &[1.0, 0.0],
+            ..SelectionConfig::default()
+        };
+        assert_eq!(resolve(&table(), &config).rows, Vec::<usize>::new());
+    }
+}
@@ -0,0 +1,191 @@
+use anyhow::{anyhow, Result};
+
+use super::Table;
+
+/// A parsed `--template`: literal text with `{0}` (column index) or `{name}`
+/// (header name) placeholders for a row's fields. `{{` and `}}` stand for
+/// literal braces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Index(usize),
+    Name(String),
+}
+
+/// Render `template` for one row. A placeholder naming no field of the row
+/// is an error.
+pub fn render(template: &str, row: &[String], headers: Option<&[String]>) -> Result<String> {
+    Template::parse(template)?.fill(row, headers, false)
+}
+
+impl Template {
+    pub fn parse(template: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some('{') | None => {
+                                return Err(anyhow!("unclosed placeholder {{{name}"));
+                            }
+                            Some(c) => name.push(c),
+                        }
+                    }
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(match name.parse() {
+                        Ok(idx) => Part::Index(idx),
+                        Err(_) => Part::Name(name),
+                    });
+                }
+                '}' => return Err(anyhow!("unmatched }} (write }}}} for a literal brace)")),
+                c => text.push(c),
+            }
+        }
+
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(Template { parts })
+    }
+
+    /// The placeholders naming no column of `table`, as written.
+    pub fn missing(&self, table: &Table) -> Vec<String> {
+        let headers = table.headers.as_deref();
+        self.placeholders()
+            .filter(|part| match part {
+                Part::Index(idx) => *idx >= table.width(),
+                _ => column(part, headers).is_none(),
+            })
+            .map(Part::to_string)
+            .collect()
+    }
+
+    /// Fill in the placeholders from a row's fields. A placeholder naming no
+    /// field is an error, or left empty with `allow_missing`.
+    pub fn fill(
+        &self,
+        row: &[String],
+        headers: Option<&[String]>,
+        allow_missing: bool,
+    ) -> Result<String> {
+        let mut out = String::new();
+        for part in &self.parts {
+            let value = match part {
+                Part::Text(text) => Some(text.as_str()),
+                field => column(field, headers).and_then(|col| row.get(col).map(String::as_str)),
+            };
+            match value {
+                Some(value) => out.push_str(value),
+                None if allow_missing => {}
+                None => return Err(anyhow!("template placeholder {} names no field", part)),
+            }
+        }
+        Ok(out)
+    }
+
+    fn placeholders(&self) -> impl Iterator<Item = &Part> {
+        self.parts
+            .iter()
+            .filter(|part| !matches!(part, Part::Text(_)))
+    }
+}
+
+impl std::fmt::Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::Text(text) => write!(f, "{text}"),
+            Part::Index(idx) => write!(f, "{{{idx}}}"),
+            Part::Name(name) => write!(f, "{{{name}}}"),
+        }
+    }
+}
+
+/// The column a placeholder refers to. Header names need headers to be
+/// found in; indices are taken as they are.
+fn column(part: &Part, headers: Option<&[String]>) -> Option<usize> {
+    match part {
+        Part::Index(idx) => Some(*idx),
+        Part::Name(name) => headers?.iter().position(|h| h == name),
+        Part::Text(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn placeholders_by_index_and_header() {
+        let headers = strings(&["name", "age"]);
+        let row = strings(&["Alice", "30"]);
+        assert_eq!(
+            render("{name} is {1} ({0})", &row, Some(&headers)).unwrap(),
+            "Alice is 30 (Alice)"
+        );
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        let headers = strings(&["name", "age"]);
+        let row = strings(&["Alice", "30"]);
+        assert_eq!(
+            render("{{\"{name}\": {{{1}}}}}", &row, Some(&headers)).unwrap(),
+            "{\"Alice\": {30}}"
+        );
+    }
+
+    #[test]
+    fn missing_placeholders_are_errors_unless_allowed() {
+        let headers = strings(&["name", "age"]);
+        let row = strings(&["Bob"]);
+        assert!(render("{city}", &row, Some(&headers)).is_err());
+        assert!(render("{age}", &row, Some(&headers)).is_err());
+        assert!(render("{name}", &row, None).is_err());
+
+        let template = Template::parse("[{city}|{age}|{0}]").unwrap();
+        assert_eq!(template.fill(&row, Some(&headers), true).unwrap(), "[||Bob]");
+    }
+
+    #[test]
+    fn missing_lists_placeholders_the_table_lacks() {
+        let table = Table::from_records(
+            Some(strings(&["name", "age"])),
+            vec![vec!["Alice", "30"], vec!["Bob"]],
+        );
+        let template = Template::parse("{city} {2} {age} {1}").unwrap();
+        assert_eq!(template.missing(&table), vec!["{city}", "{2}"]);
+    }
+
+    #[test]
+    fn unbalanced_braces_are_errors() {
+        assert!(Template::parse("{name").is_err());
+        assert!(Template::parse("{na{me}").is_err());
+        assert!(Template::parse("name}").is_err());
+    }
+}
@@ -1,43 +1,77 @@
 use std::path::PathBuf;
 use std::sync::Mutex;
+use std::time::Duration;
 
 use anyhow::anyhow;
-use clap::Parser;
+use clap::{ArgGroup, Parser};
 use once_cell::sync::{Lazy, OnceCell};
 use tracing::info;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 
+use app::clipboard::Selection;
 use app::style::Theme;
-use data::{InputFormat, OutputFormat, SelectionMode, Table};
+use data::template::Template;
+use data::{
+    output, Aggregate, CaseFold, Decode, FilterMode, InputFormat, InputSource, ModeFormats,
+    MultiEnter, OutputFormat, OutputOptions, ParseOptions, RowSlice, SelectionMode, Table,
+};
 use iced_core::Length;
 
 pub mod app;
 pub mod config;
 pub mod data;
 
-pub static THEME_PATH: Lazy<Mutex<PathBuf>> = Lazy::new(|| {
-    Mutex::new(
-        dirs::config_dir()
-            .ok_or_else(|| anyhow!("Theme config not found"))
-            .map(|path| path.join("tabsel").join("theme.scss"))
-            .unwrap(),
-    )
+/// Theme files, applied in order on top of each other.
+pub static THEME_PATHS: Lazy<Mutex<Vec<PathBuf>>> = Lazy::new(|| {
+    Mutex::new(vec![dirs::config_dir()
+        .ok_or_else(|| anyhow!("Theme config not found"))
+        .map(|path| path.join("tabsel").join("theme.scss"))
+        .unwrap()])
 });
 
 static THEME_SCALE: OnceCell<f32> = OnceCell::new();
 
+pub static SCREEN_SIZE: Lazy<(f32, f32)> = Lazy::new(get_screen_size);
+
 pub static THEME: Lazy<Theme> = Lazy::new(Theme::load);
 
 #[derive(Parser)]
 #[command(name = "tabsel")]
+#[command(group(ArgGroup::new("directive").args(["query", "select"]).multiple(true)))]
 struct Cli {
+    #[arg(help = "Files to read instead of stdin. Their rows are concatenated")]
+    files: Vec<PathBuf>,
+
+    #[arg(
+        long = "union",
+        default_value = "false",
+        help = "Merge input files with differing headers by the union of their columns, leaving missing cells empty"
+    )]
+    union: bool,
+
+    #[arg(
+        long = "exec",
+        short = 'e',
+        conflicts_with = "files",
+        help = "Run this shell command and read the table from its output instead of stdin"
+    )]
+    exec: Option<String>,
+
+    #[arg(
+        long = "null-input",
+        default_value = "false",
+        conflicts_with_all = ["files", "exec", "no_filter"],
+        help = "Experimental: read nothing and start with an empty table, typing rows into the filter bar"
+    )]
+    null_input: bool,
+
     #[arg(
         long = "theme",
         short = 't',
-        help = "Path to an alternate tabsel theme file"
+        help = "Path to an alternate tabsel theme file. Repeatable: later files override what they set in earlier ones"
     )]
-    theme: Option<PathBuf>,
+    theme: Vec<PathBuf>,
 
     #[arg(long = "scale", short = 's', help = "Change the scale of tabsel theme")]
     scale: Option<f32>,
@@ -46,10 +80,55 @@ struct Cli {
         long = "input-format",
         short = 'i',
         default_value = "csv",
-        help = "Input format: csv or json"
+        help = "Input format: csv, tsv, json, ndjson (alias jsonl: one JSON object per line), or delimited (see --sep)"
     )]
     format: String,
 
+    #[arg(
+        long = "sep",
+        help = "Split lines on this literal string, e.g. '::' or ' | '. Implies --input-format delimited"
+    )]
+    sep: Option<String>,
+
+    #[arg(
+        long = "any-delimiter",
+        value_name = "CHARS",
+        conflicts_with = "sep",
+        help = "Split lines on any one of these characters, e.g. ',;\\t' (\\t is a tab, \\\\ a backslash)"
+    )]
+    any_delimiter: Option<String>,
+
+    #[arg(
+        long = "collapse-delimiters",
+        default_value = "false",
+        requires = "any_delimiter",
+        help = "With --any-delimiter, treat a run of delimiters as one and ignore them at line ends"
+    )]
+    collapse_delimiters: bool,
+
+    #[arg(
+        long = "encoding",
+        value_name = "NAME",
+        default_value = "utf-8",
+        help = "Text encoding of the input, e.g. latin1 or windows-1252; undecodable bytes are replaced with a warning"
+    )]
+    encoding: String,
+
+    #[arg(
+        long = "json-path",
+        visible_alias = "input-json-path",
+        value_name = "PATH",
+        help = "Read the rows from the array at this dotted path in the JSON input, e.g. 'results' or 'data.0.items' (numbers index arrays)"
+    )]
+    json_path: Option<String>,
+
+    #[arg(
+        long = "flatten-json",
+        default_value = "false",
+        help = "Expand nested objects in JSON input into columns named by dotted paths, e.g. addr.city, instead of one cell of raw JSON"
+    )]
+    flatten_json: bool,
+
     #[arg(
         long = "header",
         default_value = "true",
@@ -57,6 +136,13 @@ struct Cli {
     )]
     header: bool,
 
+    #[arg(
+        long = "no-header-display",
+        default_value = "false",
+        help = "Don't draw the header row (Ctrl+H toggles it). Headers are still used for output"
+    )]
+    no_header_display: bool,
+
     #[arg(
         long = "mode",
         short = 'm',
@@ -65,6 +151,89 @@ struct Cli {
     )]
     mode: Vec<String>,
 
+    #[arg(
+        long = "initial-mode",
+        value_name = "MODE",
+        help = "Start in this selection mode instead of the first --mode, keeping the --mode order for cycling. It must be one of the --mode modes"
+    )]
+    initial_mode: Option<String>,
+
+    #[arg(
+        long = "multi",
+        default_value = "false",
+        help = "Let Tab check several rows; all checked rows are output on confirm, one per line"
+    )]
+    multi: bool,
+
+    #[arg(
+        long = "multi-enter",
+        default_value = "confirm-all",
+        requires = "multi",
+        help = "What Enter does with --multi: confirm-all (output the checked rows, or the cursor row) or toggle-row (check the row like Tab; Ctrl+Enter confirms)"
+    )]
+    multi_enter: String,
+
+    #[arg(
+        long = "decode",
+        value_name = "ENCODING",
+        help = "In cell mode, decode the confirmed cell from base64 or hex and write the raw bytes to stdout, without a trailing newline. Cells that don't decode are printed as text"
+    )]
+    decode: Option<String>,
+
+    #[arg(
+        long = "loop",
+        default_value = "false",
+        conflicts_with = "null_input",
+        help = "Print each confirmed selection on its own line and stay open for another, until Escape"
+    )]
+    loop_mode: bool,
+
+    #[arg(
+        long = "loop-remove",
+        default_value = "false",
+        requires = "loop_mode",
+        help = "With --loop, take picked rows out of the list"
+    )]
+    loop_remove: bool,
+
+    #[arg(
+        long = "quick-keys",
+        default_value = "false",
+        help = "Let 1-9 pick and confirm the Nth visible row (Ctrl+1-9 while the filter bar takes typed digits)"
+    )]
+    quick_keys: bool,
+
+    #[arg(
+        long = "strict",
+        default_value = "false",
+        help = "Exit with an error if the input has ragged rows, duplicate or empty headers, or NUL bytes"
+    )]
+    strict: bool,
+
+    #[arg(
+        long = "allow-empty",
+        default_value = "false",
+        help = "Open blank input as an empty table instead of failing, and exit 0 without output when confirming it"
+    )]
+    allow_empty: bool,
+
+    #[arg(
+        long = "head",
+        conflicts_with = "tail",
+        help = "Only show the first N rows"
+    )]
+    head: Option<usize>,
+
+    #[arg(long = "tail", help = "Only show the last N rows")]
+    tail: Option<usize>,
+
+    #[arg(
+        long = "strip-quotes",
+        default_value = "false",
+        help = "Remove one layer of matching single or double quotes left around headers and cells after parsing"
+    )]
+    strip_quotes: bool,
+
     #[arg(
         long = "hidden-column",
         short = 'H',
@@ -72,6 +241,78 @@ struct Cli {
     )]
     hidden_column: Vec<String>,
 
+    #[arg(
+        long = "pin",
+        value_name = "VALUE",
+        help = "Keep rows with a cell equal to VALUE at the top of the list, marked with a star. Repeatable."
+    )]
+    pin: Vec<String>,
+
+    #[arg(
+        long = "pin-sticky",
+        default_value = "false",
+        requires = "pin",
+        help = "Keep pinned rows listed even when the filter doesn't match them"
+    )]
+    pin_sticky: bool,
+
+    #[arg(
+        long = "limit-columns",
+        help = "Show at most this many columns (after --hidden-column); the last header notes how many more there are"
+    )]
+    limit_columns: Option<usize>,
+
+    #[arg(
+        long = "output-visible-only",
+        default_value = "false",
+        help = "Only include the displayed columns in row output"
+    )]
+    output_visible_only: bool,
+
+    #[arg(
+        long = "id-column",
+        help = "In row mode, output only this column's value for the selected row(s). Use a header name with --header, or a 0-based column number without."
+    )]
+    id_column: Option<String>,
+
+    #[arg(
+        long = "mru",
+        default_value = "false",
+        help = "Remember confirmed rows and list recently picked ones first next time"
+    )]
+    mru: bool,
+
+    #[arg(
+        long = "mru-key",
+        value_name = "COLUMN",
+        requires = "mru",
+        help = "The column identifying a row for --mru (default: the first). Use a header name with --header, or a 0-based column number without."
+    )]
+    mru_key: Option<String>,
+
+    #[arg(
+        long = "mru-file",
+        value_name = "PATH",
+        requires = "mru",
+        help = "Keep the --mru list in this file instead of $XDG_DATA_HOME/tabsel/mru.json, e.g. one per script"
+    )]
+    mru_file: Option<PathBuf>,
+
+    #[arg(
+        long = "template",
+        value_name = "FMT",
+        help = "In row mode, output this text with {0}, {1}... replaced by the row's fields by index, or {name} by header. {{ and }} are literal braces"
+    )]
+    template: Option<String>,
+
+    #[arg(
+        long = "template-allow-missing",
+        default_value = "false",
+        requires = "template",
+        help = "Leave --template placeholders that name no column empty instead of exiting with an error"
+    )]
+    template_allow_missing: bool,
+
     #[arg(
         long = "no-filter",
         default_value = "false",
@@ -79,13 +320,241 @@ struct Cli {
     )]
     no_filter: bool,
 
+    #[arg(
+        long = "no-focus",
+        default_value = "false",
+        help = "Don't focus the filter input on launch"
+    )]
+    no_focus: bool,
+
+    #[arg(
+        long = "filter-mode",
+        default_value = "substring",
+        help = "Filter matching: substring, prefix, word, or fuzzy. Ctrl+F cycles it at runtime"
+    )]
+    filter_mode: String,
+
+    #[arg(
+        long = "fold",
+        default_value = "simple",
+        help = "Case folding for filtering: simple (lowercasing) or full (Unicode folding, so ß matches ss)"
+    )]
+    fold: String,
+
+    #[arg(
+        long = "filter-weights",
+        help = "Fuzzy ranking weight per column, e.g. name=2,notes=0.5. Columns are header names, or 0-based numbers without --header. Unlisted columns weigh 1, 0 excludes a column"
+    )]
+    filter_weights: Option<String>,
+
+    #[arg(
+        long = "sort",
+        value_name = "KEYS",
+        help = "Sort the rows by these columns, e.g. name,age or name:asc,age:desc; later columns break ties in earlier ones. Numeric columns sort as numbers"
+    )]
+    sort: Option<String>,
+
+    #[arg(
+        long = "group-by",
+        value_name = "COLUMN",
+        help = "Collapse the table to one row per distinct value of this column, with a count of its rows"
+    )]
+    group_by: Option<String>,
+
+    #[arg(
+        long = "group-agg",
+        value_name = "AGG:COLUMN",
+        requires = "group_by",
+        help = "With --group-by, also aggregate a numeric column per group, e.g. sum:amount (sum, avg, min, or max). Repeat for more"
+    )]
+    group_agg: Vec<String>,
+
+    #[arg(
+        long = "query",
+        short = 'q',
+        help = "Start with this filter text"
+    )]
+    query: Option<String>,
+
+    #[arg(
+        long = "scope",
+        value_name = "QUERY",
+        help = "Only show rows matching QUERY, in the filter mode; the filter then narrows these (Ctrl+L clears the scope)"
+    )]
+    scope: Option<String>,
+
+    #[arg(
+        long = "select",
+        default_value = "0",
+        help = "Start with this row selected, counted from 0 among the rows matching --query"
+    )]
+    select: usize,
+
+    #[arg(
+        long = "list-columns",
+        default_value = "false",
+        help = "Print the column names (indices for headerless input) one per line, or as JSON with --output json, and exit without opening a window"
+    )]
+    list_columns: bool,
+
+    #[arg(
+        long = "dry-run",
+        default_value = "false",
+        help = "Don't open a window: describe the selection the other flags lead to on stderr and print its output"
+    )]
+    dry_run: bool,
+
+    #[arg(
+        long = "no-window",
+        default_value = "false",
+        requires = "directive",
+        conflicts_with_all = ["dry_run", "loop_mode", "null_input"],
+        help = "Don't open a window: print the output of the selection --query and --select lead to and exit (1 if nothing matches). Needs no display server"
+    )]
+    no_window: bool,
+
     #[arg(
         long = "output-format",
+        visible_alias = "output",
         short = 'o',
         default_value = "plain",
-        help = "Output format: plain, json, or csv"
+        help = "Output format: plain, json, csv, kv (header=value lines), shell (quoted arguments), env (NAME='value' assignments), or summary (a line like '2 rows selected: Alice, Bob'), or a per-mode list like row=csv,cell=plain. 'headers' prints the header row and 'json-rows' the filtered rows as one JSON array, both without opening a window"
     )]
     output_format: String,
+
+    #[arg(
+        long = "quote",
+        help = "Quote character for CSV input and output [default: \"]"
+    )]
+    quote: Option<char>,
+
+    #[arg(
+        long = "no-quoting",
+        default_value = "false",
+        help = "Treat quote characters in CSV input as literal text"
+    )]
+    no_quoting: bool,
+
+    #[arg(
+        long = "quote-all",
+        default_value = "false",
+        help = "Quote every field of CSV output, not just those containing separators, quotes or newlines"
+    )]
+    quote_all: bool,
+
+    #[arg(
+        long = "output-bom",
+        default_value = "false",
+        help = "Start the printed output with a UTF-8 byte order mark, for Excel on Windows"
+    )]
+    output_bom: bool,
+
+    #[arg(
+        long = "output-file",
+        value_name = "PATH",
+        help = "On confirm, write the output to this file instead of stdout, replacing what it held. --loop appends each selection after the first"
+    )]
+    output_file: Option<PathBuf>,
+
+    #[arg(
+        long = "tee",
+        default_value = "false",
+        requires = "output_file",
+        help = "With --output-file, also print the output to stdout"
+    )]
+    tee: bool,
+
+    #[arg(
+        long = "cell-context",
+        default_value = "false",
+        help = "In cell mode, output the whole row as a JSON object with \"_selected\" naming the chosen column"
+    )]
+    cell_context: bool,
+
+    #[arg(
+        long = "one-based",
+        default_value = "false",
+        help = "Count the row and column indices in the output, and in --index-fd, from 1 instead of 0"
+    )]
+    one_based: bool,
+
+    #[arg(
+        long = "multi-header",
+        default_value = "false",
+        help = "When several checked rows are output as CSV, start with the header record"
+    )]
+    multi_header: bool,
+
+    #[arg(
+        long = "plain-sep",
+        value_name = "SEP",
+        default_value = ",",
+        allow_hyphen_values = true,
+        help = "Join a row's fields (and header names) with this string in plain output; may be empty or several characters, e.g. '' or ' | '"
+    )]
+    plain_sep: String,
+
+    #[arg(
+        long = "summary-prefix",
+        value_name = "TEXT",
+        help = "Text before the colon of --output summary, e.g. 'Deleting {n} files'; {n} is the number of selected values"
+    )]
+    summary_prefix: Option<String>,
+
+    #[arg(
+        long = "footer-agg",
+        default_value = "sum",
+        help = "Aggregate in the footer row of numeric columns (shown with --show-footer in the theme): sum, avg, min, or max"
+    )]
+    footer_agg: String,
+
+    #[arg(
+        long = "timeout",
+        help = "Cancel (exit 1) if nothing is selected within this many seconds"
+    )]
+    timeout: Option<f64>,
+
+    #[arg(
+        long = "timeout-reset-on-input",
+        default_value = "false",
+        requires = "timeout",
+        help = "Restart the --timeout countdown on every key press"
+    )]
+    timeout_reset_on_input: bool,
+
+    #[arg(
+        long = "clipboard",
+        default_value = "false",
+        help = "Also copy the selection to the clipboard (Linux, needs wl-copy or xclip)"
+    )]
+    clipboard: bool,
+
+    #[arg(
+        long = "primary",
+        default_value = "false",
+        help = "Also copy the selection to the primary selection (Linux, needs wl-copy or xclip)"
+    )]
+    primary: bool,
+
+    #[arg(
+        long = "index-fd",
+        help = "On confirm, also write the selected row index (\"row col\" in cell mode, the column index in column mode) to this file descriptor"
+    )]
+    index_fd: Option<i32>,
+
+    #[arg(
+        long = "on-select",
+        help = "Run this command on confirm, with {} replaced by the output (appended if there is no {}). Split on whitespace, not run through a shell"
+    )]
+    on_select: Option<String>,
+
+    #[arg(
+        long = "on-select-detach",
+        default_value = "false",
+        requires = "on_select",
+        help = "Don't wait for the --on-select command to finish"
+    )]
+    on_select_detach: bool,
 }
 
 pub fn main() -> iced::Result {
@@ -106,13 +575,15 @@ pub fn main() -> iced::Result {
     info!("Starting tabsel");
     let cli = Cli::parse();
 
-    if let Some(theme_path) = cli.theme {
-        let path = theme_path.canonicalize();
-        if let Ok(path) = path {
-            *THEME_PATH.lock().unwrap() = path;
-        }
+    if !cli.theme.is_empty() {
+        let paths = cli
+            .theme
+            .into_iter()
+            .map(|path| path.canonicalize().unwrap_or(path))
+            .collect();
+        *THEME_PATHS.lock().unwrap() = paths;
 
-        info!("Using alternate theme : {:?}", THEME_PATH.lock().unwrap());
+        info!("Using alternate theme : {:?}", THEME_PATHS.lock().unwrap());
     }
 
     if let Some(scale) = cli.scale {
@@ -120,102 +591,473 @@ pub fn main() -> iced::Result {
         info!("Using scale value : {:?}", scale);
     }
 
-    let input_format = match cli.format.as_str() {
-        "json" => InputFormat::Json,
-        _ => InputFormat::Csv,
-    };
+    let delimiters = cli.any_delimiter.as_deref().map(|spec| {
+        data::parse::parse_delimiter_set(spec).unwrap_or_else(|err| {
+            eprintln!("Invalid --any-delimiter: {err}");
+            std::process::exit(1);
+        })
+    });
 
-    let table = data::parse::parse_stdin(input_format, cli.header).unwrap_or_else(|err| {
-        eprintln!("Error parsing input: {err}");
+    let encoding = data::parse::parse_encoding(&cli.encoding).unwrap_or_else(|err| {
+        eprintln!("Invalid --encoding: {err}");
         std::process::exit(1);
     });
 
-    if table.rows.is_empty() {
-        eprintln!("No data rows to display");
+    if cli.json_path.is_some() && cli.format != "json" {
+        eprintln!("--json-path needs JSON input: pass --input-format json");
         std::process::exit(1);
     }
 
-    info!(
-        "Parsed table: {} rows, {} columns",
-        table.rows.len(),
-        table.headers.as_ref().map_or_else(
-            || table.rows.first().map_or(0, |r| r.len()),
-            |h| h.len()
-        )
-    );
+    if cli.flatten_json && !["json", "ndjson", "jsonl"].contains(&cli.format.as_str()) {
+        eprintln!("--flatten-json needs JSON input: pass --input-format json or ndjson");
+        std::process::exit(1);
+    }
 
-    let available_modes: Vec<SelectionMode> = cli
-        .mode
-        .iter()
-        .map(|m| match m.as_str() {
-            "row" => SelectionMode::Row,
-            "column" => SelectionMode::Column,
-            "cell" => SelectionMode::Cell,
-            other => {
-                eprintln!("Unknown mode: {other}. Valid modes: row, column, cell");
+    let input_format = match (cli.format.as_str(), &cli.sep) {
+        ("json" | "ndjson" | "jsonl", _) if delimiters.is_some() => {
+            eprintln!("--any-delimiter can't be used with JSON input");
+            std::process::exit(1);
+        }
+        ("tsv", _) if delimiters.is_some() => {
+            eprintln!("--any-delimiter can't be used with TSV input");
+            std::process::exit(1);
+        }
+        _ if delimiters.is_some() => InputFormat::AnyDelimiter,
+        ("json" | "ndjson" | "jsonl", Some(_)) => {
+            eprintln!("--sep can't be used with JSON input");
+            std::process::exit(1);
+        }
+        ("tsv", Some(_)) => {
+            eprintln!("--sep can't be used with TSV input");
+            std::process::exit(1);
+        }
+        ("json", None) => InputFormat::Json,
+        ("ndjson" | "jsonl", None) => InputFormat::Ndjson,
+        ("tsv", None) => InputFormat::Tsv,
+        ("delimited", None) => {
+            eprintln!("Delimited input needs a separator: pass --sep");
+            std::process::exit(1);
+        }
+        (_, Some(sep)) if sep.is_empty() => {
+            eprintln!("--sep can't be empty");
+            std::process::exit(1);
+        }
+        (_, Some(_)) => InputFormat::Delimited,
+        _ => InputFormat::Csv,
+    };
+
+    let quote = match cli.quote {
+        None => b'"',
+        Some(c) if c.is_ascii() => c as u8,
+        Some(c) => {
+            eprintln!("Invalid quote character: {c}. It must be a single ASCII character");
+            std::process::exit(1);
+        }
+    };
+    let parse_options = ParseOptions {
+        quote,
+        quoting: !cli.no_quoting,
+        separator: cli.sep.clone().unwrap_or_else(|| ",".to_string()),
+        delimiters: delimiters.unwrap_or_default(),
+        collapse_delimiters: cli.collapse_delimiters,
+        encoding,
+        json_path: cli.json_path.clone(),
+        flatten_json: cli.flatten_json,
+        allow_empty: cli.allow_empty,
+    };
+    let output_options = OutputOptions {
+        quote,
+        quote_all: cli.quote_all,
+        bom: cli.output_bom,
+        cell_context: cli.cell_context,
+        multi_header: cli.multi_header,
+        summary_prefix: cli.summary_prefix.clone(),
+        plain_separator: cli.plain_sep.clone(),
+        one_based: cli.one_based,
+    };
+
+    let source = if cli.null_input {
+        InputSource::Null
+    } else if let Some(command) = cli.exec.clone() {
+        InputSource::Command(command)
+    } else if !cli.files.is_empty() {
+        InputSource::Files {
+            paths: cli.files.clone(),
+            union: cli.union,
+        }
+    } else {
+        InputSource::Stdin
+    };
+
+    let available_modes: Vec<SelectionMode> = cli.mode.iter().map(|m| parse_mode(m)).collect();
+    let initial_mode = match cli.initial_mode.as_deref().map(parse_mode) {
+        Some(mode) if !available_modes.contains(&mode) => {
+            eprintln!(
+                "--initial-mode {} is not one of the available modes: {}",
+                cli.initial_mode.as_deref().unwrap_or_default(),
+                cli.mode.join(", ")
+            );
+            std::process::exit(1);
+        }
+        Some(mode) => mode,
+        None => available_modes[0],
+    };
+
+    let output_formats = match cli.output_format.as_str() {
+        "headers" => {
+            let table = parse_input(input_format, cli.header, &parse_options, &source);
+            if table.headers.is_none() {
+                eprintln!("Input has no header row");
                 std::process::exit(1);
             }
-        })
-        .collect();
+            let headers = output::format_header(&table, OutputFormat::Plain, &output_options);
+            println!("{}", output::with_bom(headers, &output_options));
+            std::process::exit(0);
+        }
+        // Handled once the flags are built, like --dry-run
+        "json-rows" => ModeFormats::default(),
+        spec => ModeFormats::parse(spec).unwrap_or_else(|err| {
+            eprintln!("Invalid output format: {err}. Valid formats: plain, json, csv, kv, shell, env, summary, headers, json-rows, or a list like row=csv,cell=plain");
+            std::process::exit(1);
+        }),
+    };
+
+    if cli.list_columns {
+        let table = parse_input(input_format, cli.header, &parse_options, &source);
+        let columns = output::format_column_list(&table, output_formats.row);
+        println!("{}", output::with_bom(columns, &output_options));
+        std::process::exit(0);
+    }
 
-    let output_format = match cli.output_format.as_str() {
-        "json" => OutputFormat::Json,
-        "csv" => OutputFormat::Csv,
-        "plain" => OutputFormat::Plain,
+    let filter_enabled = !cli.no_filter;
+
+    let filter_mode = match cli.filter_mode.as_str() {
+        "substring" => FilterMode::Substring,
+        "prefix" => FilterMode::Prefix,
+        "word" => FilterMode::Word,
+        "fuzzy" => FilterMode::Fuzzy,
         other => {
-            eprintln!("Unknown output format: {other}. Valid formats: plain, json, csv");
+            eprintln!("Unknown filter mode: {other}. Valid modes: substring, prefix, word, fuzzy");
             std::process::exit(1);
         }
     };
 
-    // Resolve hidden columns to actual column indices
-    let num_cols = table
-        .headers
-        .as_ref()
-        .map_or_else(|| table.rows.first().map_or(0, |r| r.len()), |h| h.len());
+    let decode = cli.decode.as_deref().map(|decode| match decode {
+        "base64" => Decode::Base64,
+        "hex" => Decode::Hex,
+        other => {
+            eprintln!("Unknown --decode encoding: {other}. Valid encodings: base64, hex");
+            std::process::exit(1);
+        }
+    });
+
+    let multi_enter = match cli.multi_enter.as_str() {
+        "confirm-all" => MultiEnter::ConfirmAll,
+        "toggle-row" => MultiEnter::ToggleRow,
+        other => {
+            eprintln!(
+                "Unknown --multi-enter policy: {other}. Valid policies: confirm-all, toggle-row"
+            );
+            std::process::exit(1);
+        }
+    };
 
-    let hidden_columns: Vec<usize> = cli
-        .hidden_column
-        .iter()
-        .map(|spec| {
-            if let Some(headers) = &table.headers {
-                headers
-                    .iter()
-                    .position(|h| h == spec)
-                    .unwrap_or_else(|| {
-                        eprintln!("Unknown header name: {spec}. Available headers: {}", headers.join(", "));
-                        std::process::exit(1);
-                    })
-            } else {
-                spec.parse::<usize>().unwrap_or_else(|_| {
-                    eprintln!("Invalid column number: {spec}. Must be a 0-based integer when --header is false");
-                    std::process::exit(1);
-                })
-            }
+    let footer_agg = cli.footer_agg.as_str();
+    let footer_aggregate = Aggregate::from_name(footer_agg).unwrap_or_else(|| {
+        eprintln!("Unknown footer aggregate: {footer_agg}. Valid aggregates: sum, avg, min, max");
+        std::process::exit(1);
+    });
+
+    let timeout = cli.timeout.map(|secs| {
+        Duration::try_from_secs_f64(secs).unwrap_or_else(|_| {
+            eprintln!("Invalid timeout: {secs}. It must be a non-negative number of seconds");
+            std::process::exit(1);
         })
-        .collect();
+    });
 
-    for &col in &hidden_columns {
-        if col >= num_cols {
-            eprintln!("Column index {col} is out of range (table has {num_cols} columns)");
+    if let Some(fd) = cli.index_fd {
+        if let Err(err) = app::ipc::check_fd(fd) {
+            eprintln!("Invalid --index-fd: {err}");
             std::process::exit(1);
         }
     }
 
-    let filter_enabled = !cli.no_filter;
+    let case_fold = match cli.fold.as_str() {
+        "simple" => CaseFold::Simple,
+        "full" => CaseFold::Full,
+        other => {
+            eprintln!("Unknown case folding: {other}. Valid values: simple, full");
+            std::process::exit(1);
+        }
+    };
+
+    let template = cli.template.as_deref().map(|template| {
+        Template::parse(template).unwrap_or_else(|err| {
+            eprintln!("Invalid template: {err}");
+            std::process::exit(1);
+        })
+    });
+
+    let filter_weights = cli
+        .filter_weights
+        .as_deref()
+        .map(parse_filter_weights)
+        .unwrap_or_default();
+
+    let sort = cli.sort.as_deref().map(parse_sort_keys).unwrap_or_default();
+    let group_aggregates = cli.group_agg.iter().map(|value| parse_group_agg(value)).collect();
+
+    let mut copy_to = Vec::new();
+    if cli.clipboard {
+        copy_to.push(Selection::Clipboard);
+    }
+    if cli.primary {
+        copy_to.push(Selection::Primary);
+    }
+
+    let mru_path = if cli.mru {
+        let path = cli.mru_file.clone().or_else(app::mru::default_path);
+        if path.is_none() {
+            eprintln!("No data directory to keep the --mru list in; pass --mru-file");
+            std::process::exit(1);
+        }
+        path
+    } else {
+        None
+    };
+
+    let flags = app::TabselFlags {
+        input_format,
+        has_header: cli.header,
+        parse_options,
+        source,
+        available_modes,
+        initial_mode,
+        filter_enabled,
+        focus_filter: !cli.no_focus,
+        multi_select: cli.multi,
+        multi_enter,
+        decode,
+        quick_keys: cli.quick_keys,
+        pins: cli.pin,
+        pin_sticky: cli.pin_sticky,
+        loop_mode: cli.loop_mode,
+        loop_remove: cli.loop_remove,
+        filter_mode,
+        case_fold,
+        output_formats,
+        output_options,
+        hidden_columns: cli.hidden_column,
+        id_column: cli.id_column,
+        mru_path,
+        mru_key: cli.mru_key,
+        column_limit: cli.limit_columns,
+        output_visible_only: cli.output_visible_only,
+        filter_weights,
+        sort,
+        group_by: cli.group_by,
+        group_aggregates,
+        initial_query: cli.query.unwrap_or_default(),
+        scope: cli.scope,
+        initial_selection: cli.select,
+        copy_to,
+        on_select: cli.on_select,
+        on_select_detach: cli.on_select_detach,
+        show_header: !cli.no_header_display,
+        footer_aggregate,
+        timeout,
+        timeout_reset_on_input: cli.timeout_reset_on_input,
+        strict: cli.strict,
+        template,
+        template_allow_missing: cli.template_allow_missing,
+        index_fd: cli.index_fd,
+        output_file: cli.output_file,
+        tee: cli.tee,
+        row_slice: cli.head.map(RowSlice::Head).or(cli.tail.map(RowSlice::Tail)),
+        strip_quotes: cli.strip_quotes,
+    };
+
+    if cli.output_format == "json-rows" {
+        app::export_json_rows(flags);
+    }
+
+    if cli.dry_run {
+        app::dry_run(flags);
+    }
+
+    if cli.no_window {
+        app::no_window(flags);
+    }
 
     // Query screen dimensions for resolving percentage-based sizes
-    let screen_size = get_screen_size();
-    info!("Screen size: {:?}", screen_size);
+    info!("Screen size: {:?}", *SCREEN_SIZE);
+
+    // The table is parsed in the background, so open at the minimum bounds
+    // with a loading indicator and resize once the content is known.
+    app::run(flags, window_bounds())
+}
+
+/// Read and parse the input, exiting with an error message if that fails.
+fn parse_input(
+    format: InputFormat,
+    has_header: bool,
+    opts: &ParseOptions,
+    source: &InputSource,
+) -> Table {
+    let table = data::parse::parse_source(source, format, has_header, opts);
+    table.unwrap_or_else(|err| {
+        eprintln!("Error parsing input: {err}");
+        std::process::exit(1);
+    })
+}
+
+/// Read and parse the input like [`parse_input`], also keeping the decoded
+/// text of a single input stream so it can be parsed again at runtime.
+fn parse_input_keeping_raw(
+    format: InputFormat,
+    has_header: bool,
+    opts: &ParseOptions,
+    source: &InputSource,
+) -> (Table, Option<String>) {
+    let parsed = data::parse::parse_source_keeping_input(source, format, has_header, opts);
+    parsed.unwrap_or_else(|err| {
+        eprintln!("Error parsing input: {err}");
+        std::process::exit(1);
+    })
+}
+
+/// Resolve a column spec (a header name, or a 0-based index for headerless
+/// input) to an actual column index, or say why it names no column.
+fn resolve_column(table: &Table, spec: &str) -> Result<usize, String> {
+    let num_cols = table.width();
+
+    let col = if let Some(headers) = &table.headers {
+        headers.iter().position(|h| h == spec).ok_or_else(|| {
+            format!("Unknown header name: {spec}. Available headers: {}", headers.join(", "))
+        })?
+    } else {
+        spec.parse::<usize>().map_err(|_| {
+            format!("Invalid column number: {spec}. Must be a 0-based integer when --header is false")
+        })?
+    };
 
-    // Resolve min/max bounds to pixels
+    if col >= num_cols {
+        return Err(format!("Column index {col} is out of range (table has {num_cols} columns)"));
+    }
+
+    Ok(col)
+}
+
+/// Resolve `--hidden-column` specs to actual column indices.
+fn resolve_hidden_columns(table: &Table, specs: &[String]) -> Result<Vec<usize>, String> {
+    specs.iter().map(|spec| resolve_column(table, spec)).collect()
+}
+
+/// Parse a `--filter-weights` value like `name=2,notes=0.5` into
+/// `(column spec, weight)` pairs, exiting on malformed entries.
+fn parse_filter_weights(value: &str) -> Vec<(String, f64)> {
+    value
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let parsed = entry.rsplit_once('=').and_then(|(column, weight)| {
+                let weight = weight.trim().parse::<f64>().ok()?;
+                (weight.is_finite() && weight >= 0.0).then(|| (column.trim().to_string(), weight))
+            });
+            parsed.unwrap_or_else(|| {
+                eprintln!("Invalid filter weight: {entry}. Expected column=weight with a non-negative number");
+                std::process::exit(1);
+            })
+        })
+        .collect()
+}
+
+/// Parse a selection mode name, exiting on an unknown one.
+fn parse_mode(name: &str) -> SelectionMode {
+    match name {
+        "row" => SelectionMode::Row,
+        "column" => SelectionMode::Column,
+        "cell" => SelectionMode::Cell,
+        other => {
+            eprintln!("Unknown mode: {other}. Valid modes: row, column, cell");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Parse a `--sort` value like `name:asc,age:desc` into `(column spec,
+/// descending)` pairs, exiting on an unknown direction.
+fn parse_sort_keys(value: &str) -> Vec<(String, bool)> {
+    value
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let (column, direction) = match entry.rsplit_once(':') {
+                Some((column, direction)) => (column, Some(direction.trim())),
+                None => (entry, None),
+            };
+            let descending = match direction {
+                None | Some("asc") => false,
+                Some("desc") => true,
+                Some(other) => {
+                    eprintln!("Invalid sort direction: {other}. Expected asc or desc");
+                    std::process::exit(1);
+                }
+            };
+            (column.trim().to_string(), descending)
+        })
+        .collect()
+}
+
+/// Parse a `--group-agg` value like `sum:amount` into the aggregate and a
+/// column spec, exiting on an unknown aggregate or a missing column.
+fn parse_group_agg(value: &str) -> (Aggregate, String) {
+    let Some((name, column)) = value.split_once(':').filter(|(_, col)| !col.trim().is_empty())
+    else {
+        eprintln!("Invalid --group-agg: {value}. Expected AGG:COLUMN, e.g. sum:amount");
+        std::process::exit(1);
+    };
+    let aggregate = Aggregate::from_name(name.trim()).unwrap_or_else(|| {
+        eprintln!("Unknown aggregate: {name}. Valid aggregates: sum, avg, min, max");
+        std::process::exit(1);
+    });
+    (aggregate, column.trim().to_string())
+}
+
+/// Resolve `--filter-weights` pairs to a weight per actual column.
+fn resolve_filter_weights(table: &Table, weights: &[(String, f64)]) -> Result<Vec<f64>, String> {
+    let mut resolved = Vec::new();
+    for (spec, weight) in weights {
+        let col = resolve_column(table, spec)?;
+        if resolved.len() <= col {
+            resolved.resize(col + 1, 1.0);
+        }
+        resolved[col] = *weight;
+    }
+    Ok(resolved)
+}
+
+/// Resolve the theme's min/max window bounds to pixels against the screen:
+/// `(min_width, max_width, min_height, max_height)`. A maximum that resolves
+/// below its minimum (a small percentage on a small screen) is raised to it.
+fn window_bounds() -> (f32, f32, f32, f32) {
+    let screen_size = *SCREEN_SIZE;
     let min_w = THEME.min_width.resolve(screen_size.0);
-    let max_w = THEME.max_width.resolve(screen_size.0);
     let min_h = THEME.min_height.resolve(screen_size.1);
-    let max_h = THEME.max_height.resolve(screen_size.1);
+    (
+        min_w,
+        THEME.max_width.resolve(screen_size.0).max(min_w),
+        min_h,
+        THEME.max_height.resolve(screen_size.1).max(min_h),
+    )
+}
+
+/// Window size fitting the table content, clamped to the theme bounds.
+fn resolve_window_size(table: &Table, filter_enabled: bool, hidden_columns: &[usize]) -> (f32, f32) {
+    let (min_w, max_w, min_h, max_h) = window_bounds();
 
     // Calculate content-preferred size
-    let (content_w, content_h) = calculate_content_size(&table, filter_enabled, &hidden_columns);
+    let (content_w, content_h) = calculate_content_size(table, filter_enabled, hidden_columns);
     info!(
         "Content size: ({}, {}), bounds: w=[{}, {}], h=[{}, {}]",
         content_w, content_h, min_w, max_w, min_h, max_h
@@ -226,14 +1068,18 @@ pub fn main() -> iced::Result {
     let height = content_h.max(min_h).min(max_h);
     info!("Resolved window size: ({}, {})", width, height);
 
-    app::run(
-        table,
-        available_modes,
-        filter_enabled,
-        output_format,
-        hidden_columns,
-        (width, height),
-    )
+    (width, height)
+}
+
+/// Roughly how many rows fit in a window of the given height, estimated from
+/// the content size. A table that fits entirely returns its row count.
+fn rows_per_page(table: &Table, filter_enabled: bool, hidden_columns: &[usize], height: f32) -> usize {
+    let (_, content_h) = calculate_content_size(table, filter_enabled, hidden_columns);
+    if content_h <= height {
+        table.rows.len()
+    } else {
+        (table.rows.len() as f32 * height / content_h) as usize
+    }
 }
 
 fn get_screen_size() -> (f32, f32) {
@@ -248,7 +1094,7 @@ fn get_screen_size() -> (f32, f32) {
             for line in stdout.lines() {
                 // Active mode lines contain '*', e.g. "   1920x1080     60.00*+"
                 if line.contains('*') {
-                    let resolution = line.trim().split_whitespace().next()?;
+                    let resolution = line.split_whitespace().next()?;
                     let mut dims = resolution.split('x');
                     let w = dims.next()?.parse::<f32>().ok()?;
                     let h = dims.next()?.parse::<f32>().ok()?;
@@ -265,10 +1111,7 @@ fn calculate_content_size(table: &Table, filter_enabled: bool, hidden_columns: &
     let font_size = theme.font_size as f32;
     let char_width_estimate = font_size * 0.6;
 
-    let total_cols = table
-        .headers
-        .as_ref()
-        .map_or_else(|| table.rows.first().map_or(0, |r| r.len()), |h| h.len());
+    let total_cols = table.width();
     let visible_cols: Vec<usize> = (0..total_cols)
         .filter(|c| !hidden_columns.contains(c))
         .collect();
@@ -281,7 +1124,8 @@ fn calculate_content_size(table: &Table, filter_enabled: bool, hidden_columns: &
     //   - Button default internal padding (5px each side in iced)
     //   - Cell Container padding (title.padding)
     //   - Text height (title.font_size)
-    let button_padding_v: f32 = 5.0 + 5.0; // iced Button default padding top + bottom
+    // iced Button default padding top + bottom; plain rows have none
+    let button_padding_v: f32 = if theme.row_as_button { 5.0 + 5.0 } else { 0.0 };
     let row_padding = theme.app_container.rows.row.padding.top as f32
         + theme.app_container.rows.row.padding.bottom as f32;
     let title_padding = theme.app_container.rows.row.title.padding.top as f32
@@ -290,7 +1134,9 @@ fn calculate_content_size(table: &Table, filter_enabled: bool, hidden_columns: &
     // Line height ~1.5x font size: accounts for iced's 1.3x default line height
     // plus font metric variations (ascent/descent) and sub-pixel rounding
     let row_line_height = row_font_size * 1.5;
-    let row_height = row_line_height + title_padding + button_padding_v + row_padding;
+    let row_height = theme
+        .row_height
+        .unwrap_or(row_line_height + title_padding + button_padding_v + row_padding);
 
     // Header height: Container(Row(cells)) with header.padding
     let header_height = if table.headers.is_some() {
@@ -302,8 +1148,16 @@ fn calculate_content_size(table: &Table, filter_enabled: bool, hidden_columns: &
         0.0
     };
 
-    // Total rows area content (inside the scrollable)
-    let rows_content = (num_rows as f32 * row_height) + header_height;
+    // The footer sits below the scrollable and is styled like the header
+    let footer_height = if theme.show_footer {
+        let h = &theme.app_container.rows.header;
+        h.font_size as f32 * 1.5 + h.padding.top as f32 + h.padding.bottom as f32
+    } else {
+        0.0
+    };
+
+    // Total rows area content (inside the scrollable, plus the footer)
+    let rows_content = (num_rows as f32 * row_height) + header_height + footer_height;
 
     // Rows container padding
     let rows_padding_v = theme.app_container.rows.padding.top as f32
@@ -390,8 +1244,9 @@ fn calculate_content_size(table: &Table, filter_enabled: bool, hidden_columns: &
         + theme.app_container.rows.row.title.padding.right as f32;
     let total_cell_padding = cell_h_padding * num_cols as f32;
 
-    // Button adds default 5px horizontal padding on each side
-    let button_padding_h: f32 = 5.0 + 5.0;
+    // Button adds default 5px horizontal padding on each side; plain rows
+    // have none
+    let button_padding_h: f32 = if theme.row_as_button { 5.0 + 5.0 } else { 0.0 };
 
     let row_h_padding = theme.app_container.rows.row.padding.left as f32
         + theme.app_container.rows.row.padding.right as f32;
@@ -0,0 +1,128 @@
+//! Drive the `--no-window` pipeline through the binary: parse stdin, filter,
+//! select and format without a display.
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+const PEOPLE: &str = "name,age\nAlice,30\nBob,25\nCarol,35\n";
+
+fn tabsel(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tabsel"))
+        .args(args)
+        // Keep any user theme out of the way
+        .env("XDG_CONFIG_HOME", std::env::temp_dir().join("tabsel-headless-tests"))
+        .env_remove("WAYLAND_DISPLAY")
+        .env_remove("DISPLAY")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("tabsel runs");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+#[test]
+fn query_selects_the_first_match() {
+    let output = tabsel(&["--no-window", "--query", "o"], PEOPLE);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "Bob,25\n");
+}
+
+#[test]
+fn allow_empty_turns_blank_input_into_success() {
+    let output = tabsel(&["--no-window", "--select", "0"], "");
+    assert_eq!(output.status.code(), Some(1));
+
+    for format in ["csv", "json"] {
+        let args = ["--no-window", "--select", "0", "--allow-empty", "-i", format];
+        let output = tabsel(&args, "\n");
+        assert_eq!(output.status.code(), Some(0), "{}", format);
+        assert_eq!(stdout(&output), "");
+    }
+}
+
+#[test]
+fn select_picks_the_nth_row() {
+    let output = tabsel(&["--no-window", "--select", "2"], PEOPLE);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "Carol,35\n");
+}
+
+#[test]
+fn select_counts_among_the_query_matches() {
+    let output = tabsel(&["--no-window", "-q", "o", "--select", "1", "--output", "json"], PEOPLE);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "{\"name\":\"Carol\",\"age\":\"35\"}\n");
+}
+
+#[test]
+fn modes_and_formats_apply() {
+    let output = tabsel(&["--no-window", "-q", "bob", "-m", "cell", "--output", "csv"], PEOPLE);
+    assert_eq!(stdout(&output), "Bob\n");
+}
+
+#[test]
+fn no_match_exits_1_without_output() {
+    let output = tabsel(&["--no-window", "--query", "zzz"], PEOPLE);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn a_directive_is_required() {
+    let output = tabsel(&["--no-window"], PEOPLE);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn output_file_takes_the_place_of_stdout() {
+    let path = std::env::temp_dir().join(format!("tabsel-output-{}.txt", std::process::id()));
+    std::fs::write(&path, "left over from before\n").unwrap();
+    let file = path.to_str().unwrap();
+
+    let output = tabsel(&["--no-window", "-q", "carol", "--output-file", file], PEOPLE);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "Carol,35\n");
+
+    let output = tabsel(&["--no-window", "-q", "bob", "--output-file", file, "--tee"], PEOPLE);
+    assert_eq!(stdout(&output), "Bob,25\n");
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "Bob,25\n");
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn unwritable_output_file_fails() {
+    let path = std::env::temp_dir().join("tabsel-no-such-dir").join("out.txt");
+    let file = path.to_str().unwrap();
+    let output = tabsel(&["--no-window", "-q", "bob", "--output-file", file], PEOPLE);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(std::str::from_utf8(&output.stderr).unwrap().contains("Could not write --output-file"));
+}
+
+#[test]
+fn json_rows_go_to_the_output_file() {
+    let path = std::env::temp_dir().join(format!("tabsel-json-rows-{}.json", std::process::id()));
+    let file = path.to_str().unwrap();
+
+    let args = ["--output", "json-rows", "-q", "bob", "--output-file", file, "--tee"];
+    let output = tabsel(&args, PEOPLE);
+    assert_eq!(output.status.code(), Some(0));
+    let expected = "[{\"name\":\"Bob\",\"age\":\"25\"}]\n";
+    assert_eq!(stdout(&output), expected);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), expected);
+
+    std::fs::remove_file(path).unwrap();
+}